@@ -0,0 +1,81 @@
+//! Bridge command throughput — sustained `send_command` rate against an
+//! in-process echo server standing in for the factory process.
+//!
+//! Run with `cargo bench --bench bridge_throughput`. Criterion reports mean
+//! latency per iteration and throughput in commands per second.
+
+use std::io::{BufRead, BufReader, Write};
+use std::sync::Arc;
+use std::thread;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use neoxten_factory_lib::bridge::FactoryBridge;
+use neoxten_factory_lib::enforcer::FactoryCommand;
+
+const RUN_ID: &str = "bench";
+const BATCH: u64 = 1_000;
+const THREADS: u64 = 4;
+
+/// Wires a bridge to an echo thread: every NDJSON line written to the
+/// bridge's stdin is read back and written out again, mimicking the
+/// factory process's stdin/stdout loop.
+fn echo_bridge() -> FactoryBridge {
+    let (cmd_reader, cmd_writer) = std::io::pipe().expect("command pipe");
+    let (mut echo_reader, mut echo_writer) = std::io::pipe().expect("echo pipe");
+
+    thread::spawn(move || {
+        for line in BufReader::new(cmd_reader).lines() {
+            let Ok(line) = line else { break };
+            if writeln!(echo_writer, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+    thread::spawn(move || {
+        let _ = std::io::copy(&mut echo_reader, &mut std::io::sink());
+    });
+
+    FactoryBridge::with_writer(RUN_ID, Box::new(cmd_writer))
+}
+
+fn single_threaded(c: &mut Criterion) {
+    let bridge = echo_bridge();
+    let cmd = FactoryCommand::GetRunStatus.to_bridge_json();
+
+    let mut group = c.benchmark_group("bridge_send_command");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("single_thread", |b| {
+        b.iter(|| bridge.send_command(RUN_ID, cmd.clone()).unwrap())
+    });
+    group.finish();
+}
+
+fn concurrent(c: &mut Criterion) {
+    let bridge = Arc::new(echo_bridge());
+    let cmd = FactoryCommand::GetRunStatus.to_bridge_json();
+
+    let mut group = c.benchmark_group("bridge_send_command");
+    group.throughput(Throughput::Elements(BATCH * THREADS));
+    group.bench_function("4_threads", |b| {
+        b.iter(|| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    let bridge = Arc::clone(&bridge);
+                    let cmd = cmd.clone();
+                    thread::spawn(move || {
+                        for _ in 0..BATCH {
+                            bridge.send_command(RUN_ID, cmd.clone()).unwrap();
+                        }
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, single_threaded, concurrent);
+criterion_main!(benches);
@@ -1,3 +1,40 @@
+use std::fs;
+use std::path::Path;
+
+const SPEC_SCHEMA_PATH: &str = "schemas/spec-schema.json";
+/// The factory's Node.js entry point, relative to this crate.
+const FACTORY_ENTRY_PATH: &str = "../dist/cli/index.js";
+
 fn main() {
+    check_spec_schema();
+    check_factory_dist();
     tauri_build::build()
 }
+
+/// The app builds without the compiled factory, but every run would fail
+/// to spawn. Warn here, and record the result so the app can warn again
+/// at startup.
+fn check_factory_dist() {
+    println!("cargo:rerun-if-changed={}", FACTORY_ENTRY_PATH);
+    let exists = Path::new(FACTORY_ENTRY_PATH).is_file();
+    if !exists {
+        println!(
+            "cargo:warning={} is missing; run `npm run build` before starting runs",
+            FACTORY_ENTRY_PATH
+        );
+    }
+    println!("cargo:rustc-env=FACTORY_DIST_EXISTS={}", exists);
+}
+
+/// The spec schema is embedded in the binary with `include_str!`, so a
+/// malformed file has to fail the build rather than the first validation.
+fn check_spec_schema() {
+    println!("cargo:rerun-if-changed={}", SPEC_SCHEMA_PATH);
+    let raw = fs::read_to_string(SPEC_SCHEMA_PATH)
+        .unwrap_or_else(|e| panic!("reading {}: {}", SPEC_SCHEMA_PATH, e));
+    let schema: serde_json::Value = serde_json::from_str(&raw)
+        .unwrap_or_else(|e| panic!("{} is not valid JSON: {}", SPEC_SCHEMA_PATH, e));
+    if schema.get("$schema").and_then(|v| v.as_str()).is_none() {
+        panic!("{} has no \"$schema\" key", SPEC_SCHEMA_PATH);
+    }
+}
@@ -0,0 +1,242 @@
+//! Run archives — a run directory packed into a single file for audits.
+//!
+//! Entries are stored under `{run_id}/`, so an archive unpacks to the same
+//! layout as `ops/factory/runs/`.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::FactoryError;
+
+/// `{"format": "tar_gz"}`, `{"format": "zip"}` or
+/// `{"format": "encrypted_zip", "password": "..."}`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    #[default]
+    TarGz,
+    Zip,
+    /// AES-256 encrypted ZIP. The password is only ever used to encrypt.
+    EncryptedZip { password: String },
+}
+
+/// Hand-written so the password can't reach a log through `{:?}`.
+impl fmt::Debug for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveFormat::TarGz => f.write_str("TarGz"),
+            ArchiveFormat::Zip => f.write_str("Zip"),
+            ArchiveFormat::EncryptedZip { .. } => {
+                f.write_str("EncryptedZip { password: <redacted> }")
+            }
+        }
+    }
+}
+
+/// Pack `run_dir` into `output`, returning the archive's size in bytes.
+/// Only regular files are packed; symlinks are skipped, so nothing outside
+/// the run directory ends up in the archive. `output` must not be inside
+/// `run_dir`, and an encrypted ZIP needs a non-empty password.
+pub fn write_archive(
+    run_dir: &Path,
+    run_id: &str,
+    output: &Path,
+    format: &ArchiveFormat,
+) -> Result<u64, FactoryError> {
+    if matches!(format, ArchiveFormat::EncryptedZip { password } if password.is_empty()) {
+        return Err(FactoryError::InvalidArgument(
+            "an encrypted archive needs a non-empty password".into(),
+        ));
+    }
+    let io_err =
+        |e: io::Error| FactoryError::IoError(format!("writing {}: {}", output.display(), e));
+    if is_inside(output, run_dir).map_err(io_err)? {
+        return Err(FactoryError::InvalidArgument(format!(
+            "{} is inside the run directory being archived",
+            output.display()
+        )));
+    }
+    let file = File::create(output).map_err(io_err)?;
+
+    match format {
+        ArchiveFormat::TarGz => write_tar_gz(file, run_dir, run_id).map_err(io_err)?,
+        ArchiveFormat::Zip => write_zip(file, run_dir, run_id, None).map_err(io_err)?,
+        ArchiveFormat::EncryptedZip { password } => {
+            write_zip(file, run_dir, run_id, Some(password)).map_err(io_err)?
+        }
+    }
+
+    fs::metadata(output).map(|m| m.len()).map_err(io_err)
+}
+
+fn write_tar_gz(file: File, run_dir: &Path, run_id: &str) -> io::Result<()> {
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+    for path in files_under(run_dir)? {
+        let relative = path.strip_prefix(run_dir).map_err(io::Error::other)?;
+        tar.append_path_with_name(&path, Path::new(run_id).join(relative))?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
+
+fn write_zip(file: File, run_dir: &Path, run_id: &str, password: Option<&str>) -> io::Result<()> {
+    use zip::write::SimpleFileOptions;
+
+    let mut options =
+        SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    if let Some(password) = password {
+        options = options.with_aes_encryption(zip::AesMode::Aes256, password);
+    }
+
+    let mut zip = zip::ZipWriter::new(file);
+    for path in files_under(run_dir)? {
+        let relative = path.strip_prefix(run_dir).map_err(io::Error::other)?;
+        let name = Path::new(run_id).join(relative);
+        // ZIP entry names always use forward slashes.
+        let name = name.to_string_lossy().replace('\\', "/");
+        zip.start_file(name, options).map_err(io::Error::other)?;
+        io::copy(&mut File::open(&path)?, &mut zip)?;
+    }
+    zip.finish().map_err(io::Error::other)?;
+    Ok(())
+}
+
+/// Whether `output`, which need not exist yet, would be written below
+/// `dir`. Both are compared with symlinks resolved.
+fn is_inside(output: &Path, dir: &Path) -> io::Result<bool> {
+    let dir = fs::canonicalize(dir)?;
+    let parent = match output.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    Ok(fs::canonicalize(parent)?.starts_with(dir))
+}
+
+/// Every regular file below `dir`, sorted so archives are reproducible.
+fn files_under(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn run_dir(root: &Path) -> PathBuf {
+        let dir = root.join("run-1");
+        fs::create_dir_all(dir.join("artifacts")).unwrap();
+        fs::write(dir.join("manifest.json"), r#"{"runId": "run-1"}"#).unwrap();
+        fs::write(dir.join("artifacts").join("app.apk"), b"apk bytes").unwrap();
+        dir
+    }
+
+    #[test]
+    fn encrypted_zip_opens_only_with_the_password() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = run_dir(root.path());
+        let output = root.path().join("run-1.zip");
+        let format = ArchiveFormat::EncryptedZip {
+            password: "correct horse".into(),
+        };
+
+        write_archive(&dir, "run-1", &output, &format).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+        let mut contents = String::new();
+        archive
+            .by_name_decrypt("run-1/artifacts/app.apk", b"correct horse")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "apk bytes");
+        assert!(archive.by_name_decrypt("run-1/manifest.json", b"wrong").is_err());
+    }
+
+    #[test]
+    fn tar_gz_keeps_the_run_layout() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = run_dir(root.path());
+        let output = root.path().join("run-1.tar.gz");
+
+        write_archive(&dir, "run-1", &output, &ArchiveFormat::TarGz).unwrap();
+
+        let gz = flate2::read::GzDecoder::new(File::open(&output).unwrap());
+        let mut names: Vec<String> = tar::Archive::new(gz)
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().into_owned())
+            .filter(|name| !name.ends_with('/'))
+            .collect();
+        names.sort();
+        assert!(names.contains(&"run-1/manifest.json".to_string()));
+        assert!(names.contains(&"run-1/artifacts/app.apk".to_string()));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlinks_are_left_out_of_tar_archives() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = run_dir(root.path());
+        fs::write(root.path().join("secret.txt"), "outside").unwrap();
+        std::os::unix::fs::symlink(root.path().join("secret.txt"), dir.join("link.txt")).unwrap();
+        let output = root.path().join("run-1.tar.gz");
+
+        write_archive(&dir, "run-1", &output, &ArchiveFormat::TarGz).unwrap();
+
+        let gz = flate2::read::GzDecoder::new(File::open(&output).unwrap());
+        let names: Vec<String> = tar::Archive::new(gz)
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names.len(), 2, "{:?}", names);
+        assert!(!names.iter().any(|name| name.ends_with("link.txt")));
+    }
+
+    #[test]
+    fn archives_are_not_written_into_the_run_dir_or_without_a_password() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = run_dir(root.path());
+
+        let inside = dir.join("artifacts").join("run-1.tar.gz");
+        let result = write_archive(&dir, "run-1", &inside, &ArchiveFormat::TarGz);
+        assert!(matches!(result, Err(FactoryError::InvalidArgument(_))));
+        assert!(!inside.exists());
+
+        let format = ArchiveFormat::EncryptedZip {
+            password: String::new(),
+        };
+        let output = root.path().join("run-1.zip");
+        let result = write_archive(&dir, "run-1", &output, &format);
+        assert!(matches!(result, Err(FactoryError::InvalidArgument(_))));
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn debug_output_hides_the_password() {
+        let format = ArchiveFormat::EncryptedZip {
+            password: "hunter2".into(),
+        };
+        assert!(!format!("{:?}", format).contains("hunter2"));
+    }
+}
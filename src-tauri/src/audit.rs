@@ -0,0 +1,60 @@
+//! Audit log — an append-only record of destructive operations taken from
+//! the app, at `ops/factory/audit-log.ndjson`.
+//!
+//! One JSON object per line: when it happened, what was done, the trace ID
+//! of the command invocation that did it, and the details needed to tell
+//! afterwards what was lost.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use crate::error::FactoryError;
+
+pub const AUDIT_LOG_FILE: &str = "ops/factory/audit-log.ndjson";
+
+/// Append one entry for `action`, taken under `trace_id`, to the log at
+/// `path`.
+pub fn record(
+    path: &Path,
+    action: &str,
+    trace_id: &str,
+    details: serde_json::Value,
+) -> Result<(), FactoryError> {
+    let io_err =
+        |e: std::io::Error| FactoryError::IoError(format!("writing {}: {}", path.display(), e));
+    let entry = serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "action": action,
+        "traceId": trace_id,
+        "details": details,
+    });
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_err)?;
+    }
+    let mut log = OpenOptions::new().create(true).append(true).open(path).map_err(io_err)?;
+    writeln!(log, "{}", entry).map_err(io_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_appended_one_per_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_LOG_FILE);
+
+        record(&path, "first", "trace-1", serde_json::json!({ "n": 1 })).unwrap();
+        record(&path, "second", "trace-2", serde_json::json!({ "n": 2 })).unwrap();
+
+        let data = fs::read_to_string(&path).unwrap();
+        let actions: Vec<String> = data
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+            .map(|e| e["action"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(actions, ["first", "second"]);
+    }
+}
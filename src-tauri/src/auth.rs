@@ -0,0 +1,127 @@
+//! Role-based authorization for Tauri commands.
+//!
+//! The caller's role comes from `NEOXTEN_ROLE` or, failing that, from
+//! `ops/factory/session.json` (`{ "role": "operator" }`). With neither
+//! present the caller is `Admin`, which is what a single-user install
+//! has always been able to do.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::enforcer::FactoryCommand;
+use crate::error::{Denial, FactoryError};
+
+pub const ROLE_ENV_VAR: &str = "NEOXTEN_ROLE";
+pub const SESSION_FILE: &str = "ops/factory/session.json";
+
+/// Ordered from least to most privileged, so `>=` means "at least".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    ReadOnly,
+    Operator,
+    Admin,
+}
+
+impl Role {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "read_only" | "readonly" | "read-only" => Some(Role::ReadOnly),
+            "operator" => Some(Role::Operator),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Role::ReadOnly => "read_only",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthContext {
+    pub role: Role,
+}
+
+impl AuthContext {
+    /// Resolve the session role for the project at `working_dir`.
+    pub fn load(working_dir: &Path) -> Self {
+        let from_env = std::env::var(ROLE_ENV_VAR).ok().and_then(|r| Role::parse(&r));
+        let from_session = || {
+            let data = fs::read_to_string(working_dir.join(SESSION_FILE)).ok()?;
+            serde_json::from_str::<AuthContext>(&data).ok().map(|s| s.role)
+        };
+        Self {
+            role: from_env.or_else(from_session).unwrap_or(Role::Admin),
+        }
+    }
+
+    pub fn require(&self, required: Role) -> Result<(), FactoryError> {
+        if self.role >= required {
+            Ok(())
+        } else {
+            Err(FactoryError::Unauthorized(Denial::Role {
+                required,
+                actual: self.role,
+            }))
+        }
+    }
+
+    pub fn authorize(&self, cmd: &FactoryCommand) -> Result<(), FactoryError> {
+        self.require(cmd.required_role())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_are_checked_as_a_minimum() {
+        let operator = AuthContext { role: Role::Operator };
+
+        assert!(operator.authorize(&FactoryCommand::GetRunStatus).is_ok());
+        assert!(operator
+            .authorize(&FactoryCommand::AbortRun { run_id: "r".into() })
+            .is_ok());
+        assert!(matches!(
+            operator.require(Role::Admin),
+            Err(FactoryError::Unauthorized(Denial::Role {
+                required: Role::Admin,
+                actual: Role::Operator,
+            }))
+        ));
+    }
+
+    #[test]
+    fn read_only_callers_cannot_control_runs() {
+        let viewer = AuthContext { role: Role::ReadOnly };
+        let start = FactoryCommand::StartRun {
+            spec_path: "spec.yaml".into(),
+            blueprint_path: None,
+        };
+
+        assert!(viewer.authorize(&FactoryCommand::GetRunHistory { include_active: true }).is_ok());
+        assert!(viewer.authorize(&start).is_err());
+    }
+
+    #[test]
+    fn session_file_sets_the_role() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(AuthContext::load(root.path()).role, Role::Admin);
+
+        let session = root.path().join(SESSION_FILE);
+        fs::create_dir_all(session.parent().unwrap()).unwrap();
+        fs::write(&session, r#"{"role": "read_only"}"#).unwrap();
+        assert_eq!(AuthContext::load(root.path()).role, Role::ReadOnly);
+    }
+}
@@ -1,100 +1,1052 @@
-/// Factory Bridge — manages the Factory Core Node.js child process.
-///
-/// Spawns `node dist/cli/index.js` as a child process.
-/// Sends commands via stdin (JSON lines).
-/// Reads NDJSON events from stdout and relays them to the Tauri event system.
+//! Factory Bridge — manages the Factory Core Node.js child processes.
+//!
+//! Spawns `node dist/cli/index.js` as a child process per run.
+//! Sends commands via stdin (JSON lines).
+//! Reads NDJSON events from stdout and relays them to the Tauri event system.
+//!
+//! After an app restart, a factory process that outlived the previous
+//! session can be re-attached through its Unix domain socket instead,
+//! over mutual TLS when `BridgeConfig::tls` is set.
 
-use std::io::{BufRead, BufReader, Write};
+use std::any::Any;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
 
-use crate::types::FactoryEvent;
+use crate::config::{BridgeConfig, IpcMode};
+use crate::enforcer::FactoryCommand;
+use crate::envelope::CommandEnvelope;
+use crate::error::FactoryError;
+use crate::event_router::{EventSubscriptions, RunEventRouter};
+use crate::event_store::EventStore;
+use crate::events::{
+    EVENT_BRIDGE_ERROR, EVENT_BRIDGE_RECONNECTED, EVENT_BRIDGE_STARTED, EVENT_ERROR, EVENT_READY,
+};
+#[cfg(windows)]
+use crate::named_pipe::{self, PipeServer};
+use crate::pid_file;
+use crate::progress::ProgressTracker;
+use crate::session::{self, SessionRecorder, SESSIONS_DIR};
+#[cfg(unix)]
+use crate::tls;
+use crate::types::FactoryErrorEvent;
 
-pub struct FactoryBridge {
+const NODE_BINARY: &str = "node";
+const FACTORY_ENTRY: &str = "dist/cli/index.js";
+
+/// Where a panicking stdout reader thread leaves its panic message.
+pub const CRASH_LOG_FILE: &str = "ops/factory/.bridge-crash.log";
+
+/// Whether `FACTORY_ENTRY` existed when the app was built; set by `build.rs`.
+const FACTORY_DIST_EXISTS: &str = env!("FACTORY_DIST_EXISTS");
+
+/// Log a warning at startup if the app was built without the compiled
+/// factory. Spawning re-checks the entry point, so this is only an early
+/// hint for a build that was packaged before `npm run build`.
+pub fn warn_if_dist_missing() {
+    if FACTORY_DIST_EXISTS != "true" {
+        tracing::warn!(
+            entry = FACTORY_ENTRY,
+            "the factory was not built when this app was; runs will fail until it is"
+        );
+    }
+}
+
+pub(crate) type EventWaiters =
+    Arc<Mutex<HashMap<String, Vec<oneshot::Sender<serde_json::Value>>>>>;
+pub(crate) type PendingResponses = Arc<Mutex<HashMap<String, mpsc::Sender<serde_json::Value>>>>;
+
+/// Counters kept for the lifetime of the app, across factory restarts.
+#[derive(Debug, Default)]
+pub struct BridgeMetrics {
+    commands_sent: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    events_by_type: Mutex<HashMap<String, u64>>,
+    pub(crate) duplicates_dropped: AtomicU64,
+}
+
+impl BridgeMetrics {
+    pub fn commands_sent(&self) -> u64 {
+        self.commands_sent.load(Ordering::SeqCst)
+    }
+
+    /// Command bytes written to factory processes, newlines included.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::SeqCst)
+    }
+
+    /// Event bytes read from factory processes, newlines included.
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::SeqCst)
+    }
+
+    pub fn events_by_type(&self) -> HashMap<String, u64> {
+        self.events_by_type
+            .lock()
+            .map(|m| m.clone())
+            .unwrap_or_default()
+    }
+
+    /// Evidence entries not forwarded because their `seq` was already sent.
+    pub fn duplicates_dropped(&self) -> u64 {
+        self.duplicates_dropped.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn record_event(&self, event: &str) {
+        if let Ok(mut m) = self.events_by_type.lock() {
+            *m.entry(event.to_string()).or_insert(0) += 1;
+        }
+    }
+}
+
+type SharedWriter = Arc<Mutex<Box<dyn Write + Send>>>;
+
+fn is_suppressed(suppressed: &RwLock<HashSet<String>>, event_name: &str) -> bool {
+    suppressed.read().is_ok_and(|s| s.contains(event_name))
+}
+
+/// Times a spawned process from `spawn` to its first ready event.
+struct StartupTimer {
+    spawned_at: Instant,
+    duration_ms: Arc<Mutex<Option<u64>>>,
+}
+
+impl StartupTimer {
+    /// Clears the duration recorded for the previous spawn.
+    fn new(spawned_at: Instant, duration_ms: Arc<Mutex<Option<u64>>>) -> Self {
+        if let Ok(mut d) = duration_ms.lock() {
+            *d = None;
+        }
+        Self {
+            spawned_at,
+            duration_ms,
+        }
+    }
+
+    fn finish(self) -> u64 {
+        let ms = self.spawned_at.elapsed().as_millis() as u64;
+        if let Ok(mut d) = self.duration_ms.lock() {
+            *d = Some(ms);
+        }
+        ms
+    }
+}
+
+/// How long a factory spawned with `IpcMode::NamedPipe` has to connect.
+#[cfg(windows)]
+const PIPE_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The window `stderr_warning_threshold` counts lines over.
+const STDERR_WINDOW: Duration = Duration::from_secs(10);
+
+/// Counts a process's stderr lines over the last `STDERR_WINDOW`.
+struct StderrMonitor {
+    threshold: u32,
+    recent: VecDeque<(Instant, String)>,
+}
+
+impl StderrMonitor {
+    fn new(threshold: u32) -> Self {
+        Self {
+            threshold,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Record `line`, returning the `factory://bridge-error` payload once
+    /// the window holds more than `threshold` lines. The window then
+    /// starts over, so a noisy process warns once per burst, not per line.
+    fn record(&mut self, line: String, now: Instant) -> Option<serde_json::Value> {
+        if self.threshold == 0 {
+            return None;
+        }
+        while self.recent.front().is_some_and(|(at, _)| now.duration_since(*at) > STDERR_WINDOW) {
+            self.recent.pop_front();
+        }
+        self.recent.push_back((now, line));
+        if self.recent.len() <= self.threshold as usize {
+            return None;
+        }
+        let lines: Vec<String> = self.recent.drain(..).map(|(_, line)| line).collect();
+        Some(serde_json::json!({
+            "message": format!(
+                "factory process wrote {} stderr lines in {} seconds",
+                lines.len(),
+                STDERR_WINDOW.as_secs()
+            ),
+            "lines": lines,
+        }))
+    }
+}
+
+/// The text a panic was raised with; panics carry a `&str` or a `String`.
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload")
+}
+
+/// Append one line naming the run and the time to the crash log.
+fn append_crash_log(path: &Path, run_id: &str, message: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut log = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(
+        log,
+        "{} run {}: stdout reader panicked: {}",
+        chrono::Utc::now().to_rfc3339(),
+        run_id,
+        message
+    )
+}
+
+/// Where commands for a process about to be spawned will be written.
+enum CommandSink {
+    Stdin,
+    #[cfg(windows)]
+    Pipe(PipeServer),
+}
+
+/// One factory process and the stdin it reads commands from. `child` is
+/// `None` for a process this session did not spawn (a reconnected one, or
+/// a test writer); such processes are detached on shutdown, not killed.
+struct ChildContext {
     child: Option<Child>,
-    stdin_writer: Option<Arc<Mutex<Box<dyn Write + Send>>>>,
+    pid: Option<u32>,
+    stdin_writer: SharedWriter,
+}
+
+/// Commands written by `FactoryBridge::send_envelopes` whose responses
+/// haven't been collected yet. Their routing keys are unregistered when
+/// the batch is dropped, however waiting ends.
+pub struct PendingBatch {
+    ids: Vec<String>,
+    traces: HashMap<String, String>,
+    keys: Vec<String>,
+    rx: mpsc::Receiver<serde_json::Value>,
+    pending: PendingResponses,
+}
+
+impl PendingBatch {
+    /// Block until every command has a response, in send order.
+    pub fn wait(self, timeout: Duration) -> Result<Vec<serde_json::Value>, FactoryError> {
+        let deadline = Instant::now() + timeout;
+        let mut responses: HashMap<String, serde_json::Value> =
+            HashMap::with_capacity(self.ids.len());
+        while responses.len() < self.ids.len() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.rx.recv_timeout(remaining) {
+                Ok(data) => {
+                    let id = data["correlationId"].as_str().or_else(|| {
+                        self.traces.get(data["traceId"].as_str()?).map(String::as_str)
+                    });
+                    if let Some(id) = id {
+                        responses.insert(id.to_string(), data.clone());
+                    }
+                }
+                Err(_) => {
+                    return Err(FactoryError::Timeout(format!(
+                        "pipeline received {} of {} responses",
+                        responses.len(),
+                        self.ids.len()
+                    )));
+                }
+            }
+        }
+
+        Ok(self
+            .ids
+            .iter()
+            .map(|id| responses.remove(id).unwrap_or_default())
+            .collect())
+    }
+
+    /// `wait` for a batch of one command.
+    pub fn wait_one(self, timeout: Duration) -> Result<serde_json::Value, FactoryError> {
+        self.wait(timeout)?
+            .pop()
+            .ok_or_else(|| FactoryError::BridgeError("no response received".into()))
+    }
+}
+
+/// The next event of one name, as registered by
+/// `FactoryBridge::wait_for_event`. The registration is removed when this
+/// is dropped, whether or not the event arrived.
+pub struct EventWait {
+    event_name: String,
+    rx: oneshot::Receiver<serde_json::Value>,
+    waiters: EventWaiters,
+}
+
+impl EventWait {
+    /// Resolve with the event's payload, or fail after `timeout` or if the
+    /// bridge goes away first.
+    pub async fn wait(mut self, timeout: Duration) -> Result<serde_json::Value, FactoryError> {
+        tokio::time::timeout(timeout, &mut self.rx)
+            .await
+            .map_err(|_| FactoryError::Timeout(format!("waiting for {}", self.event_name)))?
+            .map_err(|_| {
+                FactoryError::BridgeError(format!("bridge closed before {}", self.event_name))
+            })
+    }
+}
+
+impl Drop for EventWait {
+    fn drop(&mut self) {
+        self.rx.close();
+        if let Ok(mut waiters) = self.waiters.lock() {
+            if let Some(senders) = waiters.get_mut(&self.event_name) {
+                senders.retain(|tx| !tx.is_closed());
+                if senders.is_empty() {
+                    waiters.remove(&self.event_name);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for PendingBatch {
+    fn drop(&mut self) {
+        // Each response removed only the key it was routed by.
+        if let Ok(mut pending) = self.pending.lock() {
+            for key in &self.keys {
+                pending.remove(key);
+            }
+        }
+    }
+}
+
+/// Multiplexes one factory process per run, keyed by run ID. Events read
+/// from each process are tagged with that process's run ID.
+pub struct FactoryBridge {
+    config: BridgeConfig,
+    children: HashMap<String, ChildContext>,
+    event_waiters: EventWaiters,
+    pending_responses: PendingResponses,
+    metrics: Arc<BridgeMetrics>,
+    progress: ProgressTracker,
+    /// From the latest `spawn` to its process's ready event.
+    spawn_duration_ms: Arc<Mutex<Option<u64>>>,
+    /// Event names the reader threads don't emit.
+    suppressed_events: Arc<RwLock<HashSet<String>>>,
+    /// The most recent `factory://error` event from any process.
+    last_error: Arc<Mutex<Option<FactoryErrorEvent>>>,
+    /// Glob subscribers from `subscribe_events_matching`.
+    subscriptions: EventSubscriptions,
+    /// The run most recently started, until it is killed or found finished
+    /// by `get_current_run_id`. A reattached process is keyed by PID, not
+    /// run, so it never becomes the current run.
+    active_run_id: Option<String>,
+    /// Set, with its panic message, once a stdout reader thread has
+    /// panicked; that process's events stop reaching the frontend. Cleared
+    /// when the next reader starts.
+    reader_crash: Arc<Mutex<Option<String>>>,
 }
 
 impl FactoryBridge {
-    pub fn new() -> Self {
+    pub fn new(config: BridgeConfig) -> Self {
+        let suppressed_events = config.suppressed_events.iter().cloned().collect();
         Self {
-            child: None,
-            stdin_writer: None,
+            config,
+            children: HashMap::new(),
+            event_waiters: Arc::new(Mutex::new(HashMap::new())),
+            pending_responses: Arc::new(Mutex::new(HashMap::new())),
+            metrics: Arc::new(BridgeMetrics::default()),
+            progress: ProgressTracker::default(),
+            spawn_duration_ms: Arc::new(Mutex::new(None)),
+            suppressed_events: Arc::new(RwLock::new(suppressed_events)),
+            last_error: Arc::new(Mutex::new(None)),
+            subscriptions: EventSubscriptions::default(),
+            active_run_id: None,
+            reader_crash: Arc::new(Mutex::new(None)),
         }
     }
 
-    pub fn spawn(&mut self, app: &AppHandle) -> Result<(), String> {
-        if self.child.is_some() {
-            return Err("factory process already running".into());
+    /// A bridge with `run_id` wired to an arbitrary writer instead of a
+    /// child's stdin. Used by benchmarks and tests to stand in for the
+    /// factory process.
+    pub fn with_writer(run_id: &str, writer: Box<dyn Write + Send>) -> Self {
+        let mut bridge = Self::new(BridgeConfig::default());
+        bridge.children.insert(
+            run_id.to_string(),
+            ChildContext {
+                child: None,
+                pid: None,
+                stdin_writer: Arc::new(Mutex::new(writer)),
+            },
+        );
+        bridge.active_run_id = Some(run_id.to_string());
+        bridge
+    }
+
+    pub fn spawn(&mut self, app: &AppHandle, run_id: &str) -> Result<(), FactoryError> {
+        let spawned_at = Instant::now();
+        self.reap_exited();
+        if self.children.contains_key(run_id) {
+            return Err(FactoryError::BridgeError(format!(
+                "factory process already running for run {}",
+                run_id
+            )));
+        }
+        if self.children.len() >= self.config.max_concurrent_runs {
+            return Err(FactoryError::BridgeError(format!(
+                "maximum of {} concurrent run(s) reached",
+                self.config.max_concurrent_runs
+            )));
+        }
+        if let IpcMode::SimulationMode { recording_path } = &self.config.ipc_mode {
+            let recording_path = self.config.working_dir.join(recording_path);
+            return self.spawn_simulation(app, run_id, &recording_path);
         }
 
-        let mut child = Command::new("node")
-            .args(["dist/cli/index.js", "factory", "run", "--spec", "pending"])
-            .stdin(Stdio::piped())
+        let node = find_on_path(NODE_BINARY).ok_or_else(|| FactoryError::ProcessFailed {
+            exit_code: None,
+            stderr: "node binary not found on PATH. Install Node.js 18+.".into(),
+        })?;
+        if let Some(min) = &self.config.min_node_version {
+            let output = Command::new(&node).arg("--version").output().map_err(|e| {
+                FactoryError::ProcessFailed {
+                    exit_code: None,
+                    stderr: format!("running node --version: {}", e),
+                }
+            })?;
+            check_node_version(&String::from_utf8_lossy(&output.stdout), min)?;
+        }
+        if !self.config.working_dir.join(FACTORY_ENTRY).is_file() {
+            return Err(FactoryError::ProcessFailed {
+                exit_code: None,
+                stderr: format!(
+                    "factory entry point {} not found. Run `npm run build` first.",
+                    FACTORY_ENTRY
+                ),
+            });
+        }
+
+        pid_file::clear_stale(&self.config.pid_file_path())?;
+
+        let mut command = Command::new(node);
+        command
+            .args([FACTORY_ENTRY, "factory", "run", "--spec", "pending"])
+            .current_dir(&self.config.working_dir)
+            .env("NEOXTEN_RUN_ID", run_id)
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("failed to spawn factory: {}", e))?;
+            .stderr(Stdio::piped());
+        let sink = self.prepare_command_sink(&mut command)?;
+        let mut child = command.spawn().map_err(|e| FactoryError::ProcessFailed {
+            exit_code: None,
+            stderr: format!("failed to spawn factory: {}", e),
+        })?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| FactoryError::BridgeError("no stdout".into()))?;
+        if let Some(stderr) = child.stderr.take() {
+            self.start_stderr_reader(app, run_id, stderr);
+        }
+        let writer: Box<dyn Write + Send> = match sink {
+            CommandSink::Stdin => Box::new(
+                child
+                    .stdin
+                    .take()
+                    .ok_or_else(|| FactoryError::BridgeError("no stdin".into()))?,
+            ),
+            #[cfg(windows)]
+            CommandSink::Pipe(server) => match server.accept(PIPE_CONNECT_TIMEOUT) {
+                Ok(pipe) => Box::new(pipe),
+                Err(e) => {
+                    let _ = stop_child(child);
+                    return Err(e);
+                }
+            },
+        };
 
-        let stdout = child.stdout.take().ok_or("no stdout")?;
-        let stdin = child.stdin.take().ok_or("no stdin")?;
+        let startup = StartupTimer::new(spawned_at, Arc::clone(&self.spawn_duration_ms));
+        self.start_reader(app, run_id, stdout, Some(startup));
+        self.children.insert(
+            run_id.to_string(),
+            ChildContext {
+                pid: Some(child.id()),
+                child: Some(child),
+                stdin_writer: Arc::new(Mutex::new(writer)),
+            },
+        );
+        self.active_run_id = Some(run_id.to_string());
+        self.sync_pid_file()
+    }
 
-        self.stdin_writer = Some(Arc::new(Mutex::new(Box::new(stdin))));
+    /// Stand in for a factory process by replaying `recording_path` into
+    /// the event reader. Commands sent to the run go nowhere.
+    fn spawn_simulation(
+        &mut self,
+        app: &AppHandle,
+        run_id: &str,
+        recording_path: &Path,
+    ) -> Result<(), FactoryError> {
+        let speed = self.config.replay_speed;
+        if !(speed > 0.0 && speed.is_finite()) {
+            return Err(FactoryError::InvalidArgument(format!(
+                "replay_speed must be a positive number, not {}",
+                speed
+            )));
+        }
+        let recording = session::read_recording(recording_path)?;
+        let (reader, writer) = std::io::pipe()
+            .map_err(|e| FactoryError::BridgeError(format!("replay pipe error: {}", e)))?;
+        std::thread::spawn(move || session::replay(&recording, speed, writer));
 
+        self.start_reader(app, run_id, reader, None);
+        self.children.insert(
+            run_id.to_string(),
+            ChildContext {
+                child: None,
+                pid: None,
+                stdin_writer: Arc::new(Mutex::new(Box::new(std::io::sink()))),
+            },
+        );
+        self.active_run_id = Some(run_id.to_string());
+        Ok(())
+    }
+
+    /// Set up `command` for the configured `IpcMode`. A named pipe is
+    /// created here, before the process exists to connect to it.
+    fn prepare_command_sink(&self, command: &mut Command) -> Result<CommandSink, FactoryError> {
+        match &self.config.ipc_mode {
+            IpcMode::Stdio => {
+                command.stdin(Stdio::piped());
+                Ok(CommandSink::Stdin)
+            }
+            #[cfg(windows)]
+            IpcMode::NamedPipe { name } => {
+                let server = named_pipe::create(name)?;
+                command
+                    .env(named_pipe::PIPE_ENV, named_pipe::pipe_path(name))
+                    .stdin(Stdio::null());
+                Ok(CommandSink::Pipe(server))
+            }
+            #[cfg(not(windows))]
+            IpcMode::NamedPipe { .. } => Err(FactoryError::InvalidArgument(
+                "named pipes only supported on Windows".into(),
+            )),
+            IpcMode::SimulationMode { .. } => Err(FactoryError::InvalidArgument(
+                "simulation mode does not spawn a factory process".into(),
+            )),
+        }
+    }
+
+    /// Attach to a factory process left running by a previous session,
+    /// through the socket it listens on, instead of spawning a new one.
+    /// The process is keyed as `pid-{pid}` until its events name a run.
+    /// Emits `factory://bridge-reconnected` once attached.
+    #[cfg(unix)]
+    pub fn reconnect(
+        &mut self,
+        app: &AppHandle,
+        pid: u32,
+        socket_path: PathBuf,
+    ) -> Result<(), FactoryError> {
+        use std::os::unix::net::UnixStream;
+
+        if !pid_file::is_alive(pid) {
+            return Err(FactoryError::BridgeError(format!(
+                "factory process {} is not running",
+                pid
+            )));
+        }
+        let key = format!("pid-{}", pid);
+        if self.children.contains_key(&key) {
+            return Ok(());
+        }
+
+        let stream = UnixStream::connect(&socket_path).map_err(|e| {
+            FactoryError::BridgeError(format!("connecting to {}: {}", socket_path.display(), e))
+        })?;
+        let writer: Box<dyn Write + Send> = match &self.config.tls {
+            Some(config) => {
+                let (reader, writer) = tls::connect(stream, config)?;
+                self.start_reader(app, &key, reader, None);
+                Box::new(writer)
+            }
+            None => {
+                let reader = stream
+                    .try_clone()
+                    .map_err(|e| FactoryError::BridgeError(format!("socket clone error: {}", e)))?;
+                self.start_reader(app, &key, reader, None);
+                Box::new(stream)
+            }
+        };
+
+        self.children.insert(
+            key,
+            ChildContext {
+                child: None,
+                pid: Some(pid),
+                stdin_writer: Arc::new(Mutex::new(writer)),
+            },
+        );
+        self.sync_pid_file()?;
+        let _ = app.emit(EVENT_BRIDGE_RECONNECTED, serde_json::json!({ "pid": pid }));
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn reconnect(
+        &mut self,
+        _app: &AppHandle,
+        _pid: u32,
+        socket_path: PathBuf,
+    ) -> Result<(), FactoryError> {
+        Err(FactoryError::BridgeError(format!(
+            "cannot reconnect through {}: Unix domain sockets are not available",
+            socket_path.display()
+        )))
+    }
+
+    /// Reconnect to every live process named in the PID file that has a
+    /// socket. Returns how many were attached; failures are logged, since
+    /// the app starts either way.
+    pub fn reconnect_existing(&mut self, app: &AppHandle) -> usize {
+        let pid_path = self.config.pid_file_path();
+        let mut attached = 0;
+        for pid in pid_file::read(&pid_path).into_iter().filter(|p| pid_file::is_alive(*p)) {
+            let socket = self.config.socket_path(pid);
+            if !socket.exists() {
+                continue;
+            }
+            match self.reconnect(app, pid, socket) {
+                Ok(()) => attached += 1,
+                Err(e) => tracing::warn!("not reconnecting to factory process {}: {}", pid, e),
+            }
+        }
+        attached
+    }
+
+    /// Relay NDJSON events read from `source` until it closes. With
+    /// `startup`, the first ready event also stops the timer and emits
+    /// `factory://bridge-started`.
+    fn start_reader(
+        &self,
+        app: &AppHandle,
+        run_id: &str,
+        source: impl Read + Send + 'static,
+        mut startup: Option<StartupTimer>,
+    ) {
         let app_handle = app.clone();
+        let key = run_id.to_string();
+        let suppressed_events = Arc::clone(&self.suppressed_events);
+        let last_error = Arc::clone(&self.last_error);
+        let subscriptions = self.subscriptions.clone();
+        let mut router = RunEventRouter::new(
+            run_id,
+            Arc::clone(&self.event_waiters),
+            Arc::clone(&self.pending_responses),
+            Arc::clone(&self.metrics),
+            self.progress.clone(),
+        );
+        let metrics = Arc::clone(&self.metrics);
+        let mut recorder = if self.config.record_session {
+            SessionRecorder::create(&self.config.working_dir.join(SESSIONS_DIR), run_id)
+                .map_err(|e| tracing::warn!("not recording run {}: {}", run_id, e))
+                .ok()
+        } else {
+            None
+        };
+        let store = if self.config.persist_events {
+            EventStore::open(&self.config.event_store_path())
+                .map_err(|e| tracing::warn!("not storing events for run {}: {}", run_id, e))
+                .ok()
+        } else {
+            None
+        };
+        let reader_crash = Arc::clone(&self.reader_crash);
+        if let Ok(mut crash) = reader_crash.lock() {
+            *crash = None;
+        }
+        let crash_log = self.config.crash_log_path();
+        let stderr = std::io::stderr();
         std::thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                match line {
-                    Ok(text) if !text.trim().is_empty() => {
-                        if let Ok(event) = serde_json::from_str::<FactoryEvent>(&text) {
-                            let event_name = format!("factory://{}", event.event);
-                            let _ = app_handle.emit(&event_name, event.data);
+            let relay = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let reader = BufReader::new(source);
+                for line in reader.lines() {
+                    let Ok(text) = line else { break };
+                    metrics.bytes_received.fetch_add(text.len() as u64 + 1, Ordering::Relaxed);
+                    if let Some(r) = recorder.as_mut() {
+                        if let Err(e) = r.record(&text) {
+                            tracing::warn!("session recording stopped: {}", e);
+                            recorder = None;
+                        }
+                    }
+                    for (event_name, payload) in router.route(&text) {
+                        if event_name == EVENT_ERROR {
+                            if let Ok(mut last) = last_error.lock() {
+                                *last = Some(FactoryErrorEvent::from_payload(&payload));
+                            }
+                        }
+                        if let Some(s) = store.as_ref() {
+                            if let Err(e) = s.insert(&key, &event_name, &payload) {
+                                tracing::warn!("event {} not stored: {}", event_name, e);
+                            }
+                        }
+                        subscriptions.dispatch(&key, &event_name, &payload);
+                        if !is_suppressed(&suppressed_events, &event_name) {
+                            let _ = app_handle.emit(&event_name, payload);
+                        }
+                        if event_name == EVENT_READY {
+                            if let Some(timer) = startup.take() {
+                                let payload = serde_json::json!({
+                                    "runId": key,
+                                    "spawnDurationMs": timer.finish(),
+                                });
+                                let _ = app_handle.emit(EVENT_BRIDGE_STARTED, payload);
+                            }
                         }
-                        let _ = app_handle.emit("factory://raw", text);
                     }
-                    Err(_) => break,
-                    _ => {}
                 }
+            }));
+            let Err(panic) = relay else { return };
+            // The thread is going down: report to stderr first, which needs
+            // nothing allocated, then make a best effort at the rest.
+            let _ = stderr.lock().write_all(b"factory bridge: stdout reader panicked\n");
+            let message = panic_message(panic.as_ref()).to_string();
+            if let Err(e) = append_crash_log(&crash_log, &key, &message) {
+                tracing::warn!("could not write {}: {}", crash_log.display(), e);
+            }
+            if let Ok(mut crash) = reader_crash.lock() {
+                *crash = Some(message.clone());
             }
+            let payload = serde_json::json!({
+                "runId": key,
+                "message": "stdout reader crashed; events from this process are no longer relayed",
+                "lines": [message],
+            });
+            let _ = app_handle.emit(EVENT_BRIDGE_ERROR, payload);
         });
+    }
 
-        self.child = Some(child);
-        Ok(())
+    /// Drain a spawned process's stderr, so it never blocks on a full pipe,
+    /// logging each line and warning the frontend about bursts.
+    fn start_stderr_reader(
+        &self,
+        app: &AppHandle,
+        run_id: &str,
+        stderr: impl Read + Send + 'static,
+    ) {
+        let app_handle = app.clone();
+        let run_id = run_id.to_string();
+        let mut monitor = StderrMonitor::new(self.config.stderr_warning_threshold);
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines() {
+                let Ok(line) = line else { break };
+                tracing::debug!(run_id = %run_id, "factory stderr: {}", line);
+                if let Some(mut payload) = monitor.record(line, Instant::now()) {
+                    payload["runId"] = serde_json::Value::String(run_id.clone());
+                    let _ = app_handle.emit(EVENT_BRIDGE_ERROR, payload);
+                }
+            }
+        });
     }
 
-    pub fn send_command(&self, json: serde_json::Value) -> Result<(), String> {
-        let writer = self
-            .stdin_writer
-            .as_ref()
-            .ok_or("factory process not running")?;
+    /// Rewrite the PID file from the processes currently attached.
+    fn sync_pid_file(&self) -> Result<(), FactoryError> {
+        let mut pids: Vec<u32> = self
+            .children
+            .values()
+            .filter_map(|ctx| ctx.pid)
+            .collect();
+        pids.sort_unstable();
+        pid_file::write(&self.config.pid_file_path(), &pids)
+    }
 
-        let mut guard = writer.lock().map_err(|e| format!("lock error: {}", e))?;
-        let line = serde_json::to_string(&json).map_err(|e| format!("serialize error: {}", e))?;
-        guard
-            .write_all(line.as_bytes())
-            .map_err(|e| format!("write error: {}", e))?;
+    fn writer_for(&self, run_id: &str) -> Result<&SharedWriter, FactoryError> {
+        if self.children.is_empty() {
+            return Err(FactoryError::BridgeNotRunning);
+        }
+        self.children
+            .get(run_id)
+            .map(|ctx| &ctx.stdin_writer)
+            .ok_or_else(|| {
+                FactoryError::InvalidArgument(format!("no factory process for run {}", run_id))
+            })
+    }
+
+    pub fn send_command(&self, run_id: &str, json: serde_json::Value) -> Result<(), FactoryError> {
+        let writer = self.writer_for(run_id)?;
+
+        let mut guard = writer
+            .lock()
+            .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+        let bytes = write_line(&mut **guard, &json, self.config.max_command_bytes)?;
         guard
-            .write_all(b"\n")
-            .map_err(|e| format!("write newline error: {}", e))?;
-        guard.flush().map_err(|e| format!("flush error: {}", e))?;
+            .flush()
+            .map_err(|e| FactoryError::BridgeError(format!("flush error: {}", e)))?;
+        self.metrics.commands_sent.fetch_add(1, Ordering::Relaxed);
+        self.metrics.bytes_sent.fetch_add(bytes, Ordering::Relaxed);
         Ok(())
     }
 
+    /// Send `envelope` to the process for `run_id`, tagged with the run ID.
+    /// The full envelope is logged at debug level for request tracing.
+    pub fn send_envelope(
+        &self,
+        run_id: &str,
+        envelope: &CommandEnvelope,
+    ) -> Result<(), FactoryError> {
+        tracing::debug!(run_id, %envelope, ?envelope, "sending factory command");
+        let mut json = envelope.to_bridge_json();
+        json["runId"] = serde_json::Value::String(run_id.to_string());
+        self.send_command(run_id, json)
+    }
+
+    /// Send every command back-to-back while holding the stdin lock, then
+    /// wait for all responses. Each command carries a `correlationId`; the
+    /// factory echoes it in its response event. Responses are returned in
+    /// the same order as `cmds`, regardless of arrival order.
+    pub fn pipeline_commands(
+        &self,
+        run_id: &str,
+        cmds: Vec<FactoryCommand>,
+        timeout: Duration,
+    ) -> Result<Vec<serde_json::Value>, FactoryError> {
+        let envelopes = cmds
+            .into_iter()
+            .map(|cmd| CommandEnvelope::new("pipeline_commands", cmd))
+            .collect();
+        self.pipeline_envelopes(run_id, envelopes, timeout)
+    }
+
+    /// `pipeline_commands` for envelopes built by the caller. A response is
+    /// matched by `correlationId` or, failing that, by `traceId`. Every
+    /// envelope is serialized and size-checked before any is written, so a
+    /// batch with one oversized envelope sends nothing.
+    pub fn pipeline_envelopes(
+        &self,
+        run_id: &str,
+        envelopes: Vec<CommandEnvelope>,
+        timeout: Duration,
+    ) -> Result<Vec<serde_json::Value>, FactoryError> {
+        self.send_envelopes(run_id, envelopes)?.wait(timeout)
+    }
+
+    /// Write `envelopes` and register for their responses without waiting
+    /// for them. The returned batch doesn't borrow the bridge, so callers
+    /// holding the bridge lock can release it before `PendingBatch::wait`.
+    pub fn send_envelopes(
+        &self,
+        run_id: &str,
+        envelopes: Vec<CommandEnvelope>,
+    ) -> Result<PendingBatch, FactoryError> {
+        let writer = self.writer_for(run_id)?;
+        let lines = envelopes
+            .iter()
+            .map(|e| encode_line(&e.to_bridge_json(), self.config.max_command_bytes))
+            .collect::<Result<Vec<String>, FactoryError>>()?;
+        let ids: Vec<String> = envelopes.iter().map(|e| e.correlation_id.to_string()).collect();
+        // Trace ID -> correlation ID, for responses that echo only the former.
+        let traces: HashMap<String, String> = envelopes
+            .iter()
+            .zip(&ids)
+            .filter_map(|(e, id)| Some((e.trace_id.clone()?, id.clone())))
+            .collect();
+        let keys: Vec<String> = ids.iter().chain(traces.keys()).cloned().collect();
+
+        let (tx, rx) = mpsc::channel();
+        {
+            let mut pending = self
+                .pending_responses
+                .lock()
+                .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+            for key in &keys {
+                pending.insert(key.clone(), tx.clone());
+            }
+        }
+        drop(tx);
+        // Dropping the batch on any early return unregisters its keys.
+        let batch = PendingBatch {
+            ids,
+            traces,
+            keys,
+            rx,
+            pending: Arc::clone(&self.pending_responses),
+        };
+
+        writer
+            .lock()
+            .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))
+            .and_then(|mut guard| {
+                for (envelope, line) in envelopes.iter().zip(&lines) {
+                    tracing::debug!(run_id, %envelope, ?envelope, "sending factory command");
+                    let bytes = write_encoded(&mut **guard, line)?;
+                    self.metrics.commands_sent.fetch_add(1, Ordering::Relaxed);
+                    self.metrics.bytes_sent.fetch_add(bytes, Ordering::Relaxed);
+                }
+                guard
+                    .flush()
+                    .map_err(|e| FactoryError::BridgeError(format!("flush error: {}", e)))
+            })?;
+        Ok(batch)
+    }
+
+    /// Send one command and block until its response arrives. Shares the
+    /// correlation-ID plumbing with `pipeline_commands`.
+    pub fn send_and_wait(
+        &self,
+        run_id: &str,
+        cmd: FactoryCommand,
+        timeout: Duration,
+    ) -> Result<serde_json::Value, FactoryError> {
+        self.send_envelope_and_wait(run_id, CommandEnvelope::new("send_and_wait", cmd), timeout)
+    }
+
+    /// `send_and_wait` for an envelope built by the caller, such as one
+    /// carrying a trace ID.
+    pub fn send_envelope_and_wait(
+        &self,
+        run_id: &str,
+        envelope: CommandEnvelope,
+        timeout: Duration,
+    ) -> Result<serde_json::Value, FactoryError> {
+        self.send_envelopes(run_id, vec![envelope])?.wait_one(timeout)
+    }
+
+    pub fn metrics(&self) -> Arc<BridgeMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Progress recorded from every run's events, for the summary task.
+    pub fn progress_tracker(&self) -> ProgressTracker {
+        self.progress.clone()
+    }
+
+    /// Resolves with the payload of the next event named `event_name`.
+    /// Register before sending the command that triggers the event.
+    pub fn wait_for_event(&self, event_name: &str) -> EventWait {
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut waiters) = self.event_waiters.lock() {
+            waiters.entry(event_name.to_string()).or_default().push(tx);
+        }
+        EventWait {
+            event_name: event_name.to_string(),
+            rx,
+            waiters: Arc::clone(&self.event_waiters),
+        }
+    }
+
+    /// Replace the events not forwarded to the frontend. Takes effect for
+    /// running processes from their next line.
+    pub fn set_suppressed_events(&self, events: Vec<String>) {
+        if let Ok(mut suppressed) = self.suppressed_events.write() {
+            *suppressed = events.into_iter().collect();
+        }
+    }
+
+    /// Milliseconds the latest spawned process took to become ready, or
+    /// `None` while it is still starting.
+    pub fn spawn_duration_ms(&self) -> Option<u64> {
+        self.spawn_duration_ms.lock().ok().and_then(|d| *d)
+    }
+
+    pub fn subscriptions(&self) -> &EventSubscriptions {
+        &self.subscriptions
+    }
+
+    /// The latest error event any factory process sent, if there was one.
+    pub fn last_error(&self) -> Option<FactoryErrorEvent> {
+        self.last_error.lock().ok().and_then(|e| e.clone())
+    }
+
+    /// The panic message of the stdout reader that crashed, if one has
+    /// since the latest reader started.
+    pub fn reader_crash(&self) -> Option<String> {
+        self.reader_crash.lock().ok().and_then(|c| c.clone())
+    }
+
     pub fn is_running(&self) -> bool {
-        self.child.is_some()
+        !self.children.is_empty()
+    }
+
+    /// The run most recently started, if it hasn't been killed.
+    pub fn active_run_id(&self) -> Option<&str> {
+        self.active_run_id.as_deref()
+    }
+
+    pub fn clear_active_run_id(&mut self) {
+        self.active_run_id = None;
+    }
+
+    /// Run IDs with an attached factory process, sorted.
+    pub fn active_run_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.children.keys().cloned().collect();
+        ids.sort();
+        ids
     }
 
-    pub fn kill(&mut self) -> Result<(), String> {
-        if let Some(ref mut child) = self.child {
-            child.kill().map_err(|e| format!("kill error: {}", e))?;
-            child.wait().map_err(|e| format!("wait error: {}", e))?;
+    /// Drop contexts whose process has already exited on its own.
+    fn reap_exited(&mut self) {
+        self.children.retain(|_, ctx| match ctx.child.as_mut() {
+            Some(child) => matches!(child.try_wait(), Ok(None)),
+            None => true,
+        });
+    }
+
+    /// Stop the factory process for one run. Safe to call repeatedly: a
+    /// process that has already exited is reaped without blocking.
+    pub fn kill_run(&mut self, run_id: &str) -> Result<(), FactoryError> {
+        if self.active_run_id.as_deref() == Some(run_id) {
+            self.active_run_id = None;
         }
-        self.child = None;
-        self.stdin_writer = None;
-        Ok(())
+        let Some(ctx) = self.children.remove(run_id) else {
+            return Ok(());
+        };
+        let stopped = ctx.child.map_or(Ok(()), stop_child);
+        if ctx.pid.is_some() {
+            self.sync_pid_file()?;
+        }
+        stopped
+    }
+
+    /// Stop every factory process. Safe to call repeatedly.
+    pub fn kill(&mut self) -> Result<(), FactoryError> {
+        let mut result = Ok(());
+        for run_id in self.active_run_ids() {
+            if let Err(e) = self.kill_run(&run_id) {
+                result = Err(e);
+            }
+        }
+        result
+    }
+}
+
+fn stop_child(mut child: Child) -> Result<(), FactoryError> {
+    let exited = |child: &mut Child| {
+        child
+            .try_wait()
+            .map(|status| status.is_some())
+            .map_err(|e| FactoryError::BridgeError(format!("wait error: {}", e)))
+    };
+    if exited(&mut child)? {
+        return Ok(());
+    }
+    child
+        .kill()
+        .map_err(|e| FactoryError::BridgeError(format!("kill error: {}", e)))?;
+    if !exited(&mut child)? {
+        child
+            .wait()
+            .map_err(|e| FactoryError::BridgeError(format!("wait error: {}", e)))?;
     }
+    Ok(())
 }
 
 impl Drop for FactoryBridge {
@@ -102,3 +1054,464 @@ impl Drop for FactoryBridge {
         let _ = self.kill();
     }
 }
+
+/// Write `json` as one line; returns the bytes written, newline included.
+/// Nothing is written if the line would exceed `max_bytes`.
+fn write_line(
+    writer: &mut dyn Write,
+    json: &serde_json::Value,
+    max_bytes: usize,
+) -> Result<u64, FactoryError> {
+    let line = encode_line(json, max_bytes)?;
+    write_encoded(writer, &line)
+}
+
+/// `json` serialized for `write_encoded`, refused if over `max_bytes`.
+fn encode_line(json: &serde_json::Value, max_bytes: usize) -> Result<String, FactoryError> {
+    let line = serde_json::to_string(json)
+        .map_err(|e| FactoryError::BridgeError(format!("serialize error: {}", e)))?;
+    if line.len() > max_bytes {
+        return Err(FactoryError::InvalidArgument("command payload too large".into()));
+    }
+    Ok(line)
+}
+
+fn write_encoded(writer: &mut dyn Write, line: &str) -> Result<u64, FactoryError> {
+    writer
+        .write_all(line.as_bytes())
+        .map_err(|e| FactoryError::BridgeError(format!("write error: {}", e)))?;
+    writer
+        .write_all(b"\n")
+        .map_err(|e| FactoryError::BridgeError(format!("write newline error: {}", e)))?;
+    Ok(line.len() as u64 + 1)
+}
+
+/// `major.minor.patch`, ignoring a leading `v` and any pre-release or
+/// build suffix. Missing components count as zero.
+fn parse_version(raw: &str) -> Option<(u64, u64, u64)> {
+    let core = raw.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+/// Compare `node --version` output against the configured minimum.
+fn check_node_version(output: &str, min: &str) -> Result<(), FactoryError> {
+    let required = parse_version(min).ok_or_else(|| {
+        FactoryError::InvalidArgument(format!("min_node_version {:?} is not a version", min))
+    })?;
+    let found = parse_version(output).ok_or_else(|| FactoryError::ProcessFailed {
+        exit_code: None,
+        stderr: format!("could not parse node --version output {:?}", output.trim()),
+    })?;
+    if found < required {
+        let (major, minor, patch) = found;
+        return Err(FactoryError::ProcessFailed {
+            exit_code: None,
+            stderr: format!("Node.js {}+ required, found {}.{}.{}", min, major, minor, patch),
+        });
+    }
+    Ok(())
+}
+
+/// Manual PATH lookup, so a missing Node.js install surfaces as a clear
+/// error instead of a raw `No such file or directory` from `spawn`.
+fn find_on_path(binary: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var).find_map(|dir| {
+        let candidate = dir.join(binary);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if cfg!(windows) {
+            let exe = dir.join(format!("{}.exe", binary));
+            if exe.is_file() {
+                return Some(exe);
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_router::route_response;
+
+    /// Stands in for the factory process: reads command lines from the
+    /// bridge and, once `batch` commands have arrived, answers them in
+    /// reverse order with their correlation IDs echoed back.
+    struct MockBridge;
+
+    impl MockBridge {
+        fn spawn(batch: usize) -> FactoryBridge {
+            let (reader, writer) = std::io::pipe().unwrap();
+            let bridge = FactoryBridge::with_writer("run-1", Box::new(writer));
+            let pending = Arc::clone(&bridge.pending_responses);
+            std::thread::spawn(move || {
+                let mut received = Vec::new();
+                for line in BufReader::new(reader).lines() {
+                    let Ok(line) = line else { break };
+                    received.push(serde_json::from_str::<serde_json::Value>(&line).unwrap());
+                    if received.len() == batch {
+                        for cmd in received.drain(..).rev() {
+                            let response = serde_json::json!({
+                                "correlationId": cmd["correlationId"],
+                                "queryType": cmd["queryType"],
+                            });
+                            route_response(&pending, &response);
+                        }
+                    }
+                }
+            });
+            bridge
+        }
+    }
+
+    #[test]
+    fn pipeline_preserves_command_order() {
+        let bridge = MockBridge::spawn(3);
+        let responses = bridge
+            .pipeline_commands(
+                "run-1",
+                vec![
+                    FactoryCommand::GetRunStatus,
+                    FactoryCommand::GetGateResults,
+                    FactoryCommand::GetRunHistory { include_active: true },
+                ],
+                Duration::from_secs(5),
+            )
+            .unwrap();
+
+        let kinds: Vec<&str> = responses
+            .iter()
+            .map(|r| r["queryType"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, ["run_status", "gate_results", "run_history"]);
+    }
+
+    #[test]
+    fn pipeline_times_out_when_responses_missing() {
+        let bridge = MockBridge::spawn(10);
+        let result = bridge.pipeline_commands(
+            "run-1",
+            vec![FactoryCommand::GetRunStatus, FactoryCommand::GetGateResults],
+            Duration::from_millis(100),
+        );
+
+        assert!(matches!(result, Err(FactoryError::Timeout(_))));
+        assert!(bridge.pending_responses.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn pipeline_sends_nothing_if_any_envelope_is_too_large() {
+        let (reader, writer) = std::io::pipe().unwrap();
+        let mut bridge = FactoryBridge::with_writer("run-1", Box::new(writer));
+        bridge.config.max_command_bytes = 1024;
+        let envelopes = [
+            FactoryCommand::GetRunStatus,
+            FactoryCommand::ValidateSpec {
+                spec_path: "x".repeat(2048),
+            },
+            FactoryCommand::GetGateResults,
+        ]
+        .into_iter()
+        .map(|cmd| CommandEnvelope::traced("test", cmd))
+        .collect();
+
+        let result = bridge.pipeline_envelopes("run-1", envelopes, Duration::from_secs(5));
+        assert!(matches!(result, Err(FactoryError::InvalidArgument(_))));
+        assert!(bridge.pending_responses.lock().unwrap().is_empty());
+        assert_eq!(bridge.metrics.commands_sent.load(Ordering::Relaxed), 0);
+
+        drop(bridge);
+        assert_eq!(BufReader::new(reader).lines().count(), 0);
+    }
+
+    #[test]
+    fn send_and_wait_returns_the_matching_response() {
+        let bridge = MockBridge::spawn(1);
+        let response = bridge
+            .send_and_wait("run-1", FactoryCommand::GetGateResults, Duration::from_secs(5))
+            .unwrap();
+
+        assert_eq!(response["queryType"], "gate_results");
+        assert!(bridge.pending_responses.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn responses_carrying_only_the_trace_id_are_routed_back() {
+        let (reader, writer) = std::io::pipe().unwrap();
+        let bridge = FactoryBridge::with_writer("run-1", Box::new(writer));
+        let pending = Arc::clone(&bridge.pending_responses);
+        std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let cmd: serde_json::Value = serde_json::from_str(&line.unwrap()).unwrap();
+                let response = serde_json::json!({ "traceId": cmd["traceId"], "ok": true });
+                route_response(&pending, &response);
+            }
+        });
+
+        let envelope = CommandEnvelope::traced("test", FactoryCommand::GetGateResults);
+        let trace_id = envelope.trace_id.clone().unwrap();
+        let response = bridge
+            .send_envelope_and_wait("run-1", envelope, Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(response["traceId"], trace_id);
+        assert!(bridge.pending_responses.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn send_and_wait_times_out_without_a_response() {
+        let bridge = FactoryBridge::with_writer("run-1", Box::new(std::io::sink()));
+        let result =
+            bridge.send_and_wait("run-1", FactoryCommand::GetRunStatus, Duration::from_millis(50));
+
+        assert!(matches!(result, Err(FactoryError::Timeout(_))));
+        assert!(bridge.pending_responses.lock().unwrap().is_empty());
+    }
+
+    fn attach_child(bridge: &mut FactoryBridge, run_id: &str, child: Child) {
+        bridge.children.insert(
+            run_id.to_string(),
+            ChildContext {
+                pid: Some(child.id()),
+                child: Some(child),
+                stdin_writer: Arc::new(Mutex::new(Box::new(std::io::sink()))),
+            },
+        );
+    }
+
+    #[test]
+    fn commands_route_to_the_named_run() {
+        let bridge = FactoryBridge::with_writer("run-1", Box::new(std::io::sink()));
+
+        assert!(bridge.send_command("run-1", serde_json::json!({"type": "query"})).is_ok());
+        assert!(matches!(
+            bridge.send_command("run-2", serde_json::json!({"type": "query"})),
+            Err(FactoryError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            FactoryBridge::new(BridgeConfig::default())
+                .send_command("run-1", serde_json::json!({"type": "query"})),
+            Err(FactoryError::BridgeNotRunning)
+        ));
+    }
+
+    #[test]
+    fn bytes_sent_counts_each_line_with_its_newline() {
+        let bridge = FactoryBridge::with_writer("run-1", Box::new(std::io::sink()));
+        bridge.send_command("run-1", serde_json::json!({"type": "query"})).unwrap();
+        bridge.send_command("run-1", serde_json::json!({})).unwrap();
+
+        // `{"type":"query"}\n` and `{}\n`.
+        assert_eq!(bridge.metrics().bytes_sent(), 17 + 3);
+        assert_eq!(bridge.metrics().bytes_received(), 0);
+    }
+
+    #[test]
+    fn oversized_commands_are_refused_before_writing() {
+        let bridge = FactoryBridge::with_writer("run-1", Box::new(std::io::sink()));
+        let cmd = FactoryCommand::DeriveSpecFromPlan {
+            plan_text: "x".repeat(2 * 1024 * 1024),
+        };
+
+        let result = bridge.send_command("run-1", cmd.to_bridge_json());
+        assert!(matches!(result, Err(FactoryError::InvalidArgument(_))));
+        assert_eq!(bridge.metrics().commands_sent(), 0);
+        assert_eq!(bridge.metrics().bytes_sent(), 0);
+    }
+
+    #[tokio::test]
+    async fn event_waits_are_unregistered_when_they_end() {
+        let bridge = FactoryBridge::new(BridgeConfig::default());
+        let timed_out = bridge.wait_for_event("factory://run-history");
+        let abandoned = bridge.wait_for_event("factory://run-history");
+        assert_eq!(bridge.event_waiters.lock().unwrap()["factory://run-history"].len(), 2);
+
+        let result = timed_out.wait(Duration::from_millis(10)).await;
+        assert!(matches!(result, Err(FactoryError::Timeout(_))));
+        assert_eq!(bridge.event_waiters.lock().unwrap()["factory://run-history"].len(), 1);
+
+        drop(abandoned);
+        assert!(bridge.event_waiters.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn spawn_duration_is_unknown_until_ready() {
+        let bridge = FactoryBridge::new(BridgeConfig::default());
+        let spawned_at = Instant::now() - Duration::from_millis(250);
+        let timer = StartupTimer::new(spawned_at, Arc::clone(&bridge.spawn_duration_ms));
+        assert_eq!(bridge.spawn_duration_ms(), None);
+
+        let ms = timer.finish();
+        assert!(ms >= 250);
+        assert_eq!(bridge.spawn_duration_ms(), Some(ms));
+    }
+
+    #[test]
+    fn suppressed_events_start_from_config_and_are_replaced() {
+        let config = BridgeConfig {
+            suppressed_events: vec!["factory://worker-progress".into()],
+            ..BridgeConfig::default()
+        };
+        let bridge = FactoryBridge::new(config);
+        let suppressed = |event| is_suppressed(&bridge.suppressed_events, event);
+        assert!(suppressed("factory://worker-progress"));
+        assert!(!suppressed("factory://evidence-entry"));
+
+        bridge.set_suppressed_events(vec!["factory://evidence-entry".into()]);
+        assert!(!suppressed("factory://worker-progress"));
+        assert!(suppressed("factory://evidence-entry"));
+    }
+
+    #[test]
+    fn stderr_warns_once_per_burst_over_the_threshold() {
+        let mut monitor = StderrMonitor::new(2);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert!(monitor.record("a".into(), at(0)).is_none());
+        assert!(monitor.record("b".into(), at(1)).is_none());
+        // "a" has left the window by the time "c" arrives.
+        assert!(monitor.record("c".into(), at(11)).is_none());
+        let warning = monitor.record("d".into(), at(11)).unwrap();
+        assert_eq!(warning["lines"], serde_json::json!(["b", "c", "d"]));
+        assert_eq!(warning["message"], "factory process wrote 3 stderr lines in 10 seconds");
+        assert!(monitor.record("e".into(), at(11)).is_none());
+
+        let mut off = StderrMonitor::new(0);
+        assert!((0..100).all(|i| off.record(i.to_string(), start).is_none()));
+    }
+
+    #[test]
+    fn node_version_is_compared_numerically() {
+        assert!(check_node_version("v18.17.1\n", "18.0.0").is_ok());
+        assert!(check_node_version("v20.0.0-nightly2023", "18").is_ok());
+        assert!(check_node_version("v100.0.0", "18.0.0").is_ok());
+
+        match check_node_version("v16.20.2\n", "18.0.0") {
+            Err(FactoryError::ProcessFailed { stderr, .. }) => {
+                assert_eq!(stderr, "Node.js 18.0.0+ required, found 16.20.2")
+            }
+            other => panic!("expected version error, got {:?}", other),
+        }
+        assert!(check_node_version("v18.9.0", "18.10.0").is_err());
+        assert!(check_node_version("not a version", "18.0.0").is_err());
+    }
+
+    /// A bridge whose PID file lives in a scratch directory.
+    fn scratch_bridge(root: &tempfile::TempDir) -> FactoryBridge {
+        FactoryBridge::new(BridgeConfig {
+            working_dir: root.path().to_path_buf(),
+            ..BridgeConfig::default()
+        })
+    }
+
+    #[test]
+    fn active_run_id_is_cleared_when_its_run_is_killed() {
+        let mut bridge = FactoryBridge::with_writer("run-1", Box::new(std::io::sink()));
+        assert_eq!(bridge.active_run_id(), Some("run-1"));
+
+        bridge.kill_run("run-2").unwrap();
+        assert_eq!(bridge.active_run_id(), Some("run-1"));
+        bridge.kill_run("run-1").unwrap();
+        assert_eq!(bridge.active_run_id(), None);
+    }
+
+    #[test]
+    fn kill_without_child_is_noop() {
+        let mut bridge = FactoryBridge::new(BridgeConfig::default());
+        assert!(bridge.kill().is_ok());
+        assert!(bridge.kill().is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kill_twice_does_not_panic() {
+        let root = tempfile::tempdir().unwrap();
+        let mut bridge = scratch_bridge(&root);
+        attach_child(&mut bridge, "run-1", Command::new("sleep").arg("30").spawn().unwrap());
+
+        assert!(bridge.kill().is_ok());
+        assert!(!bridge.is_running());
+        assert!(bridge.kill().is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kill_after_process_exited_does_not_block() {
+        let root = tempfile::tempdir().unwrap();
+        let mut bridge = scratch_bridge(&root);
+        let mut child = Command::new("true").spawn().unwrap();
+        while child.try_wait().unwrap().is_none() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        attach_child(&mut bridge, "run-1", child);
+
+        assert!(bridge.kill().is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reconnected_processes_are_detached_not_killed() {
+        let root = tempfile::tempdir().unwrap();
+        let mut bridge = scratch_bridge(&root);
+        let mut other = Command::new("sleep").arg("30").spawn().unwrap();
+        bridge.children.insert(
+            format!("pid-{}", other.id()),
+            ChildContext {
+                child: None,
+                pid: Some(other.id()),
+                stdin_writer: Arc::new(Mutex::new(Box::new(std::io::sink()))),
+            },
+        );
+        bridge.sync_pid_file().unwrap();
+        let path = root.path().join(pid_file::PID_FILE);
+        assert_eq!(pid_file::read(&path), [other.id()]);
+
+        bridge.kill().unwrap();
+        assert!(!path.exists());
+        assert!(other.try_wait().unwrap().is_none());
+        other.kill().unwrap();
+        other.wait().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pid_file_tracks_child_and_is_removed_on_shutdown() {
+        let root = tempfile::tempdir().unwrap();
+        let mut bridge = scratch_bridge(&root);
+        let child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+        attach_child(&mut bridge, "run-1", child);
+        bridge.sync_pid_file().unwrap();
+
+        let path = root.path().join(pid_file::PID_FILE);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), format!("{}\n", pid));
+
+        bridge.kill().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn reader_panics_are_appended_to_the_crash_log() {
+        let literal = std::panic::catch_unwind(|| panic!("bad line")).unwrap_err();
+        let formatted = std::panic::catch_unwind(|| panic!("bad seq {}", 7)).unwrap_err();
+        assert_eq!(panic_message(literal.as_ref()), "bad line");
+        assert_eq!(panic_message(formatted.as_ref()), "bad seq 7");
+
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join(CRASH_LOG_FILE);
+        append_crash_log(&path, "run-1", "bad line").unwrap();
+        append_crash_log(&path, "run-2", "bad seq 7").unwrap();
+        let log = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("run run-1: stdout reader panicked: bad line"));
+        assert!(lines[1].ends_with("run run-2: stdout reader panicked: bad seq 7"));
+    }
+}
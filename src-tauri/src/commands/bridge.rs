@@ -0,0 +1,158 @@
+use std::sync::Mutex;
+
+use tauri::ipc::Channel;
+use tauri::State;
+
+use crate::auth::{AuthContext, Role};
+use crate::bridge::FactoryBridge;
+use crate::config::BridgeConfig;
+use crate::enforcer::FactoryCommand;
+use crate::error::FactoryError;
+use crate::event_store::{self, EventStore};
+use crate::run_dir::STATE_FILE;
+use crate::types::{BridgeStateInfo, FactoryErrorEvent, RunPhase, RunStateFile, StoredEvent};
+
+/// Snapshot of the bridge for diagnostics panels.
+#[tauri::command]
+pub async fn get_bridge_state(
+    auth: State<'_, AuthContext>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+) -> Result<BridgeStateInfo, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let b = bridge
+        .lock()
+        .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+    let metrics = b.metrics();
+
+    Ok(BridgeStateInfo {
+        running: b.is_running(),
+        active_runs: b.active_run_ids(),
+        commands_sent: metrics.commands_sent(),
+        duplicates_dropped: metrics.duplicates_dropped(),
+        bytes_sent: metrics.bytes_sent(),
+        bytes_received: metrics.bytes_received(),
+        spawn_duration_ms: b.spawn_duration_ms(),
+        reader_crash: b.reader_crash(),
+    })
+}
+
+/// Stop forwarding the named events to the frontend, replacing the list
+/// from `BridgeConfig::suppressed_events`. An empty list forwards all.
+#[tauri::command]
+pub async fn update_suppressed_events(
+    auth: State<'_, AuthContext>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+    events: Vec<String>,
+) -> Result<(), FactoryError> {
+    auth.authorize(&FactoryCommand::UpdateSuppressedEvents {
+        events: events.clone(),
+    })?;
+    bridge
+        .lock()
+        .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?
+        .set_suppressed_events(events);
+    Ok(())
+}
+
+/// The run most recently started. If its `run-state.json` already shows
+/// it finished, it is forgotten and `None` is returned; a run without a
+/// readable state file yet counts as still going.
+#[tauri::command]
+pub async fn get_current_run_id(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+) -> Result<Option<String>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetCurrentRunId)?;
+    let mut b = bridge
+        .lock()
+        .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+    let Some(run_id) = b.active_run_id().map(str::to_string) else {
+        return Ok(None);
+    };
+    let finished = config
+        .read_run_dir(&run_id)?
+        .read_typed::<RunStateFile>(STATE_FILE)
+        .ok()
+        .and_then(|state| RunPhase::try_from(state.status.as_str()).ok())
+        .is_some_and(RunPhase::is_terminal);
+    if finished {
+        b.clear_active_run_id();
+        return Ok(None);
+    }
+    Ok(Some(run_id))
+}
+
+/// Events kept in the SQLite event store (see `persist_events`), newest
+/// first, filtered by whichever of `run_id` and `event_type` are given.
+/// Empty if nothing has been stored yet.
+#[tauri::command]
+pub async fn query_event_store(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: Option<String>,
+    event_type: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<StoredEvent>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let path = config.event_store_path();
+    let Some(store) = EventStore::open_read_only(&path)? else {
+        return Ok(Vec::new());
+    };
+    store
+        .query(run_id.as_deref(), event_type.as_deref(), limit)
+        .map_err(|e| event_store::store_err(&path, e))
+}
+
+/// The most recent `factory://error` event, for a frontend reconnecting
+/// after it may have missed it. Held in memory only.
+#[tauri::command]
+pub async fn get_last_error(
+    auth: State<'_, AuthContext>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+) -> Result<Option<FactoryErrorEvent>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetLastError)?;
+    let b = bridge
+        .lock()
+        .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+    Ok(b.last_error())
+}
+
+/// Send every event whose name matches the glob `pattern` over `channel`
+/// as `{ event, payload }`, whatever run it comes from; see
+/// `EventSubscriptions` for the names matched. Returns the token for
+/// `unsubscribe_events`.
+#[tauri::command]
+pub async fn subscribe_events_matching(
+    auth: State<'_, AuthContext>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+    pattern: String,
+    channel: Channel<serde_json::Value>,
+) -> Result<String, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let sink = Box::new(move |event: &str, payload: &serde_json::Value| {
+        channel
+            .send(serde_json::json!({ "event": event, "payload": payload }))
+            .is_ok()
+    });
+    bridge
+        .lock()
+        .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?
+        .subscriptions()
+        .subscribe(&pattern, sink)
+}
+
+/// End a subscription; `false` if the token was unknown or had already
+/// ended.
+#[tauri::command]
+pub async fn unsubscribe_events(
+    auth: State<'_, AuthContext>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+    token: String,
+) -> Result<bool, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let b = bridge
+        .lock()
+        .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+    Ok(b.subscriptions().unsubscribe(&token))
+}
@@ -0,0 +1,406 @@
+use std::fs;
+use std::io::{BufReader, Write};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use tauri::{State, Webview};
+
+use crate::audit;
+use crate::auth::{AuthContext, Role};
+use crate::commands::query::run_evidence_types;
+use crate::config::BridgeConfig;
+use crate::enforcer::FactoryCommand;
+use crate::envelope::new_trace_id;
+use crate::error::FactoryError;
+use crate::evidence_chain;
+use crate::evidence_types;
+use crate::origin;
+use crate::rate_limit::RateLimiter;
+use crate::run_dir::{
+    RunDir, ENVIRONMENT_FILE, EVIDENCE_CHAIN_FILE, EVIDENCE_CHECKPOINT_FILE, MANIFEST_FILE,
+    RUNNER_FILE, RUN_INPUTS_FILE, SPEC_SNAPSHOT_FILE, STATE_FILE,
+};
+use crate::types::{
+    CompactionReport, EvidenceTypeDescriptor, EvidenceTypeReport, ManifestFile, ReindexReport,
+    RepairReport, RunDirectoryValidation, RunStateFile,
+};
+
+/// Files every finished run directory has, besides `artifacts/`.
+const EXPECTED_RUN_FILES: &[&str] = &[MANIFEST_FILE, STATE_FILE, EVIDENCE_CHAIN_FILE];
+
+/// Files a run directory may also hold, depending on configuration.
+const OPTIONAL_RUN_FILES: &[&str] = &[
+    SPEC_SNAPSHOT_FILE,
+    ENVIRONMENT_FILE,
+    RUN_INPUTS_FILE,
+    RUNNER_FILE,
+    EVIDENCE_CHECKPOINT_FILE,
+];
+
+const ARTIFACTS_ENTRY: &str = "artifacts/";
+
+/// Merge a run's evidence chain shards back into `evidence-chain.ndjson`.
+/// Meant for finished runs; the factory must not be appending meanwhile.
+#[tauri::command]
+pub async fn compact_evidence_chain(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<CompactionReport, FactoryError> {
+    let cmd = FactoryCommand::CompactEvidenceChain {
+        run_id: run_id.clone(),
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    evidence_chain::compact(&config.write_run_dir(&run_id)?)
+}
+
+/// Every evidence entry type the factory writes, with what it means.
+#[tauri::command]
+pub async fn list_evidence_types(
+    auth: State<'_, AuthContext>,
+) -> Result<Vec<EvidenceTypeDescriptor>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    Ok(evidence_types::descriptors())
+}
+
+/// `list_evidence_types`, plus with `run_id` the types that run's chain
+/// contains and which of those the registry is missing.
+#[tauri::command]
+pub async fn get_evidence_types(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: Option<String>,
+) -> Result<EvidenceTypeReport, FactoryError> {
+    auth.authorize(&FactoryCommand::GetEvidenceTypes {
+        run_id: run_id.clone(),
+    })?;
+    let observed_types = match &run_id {
+        Some(run_id) => {
+            let file = config.read_run_dir(run_id)?.open(EVIDENCE_CHAIN_FILE)?;
+            run_evidence_types(BufReader::new(file))
+                .map_err(|e| {
+                    FactoryError::IoError(format!(
+                        "reading {} for run {}: {}",
+                        EVIDENCE_CHAIN_FILE, run_id, e
+                    ))
+                })?
+                .types
+        }
+        None => Vec::new(),
+    };
+    Ok(evidence_type_report(observed_types))
+}
+
+fn evidence_type_report(observed_types: Vec<String>) -> EvidenceTypeReport {
+    let unknown_types = observed_types
+        .iter()
+        .filter(|t| !evidence_types::KNOWN_EVIDENCE_TYPES.contains_key(t.as_str()))
+        .cloned()
+        .collect();
+    EvidenceTypeReport {
+        registered_types: evidence_types::descriptors(),
+        observed_types,
+        unknown_types,
+    }
+}
+
+/// Check every run directory after manual repairs: its manifest or state
+/// still parses for run history, its evidence chain verifies, and each
+/// artifact still has the hash its manifest recorded. What fails is
+/// reported per run; one bad run doesn't stop the rest.
+///
+/// Nothing is rewritten. History is read from the files each time and the
+/// chain has no separate index, so there is nothing stale to rebuild; and
+/// manifest hashes are evidence, so a mismatch is reported, not "fixed".
+#[tauri::command]
+pub async fn reindex_runs(
+    auth: State<'_, AuthContext>,
+    limiter: State<'_, RateLimiter>,
+    config: State<'_, BridgeConfig>,
+) -> Result<ReindexReport, FactoryError> {
+    auth.authorize(&FactoryCommand::ReindexRuns)?;
+    limiter.check(&FactoryCommand::ReindexRuns)?;
+    reindex(&config.runs_root())
+}
+
+fn reindex(runs_root: &Path) -> Result<ReindexReport, FactoryError> {
+    let mut report = ReindexReport::default();
+    if !runs_root.exists() {
+        return Ok(report);
+    }
+    let mut run_ids: Vec<String> = fs::read_dir(runs_root)
+        .map_err(|e| FactoryError::IoError(format!("listing {}: {}", runs_root.display(), e)))?
+        .flatten()
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    run_ids.sort();
+
+    for run_id in run_ids {
+        report.runs_processed += 1;
+        let mut errors = Vec::new();
+        match RunDir::for_read(runs_root, &run_id) {
+            Ok(run_dir) => reindex_run(&run_dir, &mut report, &mut errors),
+            Err(e) => errors.push(e.to_string()),
+        }
+        report.errors.extend(errors.into_iter().map(|e| format!("run {}: {}", run_id, e)));
+    }
+    Ok(report)
+}
+
+fn reindex_run(run_dir: &RunDir, report: &mut ReindexReport, errors: &mut Vec<String>) {
+    if run_dir.exists(MANIFEST_FILE) {
+        if let Err(e) = run_dir.read_typed::<ManifestFile>(MANIFEST_FILE) {
+            errors.push(e.to_string());
+        }
+    } else if let Err(e) = run_dir.read_typed::<RunStateFile>(STATE_FILE) {
+        errors.push(e.to_string());
+    }
+
+    if run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        match chain_verifies(run_dir) {
+            Ok(()) => report.evidence_files_indexed += 1,
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let manifest = run_dir.read_json(MANIFEST_FILE).unwrap_or_default();
+    for artifact in manifest["artifactHashes"].as_array().map(Vec::as_slice).unwrap_or_default() {
+        let (Some(path), Some(recorded)) = (artifact["path"].as_str(), artifact["sha256"].as_str())
+        else {
+            continue;
+        };
+        match artifact_sha256(run_dir, path) {
+            Ok(actual) if actual == recorded => report.artifacts_hashed += 1,
+            Ok(_) => {
+                report.artifacts_hashed += 1;
+                errors.push(format!("artifact {} no longer matches its recorded hash", path));
+            }
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+}
+
+fn chain_verifies(run_dir: &RunDir) -> Result<(), String> {
+    let data = run_dir.read_to_string(EVIDENCE_CHAIN_FILE).map_err(|e| e.to_string())?;
+    let entries = data
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<serde_json::Value>, _>>()
+        .map_err(|e| format!("{}: {}", EVIDENCE_CHAIN_FILE, e))?;
+    evidence_chain::verify(&entries).map_err(|e| format!("{}: {}", EVIDENCE_CHAIN_FILE, e))
+}
+
+fn artifact_sha256(run_dir: &RunDir, path: &str) -> Result<String, FactoryError> {
+    let bytes = fs::read(run_dir.safe_artifact_path(path)?)
+        .map_err(|e| FactoryError::IoError(format!("artifact {}: {}", path, e)))?;
+    let digest = Sha256::digest(bytes);
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Whether a run directory has everything a finished run leaves behind,
+/// and anything it shouldn't. Evidence chain shards count as expected.
+#[tauri::command]
+pub async fn validate_run_directory(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<RunDirectoryValidation, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    validate_run_dir(&config.read_run_dir(&run_id)?)
+}
+
+fn validate_run_dir(run_dir: &RunDir) -> Result<RunDirectoryValidation, FactoryError> {
+    let path = run_dir.path();
+    let entries = fs::read_dir(path).map_err(|e| {
+        FactoryError::IoError(format!("listing run {}: {}", run_dir.run_id(), e))
+    })?;
+    let mut unexpected_files: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|name| {
+            let shard = name.starts_with("evidence-chain") && name.ends_with(".ndjson");
+            !shard
+                && name != "artifacts"
+                && !EXPECTED_RUN_FILES.contains(&name.as_str())
+                && !OPTIONAL_RUN_FILES.contains(&name.as_str())
+        })
+        .collect();
+    unexpected_files.sort();
+
+    let has_artifacts_dir = run_dir.artifacts_dir().is_dir();
+    let mut missing_files: Vec<String> = EXPECTED_RUN_FILES
+        .iter()
+        .filter(|file| !run_dir.exists(file))
+        .map(|file| file.to_string())
+        .collect();
+    if !has_artifacts_dir {
+        missing_files.push(ARTIFACTS_ENTRY.to_string());
+    }
+
+    Ok(RunDirectoryValidation {
+        run_id: run_dir.run_id().to_string(),
+        has_manifest: run_dir.exists(MANIFEST_FILE),
+        has_state_file: run_dir.exists(STATE_FILE),
+        has_evidence_chain: run_dir.exists(EVIDENCE_CHAIN_FILE),
+        has_artifacts_dir,
+        is_complete: missing_files.is_empty(),
+        missing_files,
+        unexpected_files,
+    })
+}
+
+/// Create whatever `validate_run_directory` reports missing, with minimal
+/// content: an empty evidence chain and artifacts directory, and a state
+/// file or manifest taking its status from the other. Refused unless that
+/// status is terminal, so a run the factory is still writing is left
+/// alone. Existing files are never touched. Audit-logged when anything is
+/// created.
+#[tauri::command]
+pub async fn repair_run_directory(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<RepairReport, FactoryError> {
+    let cmd = FactoryCommand::RepairRunDirectory {
+        run_id: run_id.clone(),
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    let report = repair_run_dir(&config.write_run_dir(&run_id)?)?;
+    if !report.created.is_empty() {
+        audit::record(
+            &config.audit_log_path(),
+            "repair_run_directory",
+            &new_trace_id(),
+            serde_json::json!({ "runId": run_id, "created": report.created }),
+        )?;
+    }
+    Ok(report)
+}
+
+fn repair_run_dir(run_dir: &RunDir) -> Result<RepairReport, FactoryError> {
+    let status = run_dir.require_terminal("repair")?;
+    let validation = validate_run_dir(run_dir)?;
+    let run_id = run_dir.run_id();
+
+    let mut report = RepairReport {
+        run_id: run_id.to_string(),
+        created: Vec::new(),
+    };
+    for missing in &validation.missing_files {
+        let content = match missing.as_str() {
+            ARTIFACTS_ENTRY => {
+                fs::create_dir_all(run_dir.artifacts_dir()).map_err(|e| {
+                    FactoryError::IoError(format!("creating artifacts for run {}: {}", run_id, e))
+                })?;
+                report.created.push(missing.clone());
+                continue;
+            }
+            MANIFEST_FILE => serde_json::json!({
+                "runId": run_id,
+                "status": status,
+                "startedAt": "",
+                "durationMs": 0,
+            })
+            .to_string(),
+            STATE_FILE => serde_json::json!({
+                "runId": run_id,
+                "status": status,
+                "currentStage": "",
+                "gateResults": [],
+            })
+            .to_string(),
+            _ => String::new(),
+        };
+        run_dir
+            .open_for_write(missing)?
+            .write_all(content.as_bytes())
+            .map_err(|e| {
+                FactoryError::IoError(format!("writing {} for run {}: {}", missing, run_id, e))
+            })?;
+        report.created.push(missing.clone());
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestRunBuilder;
+
+    #[test]
+    fn reindex_reports_each_bad_run_and_carries_on() {
+        let root = tempfile::tempdir().unwrap();
+        TestRunBuilder::new()
+            .with_run_id("good")
+            .with_status("shipped")
+            .with_evidence_entries(3)
+            .with_artifact("app.apk", "apk")
+            .build(root.path())
+            .unwrap();
+        let edited = TestRunBuilder::new()
+            .with_run_id("edited")
+            .with_status("shipped")
+            .with_evidence_entries(3)
+            .with_artifact("app.apk", "apk")
+            .build(root.path())
+            .unwrap();
+        fs::write(edited.artifacts_dir().join("app.apk"), "patched").unwrap();
+        let chain = edited.path().join(EVIDENCE_CHAIN_FILE);
+        let tampered = fs::read_to_string(&chain).unwrap().replacen("\"seq\":1", "\"seq\":7", 1);
+        fs::write(&chain, tampered).unwrap();
+
+        let report = reindex(root.path()).unwrap();
+        assert_eq!(report.runs_processed, 2);
+        assert_eq!(report.evidence_files_indexed, 1);
+        assert_eq!(report.artifacts_hashed, 2);
+        assert_eq!(report.errors.len(), 2, "{:?}", report.errors);
+        assert!(report.errors.iter().all(|e| e.starts_with("run edited:")));
+    }
+
+    #[test]
+    fn run_directory_is_validated_and_repaired_in_place() {
+        let root = tempfile::tempdir().unwrap();
+        let run_dir = RunDir::for_write(root.path(), "partial").unwrap();
+        fs::create_dir_all(run_dir.path()).unwrap();
+        let state = r#"{"runId": "partial", "status": "shipped", "currentStage": "shipping"}"#;
+        fs::write(run_dir.path().join(STATE_FILE), state).unwrap();
+        fs::write(run_dir.path().join("evidence-chain.1.ndjson"), "").unwrap();
+        fs::write(run_dir.path().join("notes.txt"), "x").unwrap();
+
+        let found = validate_run_dir(&run_dir).unwrap();
+        assert!(found.has_state_file && !found.has_manifest && !found.is_complete);
+        assert_eq!(found.missing_files, [MANIFEST_FILE, EVIDENCE_CHAIN_FILE, ARTIFACTS_ENTRY]);
+        assert_eq!(found.unexpected_files, ["notes.txt"]);
+
+        let report = repair_run_dir(&run_dir).unwrap();
+        assert_eq!(report.created, found.missing_files);
+        assert!(validate_run_dir(&run_dir).unwrap().is_complete);
+        assert_eq!(fs::read_to_string(run_dir.path().join(STATE_FILE)).unwrap(), state);
+        let manifest: ManifestFile = run_dir.read_typed(MANIFEST_FILE).unwrap();
+        assert_eq!(manifest.status, "shipped");
+        assert!(repair_run_dir(&run_dir).unwrap().created.is_empty());
+
+        let active = RunDir::for_write(root.path(), "active").unwrap();
+        fs::create_dir_all(active.path()).unwrap();
+        fs::write(active.path().join(STATE_FILE), r#"{"status": "building"}"#).unwrap();
+        assert!(matches!(repair_run_dir(&active), Err(FactoryError::InvalidArgument(_))));
+        assert!(!active.path().join(MANIFEST_FILE).exists());
+    }
+
+    #[test]
+    fn evidence_type_report_flags_types_missing_from_the_registry() {
+        let observed = vec!["gate_pass".to_string(), "made_up".to_string()];
+        let report = evidence_type_report(observed);
+
+        assert_eq!(report.registered_types.len(), evidence_types::KNOWN_EVIDENCE_TYPES.len());
+        assert_eq!(report.observed_types, ["gate_pass", "made_up"]);
+        assert_eq!(report.unknown_types, ["made_up"]);
+        assert!(evidence_type_report(Vec::new()).unknown_types.is_empty());
+    }
+}
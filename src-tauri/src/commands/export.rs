@@ -0,0 +1,308 @@
+use std::collections::BTreeSet;
+use std::fs;
+
+use tauri::{State, Webview};
+
+use crate::archive::{self, ArchiveFormat};
+use crate::auth::AuthContext;
+use crate::commands::health::run_health_score;
+use crate::commands::query::{gate_result, run_stages};
+use crate::config::BridgeConfig;
+use crate::enforcer::FactoryCommand;
+use crate::error::FactoryError;
+use crate::origin;
+use crate::run_dir::{EVIDENCE_CHAIN_FILE, MANIFEST_FILE, STATE_FILE};
+use crate::types::{
+    GateResult, HtmlExportResult, RunArchiveResult, RunHealthScore, SarifExportResult,
+};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const TOOL_NAME: &str = "neoxten-factory";
+
+const RUN_REPORT_TEMPLATE: &str = include_str!("../../templates/run-report.html.j2");
+
+/// Write the run's gate checks as a SARIF 2.1.0 log, one result per
+/// check. Failed checks are `error`; passed checks are kept as `none` so
+/// tools can show what was verified, not only what broke.
+#[tauri::command]
+pub async fn export_evidence_sarif(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    output_path: String,
+) -> Result<SarifExportResult, FactoryError> {
+    let cmd = FactoryCommand::ExportEvidenceSarif {
+        run_id: run_id.clone(),
+        output_path: output_path.clone(),
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    let chain = config.read_run_dir(&run_id)?.read_to_string(EVIDENCE_CHAIN_FILE)?;
+    let (log, results_count) = sarif_log(&chain);
+
+    let json = serde_json::to_string_pretty(&log)
+        .map_err(|e| FactoryError::ParseError(format!("serializing SARIF log: {}", e)))?;
+    fs::write(&output_path, json)
+        .map_err(|e| FactoryError::IoError(format!("writing {}: {}", output_path, e)))?;
+
+    Ok(SarifExportResult {
+        path: output_path,
+        results_count,
+        run_id,
+    })
+}
+
+/// Pack the whole run directory into one archive, a `.tar.gz` unless
+/// `archive_format` asks for a (possibly encrypted) ZIP. A password is
+/// used for this export only and never kept.
+#[tauri::command]
+pub async fn export_run(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    output_path: String,
+    archive_format: Option<ArchiveFormat>,
+) -> Result<RunArchiveResult, FactoryError> {
+    let cmd = FactoryCommand::ExportRun {
+        run_id: run_id.clone(),
+        output_path: output_path.clone(),
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    let format = archive_format.unwrap_or_default();
+    let size_bytes = archive::write_archive(
+        run_dir.path(),
+        &run_id,
+        std::path::Path::new(&output_path),
+        &format,
+    )?;
+
+    Ok(RunArchiveResult {
+        path: output_path,
+        run_id,
+        size_bytes,
+    })
+}
+
+/// Write a single self-contained HTML page summarizing the run: status,
+/// duration, health score, stage timeline, gate results and artifacts.
+/// Styles are inlined and nothing is fetched, so the file can be mailed
+/// or opened anywhere.
+#[tauri::command]
+pub async fn export_run_html(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    output_path: String,
+) -> Result<HtmlExportResult, FactoryError> {
+    let cmd = FactoryCommand::ExportRunHtml {
+        run_id: run_id.clone(),
+        output_path: output_path.clone(),
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    let state = run_dir.read_json(STATE_FILE)?;
+    let manifest = run_dir.read_json(MANIFEST_FILE).unwrap_or_default();
+    let chain = if run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        run_dir.read_to_string(EVIDENCE_CHAIN_FILE)?
+    } else {
+        String::new()
+    };
+    let health = run_health_score(&config, &run_id)?;
+
+    let html = run_html(&run_id, &state, &manifest, &chain, &health)?;
+    fs::write(&output_path, &html)
+        .map_err(|e| FactoryError::IoError(format!("writing {}: {}", output_path, e)))?;
+
+    Ok(HtmlExportResult {
+        path: output_path,
+        size_bytes: html.len() as u64,
+    })
+}
+
+/// Render the report. The template is named `.html`, so every value is
+/// HTML-escaped.
+fn run_html(
+    run_id: &str,
+    state: &serde_json::Value,
+    manifest: &serde_json::Value,
+    chain: &str,
+    health: &RunHealthScore,
+) -> Result<String, FactoryError> {
+    let gates: Vec<GateResult> = state["gateResults"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(gate_result)
+        .collect();
+    let artifacts: Vec<serde_json::Value> =
+        manifest["artifactHashes"].as_array().cloned().unwrap_or_default();
+    let duration = match manifest["durationMs"].as_u64() {
+        Some(ms) => format!("{:.1} s", ms as f64 / 1000.0),
+        None => "—".to_string(),
+    };
+
+    let mut env = minijinja::Environment::new();
+    env.add_template("run-report.html", RUN_REPORT_TEMPLATE)
+        .map_err(|e| FactoryError::ParseError(format!("run report template: {}", e)))?;
+    let template = env
+        .get_template("run-report.html")
+        .map_err(|e| FactoryError::ParseError(format!("run report template: {}", e)))?;
+    template
+        .render(minijinja::context! {
+            run_id => run_id,
+            generated_at => chrono::Utc::now().to_rfc3339(),
+            status => state["status"].as_str().unwrap_or("unknown"),
+            started_at => manifest["startedAt"].as_str().unwrap_or("—"),
+            duration => duration,
+            health => health,
+            stages => run_stages(state, chain),
+            gates => gates,
+            artifacts => artifacts,
+        })
+        .map_err(|e| FactoryError::ParseError(format!("rendering run report: {}", e)))
+}
+
+/// Build the SARIF log from an evidence chain's NDJSON text.
+fn sarif_log(chain: &str) -> (serde_json::Value, u32) {
+    let mut rules = BTreeSet::new();
+    let mut results = Vec::new();
+
+    let gate_entries = chain
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .filter(|e| matches!(e["type"].as_str(), Some("gate_pass" | "gate_fail")));
+
+    for entry in gate_entries {
+        let gate_id = entry["data"]["gateId"].as_str().unwrap_or("unknown").to_string();
+        let checks = entry["data"]["checks"].as_array().cloned().unwrap_or_default();
+        for check in checks {
+            let passed = check["passed"].as_bool().unwrap_or(false);
+            let mut text = format!(
+                "{}: measured {} against threshold {}",
+                check["name"].as_str().unwrap_or("check"),
+                check["measured"],
+                check["threshold"]
+            );
+            if let Some(message) = check["message"].as_str() {
+                text = format!("{} ({})", text, message);
+            }
+            results.push(serde_json::json!({
+                "ruleId": gate_id,
+                "level": if passed { "none" } else { "error" },
+                "kind": if passed { "pass" } else { "fail" },
+                "message": { "text": text },
+                "properties": {
+                    "seq": entry["seq"],
+                    "stage": entry["stage"],
+                    "timestamp": entry["timestamp"],
+                },
+            }));
+        }
+        rules.insert(gate_id);
+    }
+
+    let count = results.len() as u32;
+    let rules: Vec<serde_json::Value> = rules
+        .into_iter()
+        .map(|id| serde_json::json!({ "id": id }))
+        .collect();
+    let log = serde_json::json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": { "driver": { "name": TOOL_NAME, "rules": rules } },
+            "results": results,
+        }],
+    });
+    (log, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gate_checks_become_sarif_results() {
+        let gate = serde_json::json!({
+            "seq": 1,
+            "type": "gate_fail",
+            "stage": "testing",
+            "data": {
+                "gateId": "perf",
+                "checks": [
+                    { "name": "startupMs", "passed": false, "measured": 900, "threshold": 500 },
+                    { "name": "bundleKb", "passed": true, "measured": 80, "threshold": 100 },
+                ],
+            },
+        });
+        let chain = format!("{}\n{}\n", r#"{"seq":0,"type":"run_start","data":{}}"#, gate);
+
+        let (log, count) = sarif_log(&chain);
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(log["runs"][0]["tool"]["driver"]["rules"][0]["id"], "perf");
+        assert_eq!(results[0]["ruleId"], "perf");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[1]["level"], "none");
+        assert!(results[0]["message"]["text"].as_str().unwrap().starts_with("startupMs"));
+    }
+
+    #[test]
+    fn run_html_report_has_every_section_and_escapes_values() {
+        use scraper::{Html, Selector};
+
+        let state = serde_json::json!({
+            "status": "failed",
+            "currentStage": "testing",
+            "gateResults": [
+                {
+                    "gateId": "perf<script>",
+                    "passed": false,
+                    "checks": [
+                        { "name": "lcp_ms", "passed": false, "measured": 900, "threshold": 500 },
+                    ],
+                },
+                { "gateId": "lint", "passed": true },
+            ],
+        });
+        let manifest = serde_json::json!({
+            "startedAt": "2026-01-01T00:00:00.000Z",
+            "durationMs": 65_000,
+            "artifactHashes": [{ "path": "app.apk", "sha256": "abc", "platform": "android" }],
+        });
+        let health = RunHealthScore {
+            score: 72.5,
+            grade: "C".to_string(),
+            components: Vec::new(),
+        };
+
+        let html = run_html("run-1", &state, &manifest, "", &health).unwrap();
+        let doc = Html::parse_document(&html);
+        let text = |id: &str| {
+            doc.select(&Selector::parse(id).unwrap())
+                .next()
+                .unwrap_or_else(|| panic!("no element {}", id))
+                .text()
+                .collect::<String>()
+        };
+
+        assert_eq!(text("#run-status"), "failed");
+        assert_eq!(text("#run-duration"), "65.0 s");
+        assert!(text("#health-score").contains('C'));
+        assert!(text("#stage-timeline").contains("testing"));
+        assert!(text("#gate-results").contains("perf<script>"));
+        assert!(text("#artifacts").contains("app.apk"));
+        assert!(html.contains("perf&lt;script&gt;"));
+        assert_eq!(doc.select(&Selector::parse("script, link").unwrap()).count(), 0);
+    }
+}
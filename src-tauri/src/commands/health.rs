@@ -0,0 +1,218 @@
+//! Run health — one 0–100 score summarizing how a run went, for people who
+//! don't want to read gate results.
+//!
+//! Four components, each 0–100, are combined as a weighted sum: gate pass
+//! rate, evidence chain integrity, duration against the historical mean,
+//! and artifacts produced against the spec's delivery targets.
+
+use tauri::State;
+
+use crate::auth::{AuthContext, Role};
+use crate::commands::query::read_run_history;
+use crate::commands::run::run_inputs;
+use crate::config::BridgeConfig;
+use crate::error::FactoryError;
+use crate::evidence_chain;
+use crate::run_dir::{RunDir, EVIDENCE_CHAIN_FILE, MANIFEST_FILE, STATE_FILE};
+use crate::types::{HealthComponent, RunHealthScore};
+
+const GATE_WEIGHT: f32 = 0.4;
+const INTEGRITY_WEIGHT: f32 = 0.3;
+const DURATION_WEIGHT: f32 = 0.2;
+const ARTIFACT_WEIGHT: f32 = 0.1;
+
+/// Lowest score for each grade, best first; anything lower is an F.
+const GRADES: &[(f32, &str)] = &[(90.0, "A"), (80.0, "B"), (70.0, "C"), (60.0, "D")];
+
+#[tauri::command]
+pub async fn get_run_health_score(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<RunHealthScore, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    run_health_score(&config, &run_id)
+}
+
+/// The score for `run_id`, also used by the HTML report.
+pub(crate) fn run_health_score(
+    config: &BridgeConfig,
+    run_id: &str,
+) -> Result<RunHealthScore, FactoryError> {
+    let run_dir = config.read_run_dir(run_id)?;
+    let state = run_dir.read_json(STATE_FILE)?;
+    let manifest = if run_dir.exists(MANIFEST_FILE) {
+        Some(run_dir.read_json(MANIFEST_FILE)?)
+    } else {
+        None
+    };
+
+    let chain_intact = chain_intact(&run_dir)?;
+    let past_durations: Vec<u64> = read_run_history(&config.runs_root(), false)?
+        .into_iter()
+        .filter(|run| run.run_id != run_id && run.duration_ms > 0)
+        .map(|run| run.duration_ms)
+        .collect();
+    let expected_artifacts = run_inputs(&run_dir)
+        .ok()
+        .flatten()
+        .and_then(|inputs| delivery_targets(&config.working_dir.join(inputs.spec_path)));
+
+    Ok(health_score(
+        &state,
+        manifest.as_ref(),
+        chain_intact,
+        &past_durations,
+        expected_artifacts,
+    ))
+}
+
+/// A missing chain counts as intact: there is nothing to have tampered with.
+fn chain_intact(run_dir: &RunDir) -> Result<bool, FactoryError> {
+    if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        return Ok(true);
+    }
+    let entries: Vec<serde_json::Value> = run_dir
+        .read_to_string(EVIDENCE_CHAIN_FILE)?
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+    Ok(evidence_chain::verify(&entries).is_ok())
+}
+
+/// How many delivery targets the spec at `path` lists, if it can be read.
+fn delivery_targets(path: &std::path::Path) -> Option<usize> {
+    let spec: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    Some(spec["delivery"]["targets"].as_sequence()?.len())
+}
+
+fn health_score(
+    state: &serde_json::Value,
+    manifest: Option<&serde_json::Value>,
+    chain_intact: bool,
+    past_durations: &[u64],
+    expected_artifacts: Option<usize>,
+) -> RunHealthScore {
+    let components = vec![
+        HealthComponent {
+            name: "gate_pass_rate".into(),
+            score: gate_pass_rate(state),
+            weight: GATE_WEIGHT,
+        },
+        HealthComponent {
+            name: "evidence_integrity".into(),
+            score: if chain_intact { 100.0 } else { 0.0 },
+            weight: INTEGRITY_WEIGHT,
+        },
+        HealthComponent {
+            name: "duration".into(),
+            score: duration_score(manifest.and_then(|m| m["durationMs"].as_u64()), past_durations),
+            weight: DURATION_WEIGHT,
+        },
+        HealthComponent {
+            name: "artifacts".into(),
+            score: artifact_score(manifest, expected_artifacts),
+            weight: ARTIFACT_WEIGHT,
+        },
+    ];
+    let score = components.iter().map(|c| c.score * c.weight).sum::<f32>();
+    // Rounded to a tenth so float error can't drop a run a grade.
+    let score = ((score * 10.0).round() / 10.0).clamp(0.0, 100.0);
+    let grade = GRADES
+        .iter()
+        .find(|(min, _)| score >= *min)
+        .map_or("F", |(_, grade)| *grade);
+
+    RunHealthScore {
+        score,
+        grade: grade.to_string(),
+        components,
+    }
+}
+
+/// Share of gates whose latest result passed. A run that has evaluated no
+/// gates has passed none.
+fn gate_pass_rate(state: &serde_json::Value) -> f32 {
+    let mut latest: Vec<(&str, bool)> = Vec::new();
+    for gate in state["gateResults"].as_array().map(Vec::as_slice).unwrap_or_default() {
+        let id = gate["gateId"].as_str().unwrap_or_default();
+        let passed = gate["passed"].as_bool().unwrap_or(false);
+        match latest.iter_mut().find(|(seen, _)| *seen == id) {
+            Some(entry) => entry.1 = passed,
+            None => latest.push((id, passed)),
+        }
+    }
+    if latest.is_empty() {
+        return 0.0;
+    }
+    let passed = latest.iter().filter(|(_, passed)| *passed).count();
+    100.0 * passed as f32 / latest.len() as f32
+}
+
+/// Full marks at or under the historical mean, falling off in proportion
+/// beyond it. Without a duration or a history there is nothing to judge.
+fn duration_score(duration_ms: Option<u64>, past_durations: &[u64]) -> f32 {
+    let Some(duration) = duration_ms.filter(|d| *d > 0) else {
+        return 100.0;
+    };
+    if past_durations.is_empty() {
+        return 100.0;
+    }
+    let mean = past_durations.iter().sum::<u64>() as f32 / past_durations.len() as f32;
+    (100.0 * mean / duration as f32).min(100.0)
+}
+
+/// Artifacts in the manifest against one per delivery target, or against
+/// at least one when the spec can't be read.
+fn artifact_score(manifest: Option<&serde_json::Value>, expected: Option<usize>) -> f32 {
+    let produced = manifest
+        .and_then(|m| m["artifactHashes"].as_array())
+        .map_or(0, Vec::len);
+    let expected = expected.unwrap_or(1).max(1);
+    (100.0 * produced as f32 / expected as f32).min(100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn healthy_run_scores_an_a() {
+        let state = serde_json::json!({ "gateResults": [
+            { "gateId": "spec_valid", "passed": true },
+            { "gateId": "tests_pass", "passed": false },
+            { "gateId": "tests_pass", "passed": true },
+        ]});
+        let manifest = serde_json::json!({
+            "durationMs": 1000,
+            "artifactHashes": [{ "path": "app.apk" }, { "path": "web.zip" }],
+        });
+
+        let health = health_score(&state, Some(&manifest), true, &[1500, 2500], Some(2));
+        assert_close(health.score, 100.0);
+        assert_eq!(health.grade, "A");
+        assert_close(health.components.iter().map(|c| c.weight).sum(), 1.0);
+    }
+
+    #[test]
+    fn each_component_pulls_the_score_down() {
+        let state = serde_json::json!({ "gateResults": [
+            { "gateId": "spec_valid", "passed": true },
+            { "gateId": "tests_pass", "passed": false },
+        ]});
+        // Twice the mean duration and one of two artifacts.
+        let manifest = serde_json::json!({ "durationMs": 2000, "artifactHashes": [{}] });
+
+        let health = health_score(&state, Some(&manifest), false, &[1000], Some(2));
+        let scores: Vec<f32> = health.components.iter().map(|c| c.score).collect();
+        assert_eq!(scores, [50.0, 0.0, 50.0, 50.0]);
+        // 0.4 * 50 + 0.3 * 0 + 0.2 * 50 + 0.1 * 50
+        assert_close(health.score, 35.0);
+        assert_eq!(health.grade, "F");
+    }
+}
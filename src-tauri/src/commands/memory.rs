@@ -0,0 +1,300 @@
+use std::fs;
+use std::path::Path;
+
+use tauri::{State, Webview};
+
+use crate::audit;
+use crate::auth::{AuthContext, Role};
+use crate::config::BridgeConfig;
+use crate::consequence::{self, MergeStrategy};
+use crate::enforcer::FactoryCommand;
+use crate::envelope::new_trace_id;
+use crate::error::FactoryError;
+use crate::origin;
+use crate::types::{
+    ConsequenceMemoryAgeEntry, ConsequenceMemoryChange, ConsequenceMemorySize,
+    ConsequenceMemorySnapshot, ExpirationReport, ImportReport, PurgeReport,
+};
+
+/// Bulk-load a consequence memory dump (a CI artifact, or an export from
+/// another machine) into the local memory file.
+#[tauri::command]
+pub async fn import_consequence_memory(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    source_path: String,
+    merge_strategy: String,
+) -> Result<ImportReport, FactoryError> {
+    let cmd = FactoryCommand::ImportConsequenceMemory {
+        source_path: source_path.clone(),
+        merge_strategy: merge_strategy.clone(),
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    let strategy: MergeStrategy = merge_strategy.parse()?;
+
+    let data = fs::read_to_string(&source_path)
+        .map_err(|e| FactoryError::IoError(format!("reading {}: {}", source_path, e)))?;
+    let incoming = consequence::parse_records(&data, Path::new(&source_path))?;
+
+    let memory_path = config.consequence_memory_path();
+    let mut records = consequence::load(&memory_path)?;
+    let report = consequence::merge_records(&mut records, incoming, strategy);
+    if report.imported_keys > 0 {
+        consequence::save(&memory_path, &records)?;
+    }
+
+    Ok(report)
+}
+
+/// IDs of the consequence records in `domain`, without their bodies.
+#[tauri::command]
+pub async fn get_consequence_memory_keys(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    domain: String,
+) -> Result<Vec<String>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetConsequenceMemoryKeys {
+        domain: domain.clone(),
+    })?;
+    let records = consequence::load(&config.consequence_memory_path())?;
+    Ok(consequence::keys(&records, &domain))
+}
+
+/// One consequence record, looked up by domain and ID.
+#[tauri::command]
+pub async fn get_consequence_memory_value(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    domain: String,
+    key: String,
+) -> Result<serde_json::Value, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let records = consequence::load(&config.consequence_memory_path())?;
+    consequence::find(&records, &domain, &key).cloned().ok_or_else(|| {
+        FactoryError::InvalidArgument(format!(
+            "no consequence record {:?} in domain {:?}",
+            key, domain
+        ))
+    })
+}
+
+/// The whole consequence memory, every domain at once.
+#[tauri::command]
+pub async fn get_consequence_memory_snapshot(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+) -> Result<ConsequenceMemorySnapshot, FactoryError> {
+    auth.authorize(&FactoryCommand::GetConsequenceMemorySnapshot)?;
+    let memory_path = config.consequence_memory_path();
+    let snapshot_at = chrono::Utc::now().to_rfc3339();
+    let records = consequence::load(&memory_path)?;
+    let size_bytes = file_size(&memory_path)?;
+
+    Ok(ConsequenceMemorySnapshot {
+        domains: consequence::by_domain(&records),
+        total_keys: records.len() as u64,
+        size_bytes,
+        snapshot_at,
+    })
+}
+
+/// Storage taken by consequence memory, overall and per domain.
+#[tauri::command]
+pub async fn get_consequence_memory_size(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+) -> Result<ConsequenceMemorySize, FactoryError> {
+    auth.authorize(&FactoryCommand::GetConsequenceMemorySize)?;
+    let memory_path = config.consequence_memory_path();
+    let domains = consequence::domain_sizes(&consequence::load(&memory_path)?);
+    Ok(ConsequenceMemorySize {
+        total_size_bytes: file_size(&memory_path)?,
+        largest_domain: domains.first().map(|d| d.domain.clone()),
+        domains,
+    })
+}
+
+/// How long ago each consequence memory domain last changed, oldest first.
+/// Empty if there is no memory file yet.
+#[tauri::command]
+pub async fn get_consequence_memory_age(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+) -> Result<Vec<ConsequenceMemoryAgeEntry>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetConsequenceMemoryAge)?;
+    let memory_path = config.consequence_memory_path();
+    let modified = match fs::metadata(&memory_path).and_then(|meta| meta.modified()) {
+        Ok(modified) => chrono::DateTime::<chrono::Utc>::from(modified),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(FactoryError::IoError(format!(
+                "reading {}: {}",
+                memory_path.display(),
+                e
+            )))
+        }
+    };
+    let records = consequence::load(&memory_path)?;
+    Ok(consequence::domain_ages(&records, modified, chrono::Utc::now()))
+}
+
+/// The consequence memory records `run_id` wrote, oldest first.
+#[tauri::command]
+pub async fn get_consequence_memory_changes(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<Vec<ConsequenceMemoryChange>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetConsequenceMemoryChanges {
+        run_id: run_id.clone(),
+    })?;
+    let records = consequence::load(&config.consequence_memory_path())?;
+    Ok(consequence::run_changes(&records, &run_id))
+}
+
+/// Size of the memory file; 0 if there isn't one yet.
+fn file_size(path: &Path) -> Result<u64, FactoryError> {
+    match fs::metadata(path) {
+        Ok(meta) => Ok(meta.len()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(FactoryError::IoError(format!("reading {}: {}", path.display(), e))),
+    }
+}
+
+/// Delete every consequence record in `domain`. Without `confirm` nothing
+/// is deleted and the report previews what would be. A confirmed purge is
+/// recorded in the audit log; the file is removed once no records remain.
+#[tauri::command]
+pub async fn purge_consequence_memory(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    domain: String,
+    confirm: bool,
+) -> Result<PurgeReport, FactoryError> {
+    let cmd = FactoryCommand::PurgeConsequenceMemory {
+        domain: domain.clone(),
+        confirm,
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    let memory_path = config.consequence_memory_path();
+    let mut records = consequence::load(&memory_path)?;
+    let (keys, size_bytes) = consequence::purge_domain(&mut records, &domain);
+
+    if confirm && keys > 0 {
+        if records.is_empty() {
+            fs::remove_file(&memory_path).map_err(|e| {
+                FactoryError::IoError(format!("removing {}: {}", memory_path.display(), e))
+            })?;
+        } else {
+            consequence::save(&memory_path, &records)?;
+        }
+        audit::record(
+            &config.audit_log_path(),
+            "purge_consequence_memory",
+            &new_trace_id(),
+            serde_json::json!({ "domain": domain, "keysDeleted": keys, "sizeBytes": size_bytes }),
+        )?;
+    }
+
+    Ok(PurgeReport {
+        domain,
+        keys_that_would_be_deleted: keys,
+        size_bytes,
+        deleted: confirm && keys > 0,
+    })
+}
+
+/// Expire `domain` `ttl_days` after its newest record; see
+/// `apply_consequence_memory_expiration`.
+#[tauri::command]
+pub async fn set_consequence_memory_ttl(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    domain: String,
+    ttl_days: u32,
+) -> Result<(), FactoryError> {
+    let cmd = FactoryCommand::SetConsequenceMemoryTtl {
+        domain: domain.clone(),
+        ttl_days,
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    if ttl_days == 0 {
+        return Err(FactoryError::InvalidArgument("ttl_days must be at least 1".into()));
+    }
+    let metadata_path = config.consequence_metadata_path();
+    let mut metadata = consequence::load_metadata(&metadata_path)?;
+    metadata.entry(domain.clone()).or_default().ttl_days = Some(ttl_days);
+    consequence::save_metadata(&metadata_path, &metadata)?;
+    audit::record(
+        &config.audit_log_path(),
+        "set_consequence_memory_ttl",
+        &new_trace_id(),
+        serde_json::json!({ "domain": domain, "ttlDays": ttl_days }),
+    )
+}
+
+/// Purge every domain whose TTL has passed.
+#[tauri::command]
+pub async fn apply_consequence_memory_expiration(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+) -> Result<ExpirationReport, FactoryError> {
+    let cmd = FactoryCommand::ApplyConsequenceMemoryExpiration;
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    expire_consequence_memory(&config)
+}
+
+/// What `apply_consequence_memory_expiration` does, without the session
+/// check, for running at startup. Each expired domain is purged as a
+/// confirmed `purge_consequence_memory` would be, and audit-logged once
+/// the purge is saved.
+pub fn expire_consequence_memory(config: &BridgeConfig) -> Result<ExpirationReport, FactoryError> {
+    let memory_path = config.consequence_memory_path();
+    let mut records = consequence::load(&memory_path)?;
+    let metadata = consequence::load_metadata(&config.consequence_metadata_path())?;
+    let expired = consequence::expired_domains(&records, &metadata, chrono::Utc::now());
+
+    let domains: std::collections::HashSet<&str> =
+        records.iter().filter_map(|r| r["domain"].as_str()).collect();
+    let mut report = ExpirationReport {
+        domains_checked: domains.len() as u32,
+        ..ExpirationReport::default()
+    };
+    if expired.is_empty() {
+        return Ok(report);
+    }
+
+    let purged: Vec<serde_json::Value> = expired
+        .iter()
+        .map(|domain| {
+            let (keys, size_bytes) = consequence::purge_domain(&mut records, domain);
+            report.domains_expired += 1;
+            report.keys_removed += keys;
+            serde_json::json!({ "domain": domain, "keysDeleted": keys, "sizeBytes": size_bytes })
+        })
+        .collect();
+    if records.is_empty() {
+        fs::remove_file(&memory_path).map_err(|e| {
+            FactoryError::IoError(format!("removing {}: {}", memory_path.display(), e))
+        })?;
+    } else {
+        consequence::save(&memory_path, &records)?;
+    }
+    for details in purged {
+        audit::record(
+            &config.audit_log_path(),
+            "expire_consequence_memory",
+            &new_trace_id(),
+            details,
+        )?;
+    }
+    Ok(report)
+}
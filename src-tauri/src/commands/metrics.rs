@@ -0,0 +1,117 @@
+//! Prometheus text exposition of bridge counters and run totals.
+//!
+//! Bridge counters are in-memory and reset when the app restarts; run
+//! totals are read from `ops/factory/runs` on every call.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tauri::State;
+
+use crate::auth::{AuthContext, Role};
+use crate::bridge::FactoryBridge;
+use crate::config::BridgeConfig;
+use crate::error::FactoryError;
+use crate::run_dir::{EVIDENCE_CHAIN_FILE, MANIFEST_FILE, STATE_FILE};
+
+#[derive(Default)]
+struct RunTotals {
+    by_status: BTreeMap<String, u64>,
+    active: u64,
+    evidence_by_run: BTreeMap<String, u64>,
+}
+
+#[tauri::command]
+pub async fn get_metrics(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+) -> Result<String, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let metrics = bridge
+        .lock()
+        .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?
+        .metrics();
+    let totals = scan_runs(&config.runs_root())?;
+    let events: BTreeMap<String, u64> = metrics.events_by_type().into_iter().collect();
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# HELP factory_runs_total Completed runs by final status.");
+    let _ = writeln!(out, "# TYPE factory_runs_total gauge");
+    for (status, count) in &totals.by_status {
+        let _ = writeln!(out, "factory_runs_total{{status=\"{}\"}} {}", escape(status), count);
+    }
+    let _ = writeln!(out, "# HELP factory_bridge_commands_total Commands written to the factory process.");
+    let _ = writeln!(out, "# TYPE factory_bridge_commands_total counter");
+    let _ = writeln!(out, "factory_bridge_commands_total {}", metrics.commands_sent());
+    let _ = writeln!(out, "# HELP factory_events_total Events received from the factory process.");
+    let _ = writeln!(out, "# TYPE factory_events_total counter");
+    for (event, count) in &events {
+        let _ = writeln!(out, "factory_events_total{{type=\"{}\"}} {}", escape(event), count);
+    }
+    let _ = writeln!(out, "# HELP factory_active_runs Runs with state on disk but no manifest yet.");
+    let _ = writeln!(out, "# TYPE factory_active_runs gauge");
+    let _ = writeln!(out, "factory_active_runs {}", totals.active);
+    let _ = writeln!(out, "# HELP factory_evidence_entries_total Evidence chain length per run.");
+    let _ = writeln!(out, "# TYPE factory_evidence_entries_total gauge");
+    for (run_id, count) in &totals.evidence_by_run {
+        let _ = writeln!(
+            out,
+            "factory_evidence_entries_total{{run_id=\"{}\"}} {}",
+            escape(run_id),
+            count
+        );
+    }
+    Ok(out)
+}
+
+fn scan_runs(runs_dir: &Path) -> Result<RunTotals, FactoryError> {
+    let mut totals = RunTotals::default();
+    if !runs_dir.exists() {
+        return Ok(totals);
+    }
+    let dirs = fs::read_dir(runs_dir)
+        .map_err(|e| FactoryError::IoError(format!("listing {}: {}", runs_dir.display(), e)))?;
+
+    for entry in dirs.flatten() {
+        if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let run_id = entry.file_name().to_string_lossy().to_string();
+        let manifest = fs::read_to_string(entry.path().join(MANIFEST_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok());
+
+        let evidence = match &manifest {
+            Some(m) => {
+                let status = m["status"].as_str().unwrap_or("unknown").to_string();
+                *totals.by_status.entry(status).or_insert(0) += 1;
+                m["evidenceChainLength"].as_u64()
+            }
+            None => {
+                if entry.path().join(STATE_FILE).exists() {
+                    totals.active += 1;
+                }
+                None
+            }
+        };
+        let evidence = evidence.unwrap_or_else(|| {
+            fs::read_to_string(entry.path().join(EVIDENCE_CHAIN_FILE))
+                .map(|data| data.lines().filter(|l| !l.trim().is_empty()).count() as u64)
+                .unwrap_or(0)
+        });
+        totals.evidence_by_run.insert(run_id, evidence);
+    }
+    Ok(totals)
+}
+
+/// Label values escape backslash, double quote, and newline.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -1,3 +1,9 @@
+pub mod bridge;
+pub mod evidence;
+pub mod export;
+pub mod health;
+pub mod memory;
+pub mod metrics;
 pub mod query;
 pub mod run;
 pub mod spec;
@@ -1,72 +1,689 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::fs;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::types::{RunHistoryEntry, RunStatus};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::auth::{AuthContext, Role};
+use crate::bridge::FactoryBridge;
+use crate::config::{BridgeConfig, RunHistorySource};
+use crate::enforcer::FactoryCommand;
+use crate::envelope::CommandEnvelope;
+use crate::error::FactoryError;
+use crate::events::{EVENT_RUN_HISTORY, EVENT_RUN_POLL};
+use crate::rate_limit::RateLimiter;
+use crate::run_dir::{
+    RunDir, ENVIRONMENT_FILE, EVIDENCE_CHAIN_FILE, EVIDENCE_CHECKPOINT_FILE, MANIFEST_FILE,
+    RUNNER_FILE, RUN_INPUTS_FILE, STATE_FILE,
+};
+use crate::types::{
+    AggregateProgress, ArtifactContent, ArtifactDiffResult, ArtifactInfo, ArtifactLocator,
+    CheckpointFile, CheckpointInfo, CorruptedRunInfo, CrossRunEvidenceMatch, EvidenceCountResult,
+    EvidenceEntry, EvidenceStats, GateAnomaly, GateCheck, GateHistoryEntry, GatePassRatePoint,
+    GateResult, GateSummary, LineDiffSummary, ManifestFile, RunCountSummary, RunErrorEntry,
+    RunEvidenceTypes, RunHistoryEntry, RunHistoryMatch, RunInputs, RunPhase, RunStateFile,
+    RunStatus, RunnerComparison, RunnerInfo, StageInfo, StageLogEntry, TimelineEvent,
+    WorkerCountInfo, WorkerStageInterval, WorkerTimeline,
+};
+
+/// How long to wait for the factory to answer a query routed over the bridge.
+const BRIDGE_RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upper bound for the polling interval once it has backed off.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Stages of the default pipeline (`PipelineConfig.defaultFactory1`), in
+/// execution order. Stages from a custom pipeline are listed after these.
+pub(crate) const STAGE_ORDER: &[&str] = &[
+    "spec_validation",
+    "planning",
+    "building",
+    "assembly",
+    "testing",
+    "ui_inspection",
+    "security_audit",
+    "release_package",
+    "run_audit",
+];
+
+/// How often `stream_worker_log` checks the chain for new entries, and
+/// how far that backs off while the worker is quiet.
+const WORKER_LOG_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_WORKER_LOG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Most runs `get_gate_pass_rate_trend` will look back over.
+const MAX_TREND_RUNS: u32 = 100;
+
+/// Most runs `get_gate_history` will look back over.
+const MAX_GATE_HISTORY_RUNS: u32 = 50;
+
+/// Earlier runs `get_run_anomalies` compares a run against, and the
+/// `|z|` above which a measurement is flagged when the caller sets none.
+const ANOMALY_HISTORY_RUNS: usize = 10;
+const DEFAULT_ANOMALY_Z_SCORE: f64 = 2.0;
+
+/// Most matches `search_all_runs_evidence` returns, and how long it may
+/// spend scanning before giving up.
+const MAX_EVIDENCE_SEARCH_RESULTS: u32 = 50;
+const EVIDENCE_SEARCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Matches `search_run_history` returns unless told otherwise.
+const DEFAULT_HISTORY_SEARCH_RESULTS: u32 = 20;
+
+/// How much of an artifact `get_artifact_content` returns when the caller
+/// doesn't say.
+const DEFAULT_ARTIFACT_CONTENT_BYTES: u64 = 512 * 1024;
+
+/// How far into an artifact to look for a NUL byte when deciding whether
+/// it is binary.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// Largest artifact, in bytes, that `get_artifact_diff` diffs line by line.
+const MAX_LINE_DIFF_BYTES: u64 = 100 * 1024;
+
+/// Most milestones `get_run_timeline` returns; beyond this only the first
+/// and last halves are kept.
+const MAX_TIMELINE_EVENTS: usize = 1000;
+
+/// How often `tail_evidence` re-reads the chain when filesystem
+/// notifications are unavailable. With notifications it is only a
+/// safety net for missed events.
+const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Entries `get_evidence_from` returns when the caller sets no limit.
+const DEFAULT_EVIDENCE_PAGE: u32 = 100;
+
+/// Entries `get_run_logs` returns when the caller sets no limit.
+const DEFAULT_RUN_LOG_LIMIT: u32 = 1000;
+
+/// Most entries `get_run_errors` returns.
+const MAX_RUN_ERRORS: usize = 200;
+
+/// Log severities, least severe first.
+const LOG_LEVELS: &[&str] = &["debug", "info", "warn", "error"];
+
+/// A worker counts as active if it reported progress this recently.
+const ACTIVE_WORKER_WINDOW_SECS: i64 = 30;
+
+/// `get_worker_count` is coarser: a worker is still counted as active if it
+/// is unfinished and was seen this recently.
+const WORKER_COUNT_WINDOW_SECS: i64 = 60;
 
 #[tauri::command]
-pub async fn get_run_status(run_id: String) -> Result<RunStatus, String> {
-    let state_path = format!("ops/factory/runs/{}/run-state.json", run_id);
-    let data =
-        fs::read_to_string(&state_path).map_err(|e| format!("read error: {}", e))?;
-    let parsed: serde_json::Value =
-        serde_json::from_str(&data).map_err(|e| format!("parse error: {}", e))?;
+pub async fn get_run_status(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<RunStatus, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    read_run_status(&config.read_run_dir(&run_id)?)
+}
+
+fn read_run_status(run_dir: &RunDir) -> Result<RunStatus, FactoryError> {
+    let state: RunStateFile = run_dir.read_typed(STATE_FILE)?;
+    let gates_passed = state.gate_results.iter().filter(|g| g.passed).count() as u32;
 
     Ok(RunStatus {
-        run_id: parsed["runId"].as_str().unwrap_or("").to_string(),
-        status: parsed["status"].as_str().unwrap_or("unknown").to_string(),
-        current_stage: parsed["currentStage"].as_str().unwrap_or("").to_string(),
-        gates_passed: parsed["gateResults"]
-            .as_array()
-            .map(|a| a.iter().filter(|g| g["passed"].as_bool() == Some(true)).count() as u32)
-            .unwrap_or(0),
-        gates_failed: parsed["gateResults"]
-            .as_array()
-            .map(|a| a.iter().filter(|g| g["passed"].as_bool() == Some(false)).count() as u32)
-            .unwrap_or(0),
+        run_id: state.run_id,
+        status: state.status,
+        current_stage: state.current_stage,
+        gates_passed,
+        gates_failed: state.gate_results.len() as u32 - gates_passed,
         duration_ms: 0,
     })
 }
 
+/// Polling fallback for environments where event subscriptions are
+/// unreliable. The interval doubles (up to 30 s) while nothing changes and
+/// resets when it does; every poll is emitted as `factory://run-poll` so
+/// the frontend can still show intermediate state. Returns the last status
+/// seen once the run is terminal or `max_wait_ms` has elapsed.
+#[tauri::command]
+pub async fn poll_run_until_complete(
+    app: AppHandle,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    poll_interval_ms: u64,
+    max_wait_ms: u64,
+) -> Result<RunStatus, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    let base_interval = Duration::from_millis(poll_interval_ms.max(1)).min(MAX_POLL_INTERVAL);
+    let deadline = Instant::now() + Duration::from_millis(max_wait_ms);
+    let mut interval = base_interval;
+    let mut last: Option<RunStatus> = None;
+
+    loop {
+        // The factory writes run-state.json shortly after the run starts;
+        // until then there is nothing to report.
+        if run_dir.exists(STATE_FILE) {
+            let status = read_run_status(&run_dir)?;
+            if is_terminal(&status.status) {
+                let _ = app.emit(EVENT_RUN_POLL, &status);
+                return Ok(status);
+            }
+            interval = if last.as_ref() == Some(&status) {
+                (interval * 2).min(MAX_POLL_INTERVAL)
+            } else {
+                base_interval
+            };
+            let _ = app.emit(EVENT_RUN_POLL, &status);
+            last = Some(status);
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return last.ok_or_else(|| {
+                FactoryError::Timeout(format!(
+                    "run {} wrote no state within {} ms",
+                    run_id, max_wait_ms
+                ))
+            });
+        }
+        tokio::time::sleep(with_jitter(interval).min(remaining)).await;
+    }
+}
+
+/// Spread polls by up to ±10% so concurrent pollers don't line up.
+fn with_jitter(interval: Duration) -> Duration {
+    let spread = interval.as_millis() as u64 / 5;
+    if spread == 0 {
+        return interval;
+    }
+    let offset = (uuid::Uuid::new_v4().as_u128() % spread as u128) as u64;
+    interval + Duration::from_millis(offset) - Duration::from_millis(spread / 2)
+}
+
+/// Completed runs and, unless `include_active` is `false`, runs still in
+/// progress: those with a state file but no manifest yet. Those are
+/// reported as `running` with a zero duration.
 #[tauri::command]
-pub async fn get_run_history() -> Result<Vec<RunHistoryEntry>, String> {
-    let runs_dir = Path::new("ops/factory/runs");
+pub async fn get_run_history(
+    auth: State<'_, AuthContext>,
+    limiter: State<'_, RateLimiter>,
+    config: State<'_, BridgeConfig>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+    include_active: Option<bool>,
+) -> Result<Vec<RunHistoryEntry>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    run_history(&limiter, &config, &bridge, include_active.unwrap_or(true)).await
+}
+
+/// Run history from wherever `run_history_source` says.
+async fn run_history(
+    limiter: &RateLimiter,
+    config: &BridgeConfig,
+    bridge: &Mutex<FactoryBridge>,
+    include_active: bool,
+) -> Result<Vec<RunHistoryEntry>, FactoryError> {
+    match config.run_history_source {
+        RunHistorySource::FileSystem => read_run_history(&config.runs_root(), include_active),
+        RunHistorySource::Bridge => {
+            limiter.check(&FactoryCommand::GetRunHistory { include_active })?;
+            run_history_via_bridge(config, bridge, include_active).await
+        }
+    }
+}
+
+pub(crate) fn read_run_history(
+    runs_dir: &Path,
+    include_active: bool,
+) -> Result<Vec<RunHistoryEntry>, FactoryError> {
     if !runs_dir.exists() {
         return Ok(vec![]);
     }
 
     let mut entries = Vec::new();
-    let dirs = fs::read_dir(runs_dir).map_err(|e| format!("read dir error: {}", e))?;
+    let dirs = fs::read_dir(runs_dir)
+        .map_err(|e| FactoryError::IoError(format!("listing {}: {}", runs_dir.display(), e)))?;
 
     for entry in dirs.flatten() {
         if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
             continue;
         }
-        let manifest_path = entry.path().join("manifest.json");
-        if manifest_path.exists() {
-            if let Ok(data) = fs::read_to_string(&manifest_path) {
-                if let Ok(m) = serde_json::from_str::<serde_json::Value>(&data) {
-                    entries.push(RunHistoryEntry {
-                        run_id: m["runId"].as_str().unwrap_or("").to_string(),
-                        status: m["status"].as_str().unwrap_or("unknown").to_string(),
-                        started_at: m["startedAt"].as_str().unwrap_or("").to_string(),
-                        duration_ms: m["durationMs"].as_u64().unwrap_or(0),
-                    });
+        let manifest_path = entry.path().join(MANIFEST_FILE);
+        if !manifest_path.exists() {
+            let state_path = entry.path().join(STATE_FILE);
+            if include_active && state_path.exists() {
+                match read_manifest::<serde_json::Value>(&state_path) {
+                    Ok(state) => entries.push(
+                        active_history_entry(&entry.path(), &state)
+                            .with_inputs(history_inputs(&entry.path())),
+                    ),
+                    Err(error) => tracing::warn!(
+                        run_dir = %entry.path().display(),
+                        %error,
+                        "skipping active run with unreadable state"
+                    ),
                 }
             }
+            continue;
+        }
+        match read_manifest::<ManifestFile>(&manifest_path) {
+            Ok(m) => {
+                let inputs = history_inputs(&entry.path());
+                entries.push(RunHistoryEntry::from(m).with_inputs(inputs));
+            }
+            Err(error) => tracing::warn!(
+                run_dir = %entry.path().display(),
+                %error,
+                "skipping run with unreadable manifest"
+            ),
+        }
+    }
+
+    entries.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(entries)
+}
+
+/// A history entry for a run that has no manifest yet, from its state
+/// file. It started when its earliest stage did.
+fn active_history_entry(dir: &Path, state: &serde_json::Value) -> RunHistoryEntry {
+    let run_id = match state["runId"].as_str() {
+        Some(id) => id.to_string(),
+        None => dir.file_name().unwrap_or_default().to_string_lossy().to_string(),
+    };
+    let started_at = state["timestamps"]
+        .as_object()
+        .and_then(|stages| stages.values().filter_map(|t| t["start"].as_str()).min())
+        .unwrap_or("")
+        .to_string();
+    RunHistoryEntry {
+        run_id,
+        status: "running".to_string(),
+        started_at,
+        duration_ms: 0,
+        spec_path: None,
+        blueprint_path: None,
+    }
+}
+
+/// The run's `run-inputs.json`. History is best-effort, so an unreadable
+/// one counts as missing.
+fn history_inputs(dir: &Path) -> Option<RunInputs> {
+    read_manifest(&dir.join(RUN_INPUTS_FILE)).ok()
+}
+
+fn read_manifest<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+/// IDs of the runs still in progress, in no particular order.
+#[tauri::command]
+pub async fn get_active_run_ids(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+) -> Result<Vec<String>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    active_run_ids(&config.runs_root())
+}
+
+/// Only the `status` field of a state file, so the rest is skipped rather
+/// than deserialized.
+#[derive(Deserialize)]
+struct StatusOnly {
+    status: String,
+}
+
+fn active_run_ids(runs_dir: &Path) -> Result<Vec<String>, FactoryError> {
+    if !runs_dir.exists() {
+        return Ok(vec![]);
+    }
+    let dirs = fs::read_dir(runs_dir)
+        .map_err(|e| FactoryError::IoError(format!("listing {}: {}", runs_dir.display(), e)))?;
+
+    let mut active = Vec::new();
+    for entry in dirs.flatten() {
+        let Ok(data) = fs::read_to_string(entry.path().join(STATE_FILE)) else {
+            continue;
+        };
+        let Ok(state) = serde_json::from_str::<StatusOnly>(&data) else {
+            continue;
+        };
+        if RunPhase::try_from(state.status.as_str()).is_ok_and(|phase| !phase.is_terminal()) {
+            active.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    Ok(active)
+}
+
+/// How much of `run-state.json` `get_run_count` reads looking for the
+/// `status` field. The factory writes it near the top.
+const RUN_STATUS_PREFIX_BYTES: u64 = 1024;
+
+/// Runs under the runs root, by status. Only the start of each state file
+/// is read, for dashboards that want the numbers without the history;
+/// a run whose status isn't in that prefix has its state file parsed in
+/// full, and one without a readable status counts as `unknown`.
+#[tauri::command]
+pub async fn get_run_count(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+) -> Result<RunCountSummary, FactoryError> {
+    auth.authorize(&FactoryCommand::GetRunCount)?;
+    run_count(&config.runs_root())
+}
+
+fn run_count(runs_dir: &Path) -> Result<RunCountSummary, FactoryError> {
+    let mut summary = RunCountSummary::default();
+    if !runs_dir.exists() {
+        return Ok(summary);
+    }
+    let dirs = fs::read_dir(runs_dir)
+        .map_err(|e| FactoryError::IoError(format!("listing {}: {}", runs_dir.display(), e)))?;
+
+    for entry in dirs.flatten() {
+        let state_path = entry.path().join(STATE_FILE);
+        if !state_path.is_file() {
+            continue;
+        }
+        let status = run_status_field(&state_path).unwrap_or_else(|| "unknown".to_string());
+        summary.total += 1;
+        *summary.by_status.entry(status).or_default() += 1;
+    }
+    Ok(summary)
+}
+
+fn run_status_field(state_path: &Path) -> Option<String> {
+    static STATUS_RE: OnceLock<Regex> = OnceLock::new();
+    let status_re =
+        STATUS_RE.get_or_init(|| Regex::new(r#""status"\s*:\s*"([^"\\]*)""#).unwrap());
+
+    let mut prefix = Vec::new();
+    fs::File::open(state_path)
+        .ok()?
+        .take(RUN_STATUS_PREFIX_BYTES)
+        .read_to_end(&mut prefix)
+        .ok()?;
+    if let Some(c) = status_re.captures(&String::from_utf8_lossy(&prefix)) {
+        return Some(c[1].to_string());
+    }
+    let state: StatusOnly = serde_json::from_str(&fs::read_to_string(state_path).ok()?).ok()?;
+    Some(state.status)
+}
+
+/// Whether a run-state `status` is final. Unknown statuses are not.
+fn is_terminal(status: &str) -> bool {
+    RunPhase::try_from(status).is_ok_and(RunPhase::is_terminal)
+}
+
+/// Status of the most recently started run, or `None` when there are no
+/// runs. Runs are compared by file modification time (the manifest, or the
+/// state file for a run still in progress), so only the winner is parsed.
+#[tauri::command]
+pub async fn get_latest_run(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+) -> Result<Option<RunStatus>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetLatestRun)?;
+    let runs_root = config.runs_root();
+    match latest_run_id(&runs_root)? {
+        Some(run_id) => read_run_status(&RunDir::for_read(&runs_root, &run_id)?).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn latest_run_id(runs_dir: &Path) -> Result<Option<String>, FactoryError> {
+    if !runs_dir.exists() {
+        return Ok(None);
+    }
+    let dirs = fs::read_dir(runs_dir)
+        .map_err(|e| FactoryError::IoError(format!("listing {}: {}", runs_dir.display(), e)))?;
+
+    let mut latest: Option<(SystemTime, String)> = None;
+    for entry in dirs.flatten() {
+        let dir = entry.path();
+        if !dir.join(STATE_FILE).is_file() {
+            continue;
+        }
+        let modified = [MANIFEST_FILE, STATE_FILE]
+            .iter()
+            .find_map(|f| fs::metadata(dir.join(f)).and_then(|m| m.modified()).ok());
+        let file_name = entry.file_name();
+        let (Some(modified), Some(name)) = (modified, file_name.to_str()) else {
+            continue;
+        };
+        if !matches!(&latest, Some((t, _)) if *t >= modified) {
+            latest = Some((modified, name.to_string()));
+        }
+    }
+    Ok(latest.map(|(_, run_id)| run_id))
+}
+
+/// Runs whose manifest exists but can't be read or parsed. History skips
+/// these; this lists them so they can be inspected and cleaned up.
+#[tauri::command]
+pub async fn find_corrupted_runs(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+) -> Result<Vec<CorruptedRunInfo>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    corrupted_runs(&config.runs_root())
+}
+
+fn corrupted_runs(runs_dir: &Path) -> Result<Vec<CorruptedRunInfo>, FactoryError> {
+    if !runs_dir.exists() {
+        return Ok(vec![]);
+    }
+    let dirs = fs::read_dir(runs_dir)
+        .map_err(|e| FactoryError::IoError(format!("listing {}: {}", runs_dir.display(), e)))?;
+
+    let mut corrupted = Vec::new();
+    for entry in dirs.flatten() {
+        if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let manifest_path = entry.path().join(MANIFEST_FILE);
+        if !manifest_path.exists() {
+            continue;
+        }
+        if let Err(error) = read_manifest::<ManifestFile>(&manifest_path) {
+            corrupted.push(CorruptedRunInfo {
+                run_id: entry.file_name().to_string_lossy().to_string(),
+                path: manifest_path.display().to_string(),
+                error,
+            });
         }
     }
+    corrupted.sort_by(|a, b| a.run_id.cmp(&b.run_id));
+    Ok(corrupted)
+}
+
+async fn run_history_via_bridge(
+    config: &BridgeConfig,
+    bridge: &Mutex<FactoryBridge>,
+    include_active: bool,
+) -> Result<Vec<RunHistoryEntry>, FactoryError> {
+    let response = {
+        let b = bridge
+            .lock()
+            .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+        let Some(run_id) = b.active_run_ids().into_iter().next() else {
+            return read_run_history(&config.runs_root(), include_active);
+        };
+        let response = b.wait_for_event(EVENT_RUN_HISTORY);
+        let envelope = CommandEnvelope::traced(
+            "get_run_history",
+            FactoryCommand::GetRunHistory { include_active },
+        );
+        b.send_envelope(&run_id, &envelope)?;
+        response
+    };
 
+    let data = response.wait(BRIDGE_RESPONSE_TIMEOUT).await?;
+
+    let runs_root = config.runs_root();
+    let mut entries: Vec<RunHistoryEntry> = data["runs"]
+        .as_array()
+        .map(|runs| {
+            runs.iter()
+                .map(history_entry)
+                .map(|e| {
+                    let inputs = history_inputs(&runs_root.join(&e.run_id));
+                    e.with_inputs(inputs)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
     entries.sort_by(|a, b| b.started_at.cmp(&a.started_at));
     Ok(entries)
 }
 
+/// Map a manifest (or a bridge run-history item, which has the same
+/// shape) to a history entry. Neither records the spec or blueprint;
+/// those come from the run's `run-inputs.json`.
+fn history_entry(m: &serde_json::Value) -> RunHistoryEntry {
+    RunHistoryEntry {
+        run_id: m["runId"].as_str().unwrap_or("").to_string(),
+        status: m["status"].as_str().unwrap_or("unknown").to_string(),
+        started_at: m["startedAt"].as_str().unwrap_or("").to_string(),
+        duration_ms: m["durationMs"].as_u64().unwrap_or(0),
+        spec_path: None,
+        blueprint_path: None,
+    }
+}
+
+#[tauri::command]
+pub async fn get_run_history_by_spec(
+    auth: State<'_, AuthContext>,
+    limiter: State<'_, RateLimiter>,
+    config: State<'_, BridgeConfig>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+    spec_path: String,
+) -> Result<Vec<RunHistoryEntry>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    Ok(run_history(&limiter, &config, &bridge, false)
+        .await?
+        .into_iter()
+        .filter(|e| e.spec_path.as_deref() == Some(spec_path.as_str()))
+        .collect())
+}
+
+/// Finished runs whose history entry or manifest contains `query`,
+/// ignoring case, newest first. History comes from `run_history_source`,
+/// as for `get_run_history`. `fields` names fields in snake_case, such as
+/// `spec_path` or `tags`; without it every top-level field is searched.
+/// An array or object field matches if any value inside it does.
+#[tauri::command]
+pub async fn search_run_history(
+    auth: State<'_, AuthContext>,
+    limiter: State<'_, RateLimiter>,
+    config: State<'_, BridgeConfig>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+    query: String,
+    fields: Option<Vec<String>>,
+    max_results: Option<u32>,
+) -> Result<Vec<RunHistoryMatch>, FactoryError> {
+    auth.authorize(&FactoryCommand::SearchRunHistory {
+        query: query.clone(),
+        fields: fields.clone(),
+        max_results,
+    })?;
+    if query.is_empty() {
+        return Err(FactoryError::InvalidArgument("search query is empty".into()));
+    }
+    let history = run_history(&limiter, &config, &bridge, false).await?;
+    Ok(search_history(
+        &config.runs_root(),
+        history,
+        &query,
+        fields.as_deref(),
+        max_results.unwrap_or(DEFAULT_HISTORY_SEARCH_RESULTS) as usize,
+    ))
+}
+
+/// Where a run has both, a history entry field wins over the manifest
+/// field of the same name.
+fn search_history(
+    runs_root: &Path,
+    history: Vec<RunHistoryEntry>,
+    query: &str,
+    fields: Option<&[String]>,
+    max_results: usize,
+) -> Vec<RunHistoryMatch> {
+    let query = query.to_lowercase();
+    let mut matches = Vec::new();
+    for entry in history {
+        if matches.len() >= max_results {
+            break;
+        }
+        let manifest = RunDir::for_read(runs_root, &entry.run_id)
+            .and_then(|d| d.read_json(MANIFEST_FILE))
+            .unwrap_or_default();
+        let mut searched: BTreeMap<String, serde_json::Value> = manifest
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (snake_case(key), value.clone()))
+            .collect();
+        if let Ok(serde_json::Value::Object(own)) = serde_json::to_value(&entry) {
+            searched.extend(own);
+        }
+        let matched_fields: Vec<String> = searched
+            .into_iter()
+            .filter(|(name, _)| match fields {
+                Some(fields) => fields.contains(name),
+                None => true,
+            })
+            .filter(|(_, value)| contains_ignoring_case(value, &query))
+            .map(|(name, _)| name)
+            .collect();
+        if !matched_fields.is_empty() {
+            matches.push(RunHistoryMatch {
+                run_id: entry.run_id.clone(),
+                entry,
+                matched_fields,
+            });
+        }
+    }
+    matches
+}
+
+/// `specPath` -> `spec_path`.
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `query` must already be lowercase.
+fn contains_ignoring_case(value: &serde_json::Value, query: &str) -> bool {
+    match value {
+        serde_json::Value::String(s) => s.to_lowercase().contains(query),
+        serde_json::Value::Array(items) => items.iter().any(|v| contains_ignoring_case(v, query)),
+        serde_json::Value::Object(fields) => {
+            fields.values().any(|v| contains_ignoring_case(v, query))
+        }
+        serde_json::Value::Null => false,
+        other => other.to_string().contains(query),
+    }
+}
+
 #[tauri::command]
-pub async fn get_gate_results(run_id: String) -> Result<Vec<serde_json::Value>, String> {
-    let state_path = format!("ops/factory/runs/{}/run-state.json", run_id);
-    let data =
-        fs::read_to_string(&state_path).map_err(|e| format!("read error: {}", e))?;
-    let parsed: serde_json::Value =
-        serde_json::from_str(&data).map_err(|e| format!("parse error: {}", e))?;
+pub async fn get_gate_results(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<Vec<serde_json::Value>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let parsed = config.read_run_dir(&run_id)?.read_json(STATE_FILE)?;
 
     Ok(parsed["gateResults"]
         .as_array()
@@ -74,17 +691,322 @@ pub async fn get_gate_results(run_id: String) -> Result<Vec<serde_json::Value>,
         .unwrap_or_default())
 }
 
+/// Counts from `run-state.json` without the per-check detail that
+/// `get_gate_results` returns.
+#[tauri::command]
+pub async fn get_gate_summary(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<GateSummary, FactoryError> {
+    auth.authorize(&FactoryCommand::GetGateSummary {
+        run_id: run_id.clone(),
+    })?;
+    let state: RunStateFile = config.read_run_dir(&run_id)?.read_typed(STATE_FILE)?;
+    Ok(gate_summary(&state))
+}
+
+/// A failed gate without a `stage` of its own is blocking the run's
+/// current stage.
+fn gate_summary(state: &RunStateFile) -> GateSummary {
+    let gates = &state.gate_results;
+    let skipped = gates.iter().filter(|g| g.skipped).count() as u32;
+    let passed = gates.iter().filter(|g| !g.skipped && g.passed).count() as u32;
+    let failed = gates.len() as u32 - skipped - passed;
+    let first_failure = gates.iter().find(|g| !g.skipped && !g.passed);
+
+    GateSummary {
+        total_gates: gates.len() as u32,
+        passed,
+        failed,
+        skipped,
+        pass_rate: if passed + failed == 0 {
+            0.0
+        } else {
+            passed as f32 / (passed + failed) as f32
+        },
+        first_failure: first_failure.map(|g| g.gate_id.clone()),
+        blocking_stage: first_failure
+            .map(|g| g.stage.clone().unwrap_or_else(|| state.current_stage.clone())),
+    }
+}
+
+/// The gates in `run-state.json` whose `stage` is `stage`, in file order.
+/// A gate without a recorded stage belongs to none.
+#[tauri::command]
+pub async fn get_stage_gates(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    stage: String,
+) -> Result<Vec<GateResult>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetStageGates {
+        run_id: run_id.clone(),
+        stage: stage.clone(),
+    })?;
+    let state = config.read_run_dir(&run_id)?.read_json(STATE_FILE)?;
+    Ok(stage_gates(&state, &stage))
+}
+
+/// The distinct stages that have gate results, in the order they first
+/// appear in `run-state.json`.
+#[tauri::command]
+pub async fn get_gate_stages(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<Vec<String>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetGateStages {
+        run_id: run_id.clone(),
+    })?;
+    let state = config.read_run_dir(&run_id)?.read_json(STATE_FILE)?;
+    Ok(gate_stages(&state))
+}
+
+fn stage_gates(state: &serde_json::Value, stage: &str) -> Vec<GateResult> {
+    state["gateResults"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|g| g["stage"].as_str() == Some(stage))
+        .map(gate_result)
+        .collect()
+}
+
+fn gate_stages(state: &serde_json::Value) -> Vec<String> {
+    let mut stages: Vec<String> = Vec::new();
+    for gate in state["gateResults"].as_array().map(Vec::as_slice).unwrap_or_default() {
+        if let Some(stage) = gate["stage"].as_str() {
+            if !stages.iter().any(|s| s == stage) {
+                stages.push(stage.to_string());
+            }
+        }
+    }
+    stages
+}
+
+/// One point per recent run that evaluated `gate_id`, oldest first. Runs
+/// that never reached the gate are left out. `last_n_runs` is capped at 100.
+#[tauri::command]
+pub async fn get_gate_pass_rate_trend(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    gate_id: String,
+    last_n_runs: u32,
+) -> Result<Vec<GatePassRatePoint>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let runs_root = config.runs_root();
+    let mut history = read_run_history(&runs_root, false)?;
+    history.truncate(last_n_runs.min(MAX_TREND_RUNS) as usize);
+    Ok(gate_trend(&runs_root, &history, &gate_id))
+}
+
+/// `history` is newest first, as `read_run_history` returns it.
+fn gate_trend(
+    runs_root: &Path,
+    history: &[RunHistoryEntry],
+    gate_id: &str,
+) -> Vec<GatePassRatePoint> {
+    history
+        .iter()
+        .rev()
+        .filter_map(|run| {
+            let state = RunDir::for_read(runs_root, &run.run_id).ok()?.read_json(STATE_FILE).ok()?;
+            // A stage retried after a failure leaves several results for
+            // the same gate; the last one is the verdict.
+            let gate = state["gateResults"]
+                .as_array()?
+                .iter()
+                .rev()
+                .find(|g| g["gateId"].as_str() == Some(gate_id))?;
+            let checks = gate["checks"].as_array().map(Vec::as_slice).unwrap_or_default();
+            Some(GatePassRatePoint {
+                run_id: run.run_id.clone(),
+                started_at: run.started_at.clone(),
+                passed: gate["passed"].as_bool().unwrap_or(false),
+                checks_passed: checks
+                    .iter()
+                    .filter(|c| c["passed"].as_bool() == Some(true))
+                    .count() as u32,
+                checks_total: checks.len() as u32,
+            })
+        })
+        .collect()
+}
+
+/// `gate_id`'s result in each of the last `last_n_runs` runs (at most 50),
+/// newest first. Unlike the pass-rate trend, runs that never reached the
+/// gate are kept, with no result.
+#[tauri::command]
+pub async fn get_gate_history(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    gate_id: String,
+    last_n_runs: u32,
+) -> Result<Vec<GateHistoryEntry>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetGateHistory {
+        gate_id: gate_id.clone(),
+        last_n_runs,
+    })?;
+    let runs_root = config.runs_root();
+    let mut history = read_run_history(&runs_root, false)?;
+    history.truncate(last_n_runs.min(MAX_GATE_HISTORY_RUNS) as usize);
+    Ok(gate_history(&runs_root, &history, &gate_id))
+}
+
+/// Gate check measurements in `run_id` that sit more than
+/// `z_score_threshold` standard deviations from the same check's mean over
+/// the previous `ANOMALY_HISTORY_RUNS` runs. A check needs two earlier
+/// measurements that aren't all equal to be judged.
+#[tauri::command]
+pub async fn get_run_anomalies(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    z_score_threshold: Option<f64>,
+) -> Result<Vec<GateAnomaly>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetRunAnomalies {
+        run_id: run_id.clone(),
+        z_score_threshold,
+    })?;
+    let threshold = z_score_threshold.unwrap_or(DEFAULT_ANOMALY_Z_SCORE);
+    if !threshold.is_finite() || threshold <= 0.0 {
+        return Err(FactoryError::InvalidArgument(format!(
+            "z_score_threshold must be a positive number, got {}",
+            threshold
+        )));
+    }
+    let state = config.read_run_dir(&run_id)?.read_json(STATE_FILE)?;
+    let current: Vec<GateResult> = state["gateResults"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(gate_result)
+        .collect();
+
+    let runs_root = config.runs_root();
+    let mut earlier = read_run_history(&runs_root, false)?;
+    earlier.retain(|run| run.run_id != run_id);
+    earlier.truncate(ANOMALY_HISTORY_RUNS);
+    let history: HashMap<String, Vec<GateHistoryEntry>> = current
+        .iter()
+        .map(|gate| (gate.gate_id.clone(), gate_history(&runs_root, &earlier, &gate.gate_id)))
+        .collect();
+    Ok(gate_anomalies(&current, &history, threshold))
+}
+
+/// `history` holds each gate's earlier results, as `gate_history` returns
+/// them. The standard deviation is the population one.
+fn gate_anomalies(
+    current: &[GateResult],
+    history: &HashMap<String, Vec<GateHistoryEntry>>,
+    threshold: f64,
+) -> Vec<GateAnomaly> {
+    let mut anomalies = Vec::new();
+    for gate in current {
+        let earlier = history.get(&gate.gate_id).map(Vec::as_slice).unwrap_or_default();
+        for check in &gate.checks {
+            let past: Vec<f64> = earlier
+                .iter()
+                .filter_map(|run| run.gate_result.as_ref())
+                .filter_map(|g| g.checks.iter().find(|c| c.name == check.name))
+                .map(|c| c.measured)
+                .collect();
+            if past.len() < 2 {
+                continue;
+            }
+            let mean = past.iter().sum::<f64>() / past.len() as f64;
+            let variance = past.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / past.len() as f64;
+            let stddev = variance.sqrt();
+            if stddev == 0.0 {
+                continue;
+            }
+            let z_score = (check.measured - mean) / stddev;
+            if z_score.abs() > threshold {
+                anomalies.push(GateAnomaly {
+                    gate_id: gate.gate_id.clone(),
+                    check_name: check.name.clone(),
+                    measured: check.measured,
+                    historical_mean: mean,
+                    historical_stddev: stddev,
+                    z_score,
+                });
+            }
+        }
+    }
+    anomalies
+}
+
+fn gate_history(
+    runs_root: &Path,
+    history: &[RunHistoryEntry],
+    gate_id: &str,
+) -> Vec<GateHistoryEntry> {
+    history
+        .iter()
+        .map(|run| {
+            let state = RunDir::for_read(runs_root, &run.run_id)
+                .and_then(|dir| dir.read_json(STATE_FILE))
+                .unwrap_or_default();
+            // As in the trend, the last result for a retried gate wins.
+            let gate_result = state["gateResults"]
+                .as_array()
+                .and_then(|gates| {
+                    gates.iter().rev().find(|g| g["gateId"].as_str() == Some(gate_id))
+                })
+                .map(gate_result);
+            GateHistoryEntry {
+                run_id: run.run_id.clone(),
+                started_at: run.started_at.clone(),
+                gate_result,
+            }
+        })
+        .collect()
+}
+
+/// A `gateResults` element of run-state.json, which is camelCase and may
+/// omit fields an older factory didn't write.
+pub(crate) fn gate_result(gate: &serde_json::Value) -> GateResult {
+    GateResult {
+        gate_id: gate["gateId"].as_str().unwrap_or_default().to_string(),
+        passed: gate["passed"].as_bool().unwrap_or(false),
+        timestamp: gate["timestamp"].as_str().unwrap_or_default().to_string(),
+        stage: gate["stage"].as_str().map(str::to_string),
+        checks: gate["checks"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|c| GateCheck {
+                name: c["name"].as_str().unwrap_or_default().to_string(),
+                passed: c["passed"].as_bool().unwrap_or(false),
+                measured: c["measured"].as_f64().unwrap_or(0.0),
+                threshold: c["threshold"].as_f64().unwrap_or(0.0),
+            })
+            .collect(),
+    }
+}
+
+/// Entries with `from <= seq <= to`. Pass `to = u64::MAX` for everything
+/// from `from` to the end of the chain. JavaScript numbers can't carry
+/// `u64::MAX` exactly, so the UI should use `get_evidence_from` instead.
 #[tauri::command]
 pub async fn get_evidence_range(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
     run_id: String,
     from: u64,
     to: u64,
-) -> Result<Vec<serde_json::Value>, String> {
-    let chain_path = format!("ops/factory/runs/{}/evidence-chain.ndjson", run_id);
-    let data =
-        fs::read_to_string(&chain_path).map_err(|e| format!("read error: {}", e))?;
+) -> Result<Vec<serde_json::Value>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let data = config.read_run_dir(&run_id)?.read_to_string(EVIDENCE_CHAIN_FILE)?;
+    Ok(evidence_range(&data, from, to))
+}
 
-    let entries: Vec<serde_json::Value> = data
+fn evidence_range(chain: &str, from: u64, to: u64) -> Vec<serde_json::Value> {
+    chain
         .lines()
         .filter(|l| !l.trim().is_empty())
         .filter_map(|l| serde_json::from_str(l).ok())
@@ -92,7 +1014,2331 @@ pub async fn get_evidence_range(
             let seq = e["seq"].as_u64().unwrap_or(0);
             seq >= from && seq <= to
         })
-        .collect();
+        .collect()
+}
+
+/// Up to `limit` entries (default 100) starting at seq `from`, without
+/// first asking how long the chain is.
+#[tauri::command]
+pub async fn get_evidence_from(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    from: u64,
+    limit: Option<u32>,
+) -> Result<Vec<EvidenceEntry>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        return Ok(vec![]);
+    }
+    let file = run_dir.open(EVIDENCE_CHAIN_FILE)?;
+    let limit = limit.unwrap_or(DEFAULT_EVIDENCE_PAGE) as usize;
+    evidence_from(BufReader::new(file), from, limit).map_err(|e| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    })
+}
 
+/// Stops reading as soon as `limit` entries are collected.
+fn evidence_from(
+    reader: impl BufRead,
+    from: u64,
+    limit: usize,
+) -> std::io::Result<Vec<EvidenceEntry>> {
+    let mut entries = Vec::new();
+    for line in reader.lines() {
+        if entries.len() == limit {
+            break;
+        }
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<EvidenceEntry>(&line) else {
+            continue;
+        };
+        if entry.seq >= from {
+            entries.push(entry);
+        }
+    }
     Ok(entries)
 }
+
+/// The entry whose `hash` is exactly `hash`, a 64-digit hex SHA-256, for
+/// tracing a hash from a security report back to its entry.
+#[tauri::command]
+pub async fn get_evidence_by_hash(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    hash: String,
+) -> Result<Option<EvidenceEntry>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetEvidenceByHash {
+        run_id: run_id.clone(),
+        hash: hash.clone(),
+    })?;
+    if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(FactoryError::InvalidArgument(format!(
+            "{:?} is not a 64-digit hex SHA-256",
+            hash
+        )));
+    }
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        return Ok(None);
+    }
+    let file = run_dir.open(EVIDENCE_CHAIN_FILE)?;
+    evidence_by_hash(BufReader::new(file), &hash).map_err(|e| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    })
+}
+
+/// Stops reading at the first match. Only lines containing `hash` are
+/// parsed.
+fn evidence_by_hash(reader: impl BufRead, hash: &str) -> std::io::Result<Option<EvidenceEntry>> {
+    for line in reader.lines() {
+        let line = line?;
+        if !line.contains(hash) {
+            continue;
+        }
+        match serde_json::from_str::<EvidenceEntry>(&line) {
+            Ok(entry) if entry.hash == hash => return Ok(Some(entry)),
+            _ => continue,
+        }
+    }
+    Ok(None)
+}
+
+/// The run's error entries, those whose type is in
+/// `BridgeConfig::run_error_types`: the first `MAX_RUN_ERRORS` by `seq`.
+#[tauri::command]
+pub async fn get_run_errors(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<Vec<RunErrorEntry>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetRunErrors {
+        run_id: run_id.clone(),
+    })?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        return Ok(vec![]);
+    }
+    let file = run_dir.open(EVIDENCE_CHAIN_FILE)?;
+    run_errors(BufReader::new(file), &config.run_error_types).map_err(|e| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    })
+}
+
+/// Stops reading once `MAX_RUN_ERRORS` are collected; the chain is
+/// written in `seq` order, so those are the first.
+fn run_errors(reader: impl BufRead, error_types: &[String]) -> std::io::Result<Vec<RunErrorEntry>> {
+    let mut errors = Vec::new();
+    for line in reader.lines() {
+        if errors.len() == MAX_RUN_ERRORS {
+            break;
+        }
+        let Ok(entry) = serde_json::from_str::<EvidenceEntry>(&line?) else {
+            continue;
+        };
+        if error_types.contains(&entry.entry_type) {
+            let message = entry.data["message"].as_str().map(str::to_string);
+            errors.push(RunErrorEntry { entry, message });
+        }
+    }
+    errors.sort_by_key(|e| e.entry.seq);
+    Ok(errors)
+}
+
+/// Like `get_evidence_range`, but sends entries over `channel` one at a
+/// time as the chain is read, so a large range is never held in memory.
+#[tauri::command]
+pub async fn stream_evidence_range(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    from: u64,
+    to: u64,
+    channel: Channel<EvidenceEntry>,
+) -> Result<(), FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let file = config.read_run_dir(&run_id)?.open(EVIDENCE_CHAIN_FILE)?;
+    let io_err = |e: std::io::Error| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    };
+
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(io_err)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<EvidenceEntry>(&line) else {
+            continue;
+        };
+        // The chain is append-only, so sequence numbers only go up.
+        if entry.seq > to {
+            break;
+        }
+        if entry.seq < from {
+            continue;
+        }
+        channel
+            .send(entry)
+            .map_err(|e| FactoryError::BridgeError(format!("evidence channel closed: {}", e)))?;
+        tokio::task::yield_now().await;
+    }
+    Ok(())
+}
+
+/// Tail-follow the evidence chain, sending `worker_id`'s entries over
+/// `channel` as they are appended. Returns once the run reaches a terminal
+/// status (after sending whatever it wrote last) or the channel is closed.
+#[tauri::command]
+pub async fn stream_worker_log(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    worker_id: String,
+    channel: Channel<StageLogEntry>,
+) -> Result<(), FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    let io_err = |e: std::io::Error| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    };
+
+    let mut tail = ChainTail::default();
+    let mut interval = WORKER_LOG_POLL_INTERVAL;
+    loop {
+        // Checked before reading, so the final read sees everything the
+        // factory wrote before it marked the run finished.
+        let finished = run_dir.exists(STATE_FILE)
+            && is_terminal(&read_run_status(&run_dir)?.status);
+
+        let mut sent = false;
+        if run_dir.exists(EVIDENCE_CHAIN_FILE) {
+            let mut file = run_dir.open(EVIDENCE_CHAIN_FILE)?;
+            for entry in tail.read_new(&mut file).map_err(io_err)? {
+                if entry.worker_id != worker_id {
+                    continue;
+                }
+                if channel.send(entry.into()).is_err() {
+                    return Ok(());
+                }
+                sent = true;
+            }
+        }
+
+        if finished {
+            return Ok(());
+        }
+        interval = if sent {
+            WORKER_LOG_POLL_INTERVAL
+        } else {
+            (interval * 2).min(MAX_WORKER_LOG_POLL_INTERVAL)
+        };
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Send evidence entries over `channel` as they are appended, starting
+/// from the current end of the chain. The run directory is watched for
+/// changes where the platform supports it and polled otherwise. Returns
+/// once the run reaches a terminal status or the channel is closed.
+#[tauri::command]
+pub async fn tail_evidence(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    channel: Channel<EvidenceEntry>,
+) -> Result<(), FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    let io_err = |e: std::io::Error| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    };
+
+    let mut tail = ChainTail::default();
+    if run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        tail.read_new(&mut run_dir.open(EVIDENCE_CHAIN_FILE)?).map_err(io_err)?;
+    }
+
+    // The factory replaces the chain file when it persists it, so the
+    // directory is watched rather than the file. Held until we return.
+    let (changed_tx, mut changed) = tokio::sync::mpsc::unbounded_channel();
+    let _watcher = notify::recommended_watcher(move |_: notify::Result<notify::Event>| {
+        let _ = changed_tx.send(());
+    })
+    .and_then(|mut w| {
+        notify::Watcher::watch(&mut w, run_dir.path(), notify::RecursiveMode::NonRecursive)?;
+        Ok(w)
+    })
+    .map_err(|e| tracing::debug!("watching run {} failed, polling instead: {}", run_id, e))
+    .ok();
+
+    loop {
+        let finished = run_dir.exists(STATE_FILE)
+            && is_terminal(&read_run_status(&run_dir)?.status);
+
+        if run_dir.exists(EVIDENCE_CHAIN_FILE) {
+            let mut file = run_dir.open(EVIDENCE_CHAIN_FILE)?;
+            for entry in tail.read_new(&mut file).map_err(io_err)? {
+                if channel.send(entry).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+
+        if finished {
+            return Ok(());
+        }
+        // Without a watcher the sender is dropped, so recv returns at once
+        // and the timeout alone paces the loop.
+        match tokio::time::timeout(TAIL_POLL_INTERVAL, changed.recv()).await {
+            Ok(Some(())) => while changed.try_recv().is_ok() {},
+            Ok(None) => tokio::time::sleep(TAIL_POLL_INTERVAL).await,
+            Err(_) => {}
+        }
+    }
+}
+
+/// Every worker's log entries for a run, in seq order. `level` is a
+/// minimum severity: `"warn"` returns warnings and errors. When more than
+/// `limit` entries match, the most recent ones are kept. The evidence
+/// checkpoint (see `get_checkpoint`) records only how far the chain had
+/// got, not its entries, so the chain is read from the start.
+#[tauri::command]
+pub async fn get_run_logs(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    level: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<StageLogEntry>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetRunLogs {
+        run_id: run_id.clone(),
+        level: level.clone(),
+        limit,
+    })?;
+    let min_level = match level.as_deref() {
+        None => 0,
+        Some(l) => LOG_LEVELS.iter().position(|known| *known == l).ok_or_else(|| {
+            FactoryError::InvalidArgument(format!(
+                "unknown log level '{}' (expected one of: {})",
+                l,
+                LOG_LEVELS.join(", ")
+            ))
+        })?,
+    };
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        return Ok(vec![]);
+    }
+
+    let file = run_dir.open(EVIDENCE_CHAIN_FILE)?;
+    let limit = limit.unwrap_or(DEFAULT_RUN_LOG_LIMIT) as usize;
+    run_logs(BufReader::new(file), min_level, limit).map_err(|e| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    })
+}
+
+fn run_logs(
+    reader: impl BufRead,
+    min_level: usize,
+    limit: usize,
+) -> std::io::Result<Vec<StageLogEntry>> {
+    let mut kept = VecDeque::with_capacity(limit.min(DEFAULT_RUN_LOG_LIMIT as usize));
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<EvidenceEntry>(&line) else {
+            continue;
+        };
+        if log_level(&entry) < min_level || limit == 0 {
+            continue;
+        }
+        if kept.len() == limit {
+            kept.pop_front();
+        }
+        kept.push_back(StageLogEntry::from(entry));
+    }
+    let mut logs = Vec::from(kept);
+    logs.sort_by_key(|e| e.seq);
+    Ok(logs)
+}
+
+/// An entry's index in `LOG_LEVELS`: its own `data.level` when it names
+/// one, otherwise a severity implied by the entry type.
+fn log_level(entry: &EvidenceEntry) -> usize {
+    let level = entry.data["level"]
+        .as_str()
+        .filter(|l| LOG_LEVELS.contains(l))
+        .unwrap_or(match entry.entry_type.as_str() {
+            "error" => "error",
+            "gate_fail" => "warn",
+            "llm_call" => "debug",
+            _ => "info",
+        });
+    LOG_LEVELS.iter().position(|l| *l == level).unwrap_or(0)
+}
+
+/// Stage, gate, worker and run milestones from the evidence chain, in
+/// timestamp order. Entries that aren't milestones (LLM calls, most notes)
+/// are left out.
+#[tauri::command]
+pub async fn get_run_timeline(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<Vec<TimelineEvent>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        return Ok(vec![]);
+    }
+    Ok(run_timeline(&run_dir.read_to_string(EVIDENCE_CHAIN_FILE)?))
+}
+
+fn run_timeline(chain: &str) -> Vec<TimelineEvent> {
+    let mut events: Vec<TimelineEvent> = chain
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str::<EvidenceEntry>(l).ok())
+        .filter_map(timeline_event)
+        .collect();
+    // RFC 3339 timestamps in one zone sort lexically; the sort is stable,
+    // so same-millisecond entries keep chain order.
+    events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+    if events.len() > MAX_TIMELINE_EVENTS {
+        let half = MAX_TIMELINE_EVENTS / 2;
+        events.drain(half..events.len() - half);
+    }
+    events
+}
+
+fn timeline_event(entry: EvidenceEntry) -> Option<TimelineEvent> {
+    let (event_type, description) = match entry.entry_type.as_str() {
+        "run_start" => ("run_started", "run started".to_string()),
+        "run_end" => (
+            "run_completed",
+            format!("run ended: {}", entry.data["status"].as_str().unwrap_or("unknown")),
+        ),
+        "worker_start" => ("worker_joined", format!("{} started", entry.worker_id)),
+        "worker_end" => ("worker_left", format!("{} finished", entry.worker_id)),
+        "gate_pass" | "gate_fail" => (
+            "gate_evaluated",
+            format!(
+                "gate {} {}",
+                entry.data["gateId"].as_str().unwrap_or("?"),
+                if entry.entry_type == "gate_pass" { "passed" } else { "failed" }
+            ),
+        ),
+        "note" => match entry.data["event"].as_str() {
+            Some("stage_start") => ("stage_started", format!("{} started", entry.stage)),
+            Some("stage_end") => ("stage_completed", format!("{} completed", entry.stage)),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(TimelineEvent {
+        timestamp: entry.timestamp,
+        event_type: event_type.to_string(),
+        description,
+        metadata: serde_json::json!({
+            "seq": entry.seq,
+            "stage": entry.stage,
+            "workerId": entry.worker_id,
+            "data": entry.data,
+        }),
+    })
+}
+
+/// Read position in an evidence chain that is being written to.
+#[derive(Default)]
+struct ChainTail {
+    /// Byte offset just past the last complete line read.
+    position: u64,
+    last_seq: Option<u64>,
+}
+
+impl ChainTail {
+    /// Entries appended since the last call. A trailing line without its
+    /// newline is left for the next call. The factory rewrites the whole
+    /// file when it persists the chain, so a file shorter than `position`
+    /// is read again from the start, skipping sequence numbers already
+    /// returned.
+    fn read_new(&mut self, file: &mut fs::File) -> std::io::Result<Vec<EvidenceEntry>> {
+        if file.metadata()?.len() < self.position {
+            self.position = 0;
+        }
+        file.seek(SeekFrom::Start(self.position))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+
+        let complete = buf.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+        self.position += complete as u64;
+
+        let mut entries = Vec::new();
+        for line in buf[..complete].split(|b| *b == b'\n') {
+            let Ok(entry) = serde_json::from_slice::<EvidenceEntry>(line) else {
+                continue;
+            };
+            if self.last_seq.is_some_and(|last| entry.seq <= last) {
+                continue;
+            }
+            self.last_seq = Some(entry.seq);
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+#[tauri::command]
+pub async fn get_artifact(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    path: String,
+) -> Result<ArtifactInfo, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    let resolved = run_dir.safe_artifact_path(&path)?;
+    let meta = fs::metadata(&resolved).map_err(|e| {
+        FactoryError::IoError(format!("artifact {} for run {}: {}", path, run_id, e))
+    })?;
+
+    // Hash and platform come from the manifest; the factory computed them
+    // when the artifact was produced.
+    let manifest = run_dir.read_json(MANIFEST_FILE).unwrap_or_default();
+    let recorded = manifest["artifactHashes"].as_array().and_then(|hashes| {
+        hashes
+            .iter()
+            .find(|a| a["path"].as_str().is_some_and(|p| Path::new(p).ends_with(&path)))
+    });
+
+    Ok(ArtifactInfo {
+        path,
+        sha256: recorded
+            .and_then(|a| a["sha256"].as_str())
+            .unwrap_or("")
+            .to_string(),
+        size_bytes: meta.len(),
+        platform: recorded
+            .and_then(|a| a["platform"].as_str())
+            .unwrap_or("unknown")
+            .to_string(),
+    })
+}
+
+/// Compare `artifact_name` between two runs by the hashes their manifests
+/// recorded, with a line diff for small text files.
+#[tauri::command]
+pub async fn get_artifact_diff(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id_a: String,
+    run_id_b: String,
+    artifact_name: String,
+) -> Result<ArtifactDiffResult, FactoryError> {
+    auth.authorize(&FactoryCommand::GetArtifactDiff {
+        run_id_a: run_id_a.clone(),
+        run_id_b: run_id_b.clone(),
+        artifact_name: artifact_name.clone(),
+    })?;
+    let a = recorded_artifact(&config.read_run_dir(&run_id_a)?, &artifact_name);
+    let b = recorded_artifact(&config.read_run_dir(&run_id_b)?, &artifact_name);
+    Ok(artifact_diff(artifact_name, a, b))
+}
+
+/// An artifact's hash and size as one run's manifest recorded them, and
+/// its file if the run still has it.
+struct RecordedArtifact {
+    sha256: Option<String>,
+    size_bytes: Option<u64>,
+    file: Option<std::path::PathBuf>,
+}
+
+fn recorded_artifact(run_dir: &RunDir, name: &str) -> RecordedArtifact {
+    let manifest = run_dir.read_json(MANIFEST_FILE).unwrap_or_default();
+    let recorded = manifest["artifactHashes"].as_array().and_then(|hashes| {
+        hashes
+            .iter()
+            .find(|a| a["path"].as_str().is_some_and(|p| Path::new(p).ends_with(name)))
+    });
+    let file = run_dir.safe_artifact_path(name).ok().filter(|p| p.is_file());
+    RecordedArtifact {
+        sha256: recorded.and_then(|a| a["sha256"].as_str()).map(str::to_string),
+        size_bytes: recorded.and_then(|a| a["sizeBytes"].as_u64()).or_else(|| {
+            let file = file.as_ref()?;
+            fs::metadata(file).ok().map(|m| m.len())
+        }),
+        file,
+    }
+}
+
+fn artifact_diff(name: String, a: RecordedArtifact, b: RecordedArtifact) -> ArtifactDiffResult {
+    let line_diff = match (&a.file, &b.file) {
+        (Some(file_a), Some(file_b)) => line_diff(file_a, file_b),
+        _ => None,
+    };
+    ArtifactDiffResult {
+        artifact_name: name,
+        changed: a.sha256 != b.sha256 || a.size_bytes != b.size_bytes,
+        hash_a: a.sha256,
+        hash_b: b.sha256,
+        size_a: a.size_bytes,
+        size_b: b.size_bytes,
+        line_diff,
+    }
+}
+
+/// `None` unless both files are UTF-8 text of at most 100 KB.
+fn line_diff(a: &Path, b: &Path) -> Option<LineDiffSummary> {
+    let read = |path: &Path| {
+        let small = fs::metadata(path).ok()?.len() <= MAX_LINE_DIFF_BYTES;
+        small.then(|| fs::read_to_string(path).ok()).flatten()
+    };
+    let (a, b) = (read(a)?, read(b)?);
+    let mut summary = LineDiffSummary {
+        lines_added: 0,
+        lines_removed: 0,
+        lines_unchanged: 0,
+    };
+    for change in similar::TextDiff::from_lines(&a, &b).iter_all_changes() {
+        match change.tag() {
+            similar::ChangeTag::Insert => summary.lines_added += 1,
+            similar::ChangeTag::Delete => summary.lines_removed += 1,
+            similar::ChangeTag::Equal => summary.lines_unchanged += 1,
+        }
+    }
+    Some(summary)
+}
+
+/// The environment variables recorded when the run started. Empty when
+/// none were, which is the default (see `capture_environment`).
+#[tauri::command]
+pub async fn get_run_environment(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<HashMap<String, String>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetRunEnvironment {
+        run_id: run_id.clone(),
+    })?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(ENVIRONMENT_FILE) {
+        tracing::warn!(
+            "run {} has no {}; its environment was not captured",
+            run_id,
+            ENVIRONMENT_FILE
+        );
+        return Ok(HashMap::new());
+    }
+    run_dir.read_typed(ENVIRONMENT_FILE)
+}
+
+/// The machine the run executed on. Fails with `IoError` for a run the
+/// factory wrote no `runner.json` for.
+#[tauri::command]
+pub async fn get_runner_info(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<RunnerInfo, FactoryError> {
+    auth.authorize(&FactoryCommand::GetRunnerInfo {
+        run_id: run_id.clone(),
+    })?;
+    config.read_run_dir(&run_id)?.read_typed(RUNNER_FILE)
+}
+
+/// Which parts of the machine differ between two runs, for spotting
+/// flakiness that follows a runner. Either run lacking `runner.json` is an
+/// error rather than a comparison that finds nothing changed.
+#[tauri::command]
+pub async fn get_runner_comparison(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id_a: String,
+    run_id_b: String,
+) -> Result<RunnerComparison, FactoryError> {
+    auth.authorize(&FactoryCommand::GetRunnerComparison {
+        run_id_a: run_id_a.clone(),
+        run_id_b: run_id_b.clone(),
+    })?;
+    let read_runner = |run_id: &str| -> Result<RunnerInfo, FactoryError> {
+        let run_dir = config.read_run_dir(run_id)?;
+        if !run_dir.exists(RUNNER_FILE) {
+            return Err(FactoryError::InvalidArgument(format!(
+                "run {} has no {}",
+                run_id, RUNNER_FILE
+            )));
+        }
+        run_dir.read_typed(RUNNER_FILE)
+    };
+    Ok(runner_comparison(read_runner(&run_id_a)?, read_runner(&run_id_b)?))
+}
+
+fn runner_comparison(runner_a: RunnerInfo, runner_b: RunnerInfo) -> RunnerComparison {
+    RunnerComparison {
+        hostname_changed: runner_a.hostname != runner_b.hostname,
+        os_changed: runner_a.os != runner_b.os,
+        cpu_count_changed: runner_a.cpu_count != runner_b.cpu_count,
+        node_version_changed: runner_a.node_version != runner_b.node_version,
+        factory_version_changed: runner_a.factory_version != runner_b.factory_version,
+        runner_a,
+        runner_b,
+    }
+}
+
+/// Up to `max_bytes` of an artifact, base64-encoded. Text artifacts over
+/// the limit come back truncated; binary ones are refused, since a prefix
+/// of an APK or a zip is of no use to anyone.
+#[tauri::command]
+pub async fn get_artifact_content(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    path: String,
+    max_bytes: Option<u64>,
+) -> Result<ArtifactContent, FactoryError> {
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_ARTIFACT_CONTENT_BYTES);
+    auth.authorize(&FactoryCommand::GetArtifactContent {
+        run_id: run_id.clone(),
+        path: path.clone(),
+        max_bytes,
+    })?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    let resolved = run_dir.safe_artifact_path(&path)?;
+    let io_err = |e: std::io::Error| {
+        FactoryError::IoError(format!("artifact {} for run {}: {}", path, run_id, e))
+    };
+    let file = fs::File::open(&resolved).map_err(io_err)?;
+    let total_bytes = file.metadata().map_err(io_err)?.len();
+    artifact_content(path, file, total_bytes, max_bytes)
+}
+
+fn artifact_content(
+    path: String,
+    file: impl Read,
+    total_bytes: u64,
+    max_bytes: u64,
+) -> Result<ArtifactContent, FactoryError> {
+    use base64::Engine;
+
+    let mut content = Vec::new();
+    file.take(max_bytes)
+        .read_to_end(&mut content)
+        .map_err(|e| FactoryError::IoError(format!("reading artifact {}: {}", path, e)))?;
+    let truncated = total_bytes > content.len() as u64;
+    if truncated && content[..content.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+        return Err(FactoryError::InvalidArgument(format!(
+            "artifact {} is binary and {} bytes, over the {}-byte limit",
+            path, total_bytes, max_bytes
+        )));
+    }
+
+    Ok(ArtifactContent {
+        path,
+        content_base64: base64::engine::general_purpose::STANDARD.encode(&content),
+        truncated,
+        total_bytes,
+    })
+}
+
+/// The newest artifact, across completed runs, whose recorded path matches
+/// the glob `pattern` (such as `release/*.apk`). Runs are taken newest
+/// first by start time; within a run, manifest order decides.
+#[tauri::command]
+pub async fn get_latest_artifact(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    pattern: String,
+) -> Result<Option<ArtifactLocator>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetLatestArtifact {
+        pattern: pattern.clone(),
+    })?;
+    latest_artifact(&config.runs_root(), &pattern)
+}
+
+fn latest_artifact(
+    runs_root: &Path,
+    pattern: &str,
+) -> Result<Option<ArtifactLocator>, FactoryError> {
+    let traverses = pattern.starts_with('/')
+        || pattern.starts_with('\\')
+        || pattern.contains(':')
+        || pattern.split(['/', '\\']).any(|part| part == "..");
+    if traverses {
+        return Err(FactoryError::InvalidArgument(format!(
+            "artifact pattern {:?} must be relative and must not contain '..'",
+            pattern
+        )));
+    }
+    let glob = glob::Pattern::new(pattern).map_err(|e| {
+        FactoryError::InvalidArgument(format!("artifact pattern {:?}: {}", pattern, e))
+    })?;
+
+    for run in read_run_history(runs_root, false)? {
+        let Ok(manifest) = RunDir::for_read(runs_root, &run.run_id)?.read_json(MANIFEST_FILE) else {
+            continue;
+        };
+        let artifacts = manifest["artifactHashes"].as_array().cloned().unwrap_or_default();
+        let found = artifacts
+            .iter()
+            .find(|a| a["path"].as_str().is_some_and(|p| glob.matches(p)));
+        if let Some(a) = found {
+            return Ok(Some(ArtifactLocator {
+                run_id: run.run_id,
+                artifact: ArtifactInfo {
+                    path: a["path"].as_str().unwrap_or_default().to_string(),
+                    sha256: a["sha256"].as_str().unwrap_or("").to_string(),
+                    size_bytes: a["sizeBytes"].as_u64().unwrap_or(0),
+                    platform: a["platform"].as_str().unwrap_or("unknown").to_string(),
+                },
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// Entry count for pagination. Lines are counted as raw bytes; only the
+/// last entry is parsed, for its `seq`, since the chain is append-only.
+#[tauri::command]
+pub async fn get_evidence_count(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<EvidenceCountResult, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        return Ok(EvidenceCountResult::default());
+    }
+
+    let file = run_dir.open(EVIDENCE_CHAIN_FILE)?;
+    count_evidence(BufReader::new(file)).map_err(|e| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    })
+}
+
+/// The run's evidence checkpoint, or `None` if it has none. Only the small
+/// checkpoint file and the chain's last line are read.
+#[tauri::command]
+pub async fn get_checkpoint(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<Option<CheckpointInfo>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetCheckpoint {
+        run_id: run_id.clone(),
+    })?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(EVIDENCE_CHECKPOINT_FILE) {
+        return Ok(None);
+    }
+    let checkpoint: CheckpointFile = run_dir.read_typed(EVIDENCE_CHECKPOINT_FILE)?;
+    let file_size_bytes = fs::metadata(run_dir.path().join(EVIDENCE_CHECKPOINT_FILE))
+        .map_err(|e| FactoryError::IoError(format!("checkpoint for run {}: {}", run_id, e)))?
+        .len();
+    let chain = if run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        let file = run_dir.open(EVIDENCE_CHAIN_FILE)?;
+        count_evidence(BufReader::new(file)).map_err(|e| {
+            let file = EVIDENCE_CHAIN_FILE;
+            FactoryError::IoError(format!("reading {} for run {}: {}", file, run_id, e))
+        })?
+    } else {
+        EvidenceCountResult::default()
+    };
+    Ok(Some(checkpoint_info(checkpoint, file_size_bytes, &chain)))
+}
+
+fn checkpoint_info(
+    checkpoint: CheckpointFile,
+    file_size_bytes: u64,
+    chain: &EvidenceCountResult,
+) -> CheckpointInfo {
+    CheckpointInfo {
+        is_current: checkpoint.seq >= chain.max_seq,
+        seq: checkpoint.seq,
+        created_at: checkpoint.created_at,
+        covers_entries: checkpoint.covers_entries,
+        file_size_bytes,
+    }
+}
+
+fn count_evidence(reader: impl BufRead) -> std::io::Result<EvidenceCountResult> {
+    let mut total = 0;
+    let mut last = Vec::new();
+    for line in reader.split(b'\n') {
+        let line = line?;
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        total += 1;
+        last = line;
+    }
+
+    let max_seq = serde_json::from_slice::<EvidenceHeader>(&last)
+        .ok()
+        .and_then(|h| h.seq)
+        .unwrap_or(0);
+    Ok(EvidenceCountResult { total, max_seq })
+}
+
+/// The fields of an evidence line that stats need; `data` is never parsed.
+#[derive(Deserialize)]
+struct EvidenceHeader {
+    seq: Option<u64>,
+    #[serde(rename = "type")]
+    entry_type: Option<String>,
+    #[serde(rename = "workerId")]
+    worker_id: Option<String>,
+    timestamp: Option<String>,
+}
+
+/// Streams the chain line by line; memory is bounded by the number of
+/// distinct entry types and workers, not by chain length.
+#[tauri::command]
+pub async fn get_evidence_stats(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<EvidenceStats, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let file = config.read_run_dir(&run_id)?.open(EVIDENCE_CHAIN_FILE)?;
+    let io_err = |e: std::io::Error| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    };
+
+    let size = file.metadata().map_err(io_err)?.len();
+    let mut stats = evidence_stats(BufReader::new(file)).map_err(io_err)?;
+    stats.chain_size_bytes = size;
+    Ok(stats)
+}
+
+fn evidence_stats(reader: impl BufRead) -> std::io::Result<EvidenceStats> {
+    let mut stats = EvidenceStats::default();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(header) = serde_json::from_str::<EvidenceHeader>(&line) else {
+            continue;
+        };
+
+        if let Some(seq) = header.seq {
+            if stats.total_entries == 0 {
+                stats.min_seq = seq;
+                stats.max_seq = seq;
+            } else {
+                stats.min_seq = stats.min_seq.min(seq);
+                stats.max_seq = stats.max_seq.max(seq);
+            }
+        }
+        stats.total_entries += 1;
+        if let Some(t) = header.entry_type {
+            *stats.entries_by_type.entry(t).or_insert(0) += 1;
+        }
+        if let Some(w) = header.worker_id.filter(|w| !w.is_empty()) {
+            *stats.entries_by_worker.entry(w).or_insert(0) += 1;
+        }
+        if let Some(ts) = header.timestamp {
+            if stats.first_timestamp.is_empty() {
+                stats.first_timestamp = ts.clone();
+            }
+            stats.last_timestamp = ts;
+        }
+    }
+    Ok(stats)
+}
+
+/// The entry types a run's chain actually contains, for filter dropdowns;
+/// `list_evidence_types` lists every type the factory can write. Streams
+/// the chain as `get_evidence_stats` does.
+#[tauri::command]
+pub async fn list_run_evidence_types(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<RunEvidenceTypes, FactoryError> {
+    auth.authorize(&FactoryCommand::ListRunEvidenceTypes {
+        run_id: run_id.clone(),
+    })?;
+    let file = config.read_run_dir(&run_id)?.open(EVIDENCE_CHAIN_FILE)?;
+    run_evidence_types(BufReader::new(file)).map_err(|e| {
+        FactoryError::IoError(format!("reading {} for run {}: {}", EVIDENCE_CHAIN_FILE, run_id, e))
+    })
+}
+
+pub(crate) fn run_evidence_types(reader: impl BufRead) -> std::io::Result<RunEvidenceTypes> {
+    let counts = evidence_stats(reader)?.entries_by_type;
+    let mut types: Vec<String> = counts.keys().cloned().collect();
+    types.sort();
+    Ok(RunEvidenceTypes { types, counts })
+}
+
+/// Every stage of a run with its status, timing and gate counts. Status
+/// comes from `run-state.json`; timing prefers the chain's `stage_start` /
+/// `stage_end` notes and falls back to the state file's timestamps.
+#[tauri::command]
+pub async fn list_run_stages(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<Vec<StageInfo>, FactoryError> {
+    auth.authorize(&FactoryCommand::ListRunStages {
+        run_id: run_id.clone(),
+    })?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    let state = run_dir.read_json(STATE_FILE)?;
+    let chain = if run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        run_dir.read_to_string(EVIDENCE_CHAIN_FILE)?
+    } else {
+        String::new()
+    };
+    Ok(run_stages(&state, &chain))
+}
+
+#[derive(Default)]
+struct StageTiming {
+    started_at: Option<String>,
+    completed_at: Option<String>,
+    gates_passed: u32,
+    gates_failed: u32,
+}
+
+pub(crate) fn run_stages(state: &serde_json::Value, chain: &str) -> Vec<StageInfo> {
+    let mut names: Vec<String> = STAGE_ORDER.iter().map(|s| s.to_string()).collect();
+    let mut timings: HashMap<String, StageTiming> = HashMap::new();
+
+    if let Some(stamps) = state["timestamps"].as_object() {
+        for (stage, stamp) in stamps {
+            let timing = timings.entry(stage.clone()).or_default();
+            timing.started_at = stamp["start"].as_str().map(str::to_string);
+            timing.completed_at = stamp["end"].as_str().map(str::to_string);
+        }
+    }
+    for entry in chain
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+    {
+        let Some(stage) = entry["stage"].as_str() else {
+            continue;
+        };
+        let timestamp = entry["timestamp"].as_str().map(str::to_string);
+        let timing = timings.entry(stage.to_string()).or_default();
+        match (entry["type"].as_str(), entry["data"]["event"].as_str()) {
+            (Some("gate_pass"), _) => timing.gates_passed += 1,
+            (Some("gate_fail"), _) => timing.gates_failed += 1,
+            (Some("note"), Some("stage_start")) => timing.started_at = timestamp,
+            (Some("note"), Some("stage_end")) => timing.completed_at = timestamp,
+            _ => continue,
+        }
+        if !names.iter().any(|n| n == stage) {
+            names.push(stage.to_string());
+        }
+    }
+    for stage in state["timestamps"].as_object().into_iter().flat_map(|m| m.keys()) {
+        if stage != "initializing" && !names.contains(stage) {
+            names.push(stage.clone());
+        }
+    }
+
+    let run_status = state["status"].as_str().unwrap_or("running");
+    let current = state["currentStage"].as_str().unwrap_or("");
+    names
+        .into_iter()
+        .map(|name| {
+            let timing = timings.remove(&name).unwrap_or_default();
+            let status = if timing.gates_failed > 0 {
+                "failed"
+            } else if timing.completed_at.is_some() {
+                "completed"
+            } else if timing.started_at.is_some() || name == current {
+                match run_status {
+                    "running" => "active",
+                    "aborted" => "failed",
+                    _ => "completed",
+                }
+            } else if run_status == "running" {
+                "pending"
+            } else {
+                "skipped"
+            };
+            let duration_ms = match (&timing.started_at, &timing.completed_at) {
+                (Some(start), Some(end)) => duration_between(start, end),
+                _ => None,
+            };
+            StageInfo {
+                name,
+                status: status.to_string(),
+                started_at: timing.started_at,
+                completed_at: timing.completed_at,
+                duration_ms,
+                gates_passed: timing.gates_passed,
+                gates_failed: timing.gates_failed,
+            }
+        })
+        .collect()
+}
+
+fn duration_between(start: &str, end: &str) -> Option<u64> {
+    let start = DateTime::parse_from_rfc3339(start).ok()?;
+    let end = DateTime::parse_from_rfc3339(end).ok()?;
+    u64::try_from((end - start).num_milliseconds()).ok()
+}
+
+struct WorkerProgress {
+    last_seen: Option<DateTime<Utc>>,
+    stage: String,
+    percent: f32,
+}
+
+/// Latest progress per worker, from every entry that names a worker.
+fn worker_progress(chain: &str) -> HashMap<String, WorkerProgress> {
+    let mut workers: HashMap<String, WorkerProgress> = HashMap::new();
+    for entry in chain
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+    {
+        let worker_id = match entry["workerId"].as_str() {
+            Some(id) if !id.is_empty() => id.to_string(),
+            _ => continue,
+        };
+        let progress = workers.entry(worker_id).or_insert(WorkerProgress {
+            last_seen: None,
+            stage: String::new(),
+            percent: 0.0,
+        });
+        progress.last_seen = entry["timestamp"]
+            .as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+            .or(progress.last_seen);
+        progress.stage = entry["stage"].as_str().unwrap_or("").to_string();
+        if entry["type"].as_str() == Some("worker_end") {
+            progress.percent = 100.0;
+        } else if let Some(p) = entry["data"]["progress"].as_f64() {
+            progress.percent = p.clamp(0.0, 100.0) as f32;
+        }
+    }
+    workers
+}
+
+/// How many workers have appeared in the run, how many are still working
+/// and how many finished. The evidence checkpoint holds no per-worker
+/// state to start from, so the chain is scanned in full.
+#[tauri::command]
+pub async fn get_worker_count(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<WorkerCountInfo, FactoryError> {
+    auth.authorize(&FactoryCommand::GetWorkerCount {
+        run_id: run_id.clone(),
+    })?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        return Ok(WorkerCountInfo::default());
+    }
+    let workers = worker_progress(&run_dir.read_to_string(EVIDENCE_CHAIN_FILE)?);
+    Ok(worker_count(&workers, Utc::now()))
+}
+
+/// The stages `worker_id` was active in and the gates it evaluated. An
+/// interval starts at the worker's first entry in a stage and ends at its
+/// `stage_end` note or its first entry in another stage.
+#[tauri::command]
+pub async fn get_worker_timeline(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+    worker_id: String,
+) -> Result<WorkerTimeline, FactoryError> {
+    auth.authorize(&FactoryCommand::GetWorkerTimeline {
+        run_id: run_id.clone(),
+        worker_id: worker_id.clone(),
+    })?;
+    let file = config.read_run_dir(&run_id)?.open(EVIDENCE_CHAIN_FILE)?;
+    worker_timeline(BufReader::new(file), &worker_id)
+        .map_err(|e| {
+            FactoryError::IoError(format!(
+                "reading {} for run {}: {}",
+                EVIDENCE_CHAIN_FILE, run_id, e
+            ))
+        })?
+        .ok_or_else(|| {
+            FactoryError::InvalidArgument(format!(
+                "run {} has no evidence from worker {}",
+                run_id, worker_id
+            ))
+        })
+}
+
+fn worker_timeline(
+    reader: impl BufRead,
+    worker_id: &str,
+) -> std::io::Result<Option<WorkerTimeline>> {
+    let mut intervals: Vec<WorkerStageInterval> = Vec::new();
+    let mut gates: Vec<String> = Vec::new();
+    let mut first_seen: Option<String> = None;
+    let mut last_seen = String::new();
+    let close = |interval: &mut WorkerStageInterval, at: &str| {
+        interval.duration_ms = duration_between(&interval.started_at, at).unwrap_or(0);
+        interval.ended_at = Some(at.to_string());
+    };
+
+    for line in reader.lines() {
+        let line = line?;
+        let Ok(entry) = serde_json::from_str::<EvidenceEntry>(&line) else {
+            continue;
+        };
+        if entry.worker_id != worker_id {
+            continue;
+        }
+        first_seen.get_or_insert_with(|| entry.timestamp.clone());
+        last_seen.clone_from(&entry.timestamp);
+
+        let open = intervals.last_mut().filter(|i| i.ended_at.is_none());
+        match (entry.entry_type.as_str(), entry.data["event"].as_str()) {
+            ("note", Some("stage_end")) => {
+                if let Some(interval) = open.filter(|i| i.stage == entry.stage) {
+                    close(interval, &entry.timestamp);
+                }
+                continue;
+            }
+            ("gate_pass" | "gate_fail", _) => {
+                if let Some(gate) = entry.data["gateId"].as_str() {
+                    if !gates.iter().any(|g| g == gate) {
+                        gates.push(gate.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+        let open = intervals.last_mut().filter(|i| i.ended_at.is_none());
+        match open {
+            Some(interval) if interval.stage == entry.stage => {}
+            other => {
+                if let Some(interval) = other {
+                    close(interval, &entry.timestamp);
+                }
+                intervals.push(WorkerStageInterval {
+                    stage: entry.stage.clone(),
+                    started_at: entry.timestamp.clone(),
+                    ended_at: None,
+                    duration_ms: 0,
+                });
+            }
+        }
+    }
+
+    let Some(started_at) = first_seen else {
+        return Ok(None);
+    };
+    if let Some(interval) = intervals.last_mut().filter(|i| i.ended_at.is_none()) {
+        interval.duration_ms = duration_between(&interval.started_at, &last_seen).unwrap_or(0);
+    }
+    let completed_at = intervals.last().and_then(|i| i.ended_at.clone());
+    let end = completed_at.as_deref().unwrap_or(&last_seen);
+    Ok(Some(WorkerTimeline {
+        worker_id: worker_id.to_string(),
+        total_duration_ms: duration_between(&started_at, end).unwrap_or(0),
+        started_at,
+        completed_at,
+        stages_active: intervals,
+        gates_evaluated: gates,
+    }))
+}
+
+fn worker_count(workers: &HashMap<String, WorkerProgress>, now: DateTime<Utc>) -> WorkerCountInfo {
+    let mut count = WorkerCountInfo {
+        total_ever_seen: workers.len() as u32,
+        ..WorkerCountInfo::default()
+    };
+    for progress in workers.values() {
+        if progress.percent >= 100.0 {
+            count.completed += 1;
+        } else if progress
+            .last_seen
+            .is_some_and(|t| (now - t).num_seconds() <= WORKER_COUNT_WINDOW_SECS)
+        {
+            count.currently_active += 1;
+        }
+    }
+    count
+}
+
+#[tauri::command]
+pub async fn get_worker_aggregate_progress(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<AggregateProgress, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+        return Ok(AggregateProgress::default());
+    }
+    let workers = worker_progress(&run_dir.read_to_string(EVIDENCE_CHAIN_FILE)?);
+    if workers.is_empty() {
+        return Ok(AggregateProgress::default());
+    }
+
+    let now = Utc::now();
+    let mut stages_active = BTreeSet::new();
+    let mut active_workers = 0;
+    for progress in workers.values() {
+        let recent = progress
+            .last_seen
+            .map(|t| (now - t).num_seconds() <= ACTIVE_WORKER_WINDOW_SECS)
+            .unwrap_or(false);
+        if recent {
+            active_workers += 1;
+            if !progress.stage.is_empty() {
+                stages_active.insert(progress.stage.clone());
+            }
+        }
+    }
+
+    let total_percent: f32 = workers.values().map(|p| p.percent).sum();
+    let slowest_worker = workers
+        .iter()
+        .min_by(|a, b| a.1.percent.total_cmp(&b.1.percent))
+        .map(|(id, _)| id.clone());
+    let fastest_worker = workers
+        .iter()
+        .max_by(|a, b| a.1.percent.total_cmp(&b.1.percent))
+        .map(|(id, _)| id.clone());
+
+    Ok(AggregateProgress {
+        total_workers: workers.len() as u32,
+        active_workers,
+        average_percent: total_percent / workers.len() as f32,
+        slowest_worker,
+        fastest_worker,
+        stages_active: stages_active.into_iter().collect(),
+    })
+}
+
+/// Evidence entries, across every run, containing `query`, newest run
+/// first and in chain order within a run. Lines are matched as raw text
+/// and only hits are parsed. Returns at most 50 matches; fails with
+/// `Timeout` if the scan takes longer than 10 s.
+#[tauri::command]
+pub async fn search_all_runs_evidence(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    query: String,
+    type_filter: Option<String>,
+    max_results: u32,
+) -> Result<Vec<CrossRunEvidenceMatch>, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    if query.is_empty() {
+        return Err(FactoryError::InvalidArgument("search query is empty".into()));
+    }
+    search_runs_evidence(
+        &config.runs_root(),
+        &query,
+        type_filter.as_deref(),
+        max_results.min(MAX_EVIDENCE_SEARCH_RESULTS) as usize,
+        Instant::now() + EVIDENCE_SEARCH_TIMEOUT,
+    )
+}
+
+fn search_runs_evidence(
+    runs_root: &Path,
+    query: &str,
+    type_filter: Option<&str>,
+    max_results: usize,
+    deadline: Instant,
+) -> Result<Vec<CrossRunEvidenceMatch>, FactoryError> {
+    let mut matches = Vec::new();
+    for run in read_run_history(runs_root, true)? {
+        if matches.len() >= max_results {
+            break;
+        }
+        let run_dir = RunDir::for_read(runs_root, &run.run_id)?;
+        if !run_dir.exists(EVIDENCE_CHAIN_FILE) {
+            continue;
+        }
+        let chain = BufReader::new(run_dir.open(EVIDENCE_CHAIN_FILE)?);
+        for line in chain.lines() {
+            if Instant::now() >= deadline {
+                return Err(FactoryError::Timeout(format!(
+                    "evidence search for {:?} after {} matches",
+                    query,
+                    matches.len()
+                )));
+            }
+            let line = line.map_err(|e| {
+                FactoryError::IoError(format!("reading evidence for run {}: {}", run.run_id, e))
+            })?;
+            if !line.contains(query) {
+                continue;
+            }
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            if type_filter.is_some_and(|t| value["type"].as_str() != Some(t)) {
+                continue;
+            }
+            // A hit only in JSON escaping isn't one in the entry itself.
+            let Some(matched_field) = matched_field(&value, query, "") else {
+                continue;
+            };
+            let Ok(entry) = serde_json::from_value(value) else {
+                continue;
+            };
+            matches.push(CrossRunEvidenceMatch {
+                run_id: run.run_id.clone(),
+                entry,
+                matched_field,
+            });
+            if matches.len() >= max_results {
+                break;
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Path, dot-separated, of the first key or value in `value` containing
+/// `query`.
+fn matched_field(value: &serde_json::Value, query: &str, path: &str) -> Option<String> {
+    match value {
+        serde_json::Value::Object(fields) => fields.iter().find_map(|(key, field)| {
+            let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+            if key.contains(query) {
+                Some(path)
+            } else {
+                matched_field(field, query, &path)
+            }
+        }),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(i, item)| matched_field(item, query, &format!("{}.{}", path, i))),
+        serde_json::Value::String(s) => s.contains(query).then(|| path.to_string()),
+        serde_json::Value::Null => None,
+        other => other.to_string().contains(query).then(|| path.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestRunBuilder;
+
+    #[test]
+    fn run_status_counts_passed_and_failed_gates() {
+        let root = tempfile::tempdir().unwrap();
+        let run_dir = TestRunBuilder::new()
+            .with_run_id("run-1")
+            .with_stages(&["spec_validation", "planning", "building"])
+            .with_gate_result("spec_valid", true)
+            .with_gate_result("plan_complete", true)
+            .with_gate_result("build_ok", false)
+            .build(root.path())
+            .unwrap();
+
+        let status = read_run_status(&run_dir).unwrap();
+        assert_eq!(status.run_id, "run-1");
+        assert_eq!(status.status, "running");
+        assert_eq!(status.current_stage, "building");
+        assert_eq!((status.gates_passed, status.gates_failed), (2, 1));
+    }
+
+    #[test]
+    fn gate_summary_counts_and_finds_the_first_failure() {
+        let state: RunStateFile = serde_json::from_value(serde_json::json!({
+            "runId": "run-1",
+            "status": "failed",
+            "currentStage": "testing",
+            "gateResults": [
+                { "gateId": "lint", "passed": true, "checks": [{ "name": "x" }] },
+                { "gateId": "a11y", "passed": false, "skipped": true },
+                { "gateId": "perf", "passed": false, "stage": "building" },
+                { "gateId": "e2e", "passed": false },
+            ],
+        }))
+        .unwrap();
+
+        let summary = gate_summary(&state);
+        assert_eq!(
+            (summary.total_gates, summary.passed, summary.failed, summary.skipped),
+            (4, 1, 2, 1)
+        );
+        assert!((summary.pass_rate - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(summary.first_failure.as_deref(), Some("perf"));
+        assert_eq!(summary.blocking_stage.as_deref(), Some("building"));
+
+        let empty: RunStateFile = serde_json::from_value(serde_json::json!({
+            "runId": "run-2", "status": "running", "currentStage": "planning",
+        }))
+        .unwrap();
+        let summary = gate_summary(&empty);
+        assert_eq!((summary.pass_rate, summary.first_failure), (0.0, None));
+    }
+
+    #[test]
+    fn worker_timeline_splits_entries_into_stage_intervals() {
+        let line = |worker: &str, kind: &str, stage: &str, second: u32, data: serde_json::Value| {
+            serde_json::json!({
+                "seq": 0,
+                "type": kind,
+                "workerId": worker,
+                "stage": stage,
+                "timestamp": format!("2026-01-01T00:00:{:02}Z", second),
+                "hash": "h",
+                "data": data,
+            })
+            .to_string()
+        };
+        let chain = [
+            line("planner", "note", "planning", 0, serde_json::json!({"event": "stage_start"})),
+            line("builder", "note", "building", 1, serde_json::json!({})),
+            line("planner", "gate_pass", "planning", 2, serde_json::json!({"gateId": "plan_ok"})),
+            line("planner", "gate_fail", "planning", 3, serde_json::json!({"gateId": "plan_ok"})),
+            line("planner", "note", "building", 5, serde_json::json!({})),
+            line("planner", "note", "building", 9, serde_json::json!({"event": "stage_end"})),
+        ]
+        .join("\n");
+
+        let timeline = worker_timeline(chain.as_bytes(), "planner").unwrap().unwrap();
+        assert_eq!(timeline.started_at, "2026-01-01T00:00:00Z");
+        assert_eq!(timeline.completed_at.as_deref(), Some("2026-01-01T00:00:09Z"));
+        assert_eq!(timeline.total_duration_ms, 9000);
+        assert_eq!(timeline.gates_evaluated, ["plan_ok"]);
+        let stages: Vec<_> = timeline
+            .stages_active
+            .iter()
+            .map(|i| (i.stage.as_str(), i.duration_ms))
+            .collect();
+        assert_eq!(stages, [("planning", 5000), ("building", 4000)]);
+
+        let open = worker_timeline(chain.as_bytes(), "builder").unwrap().unwrap();
+        assert_eq!((open.completed_at, open.total_duration_ms), (None, 0));
+        assert!(worker_timeline(chain.as_bytes(), "nobody").unwrap().is_none());
+    }
+
+    #[test]
+    fn evidence_stats_counts_without_keeping_entries() {
+        let chain = [
+            r#"{"seq":0,"type":"run_start","workerId":"master","timestamp":"t0","data":{}}"#,
+            "",
+            r#"{"seq":1,"type":"note","workerId":"planner","timestamp":"t1","data":{"x":1}}"#,
+            "not json",
+            r#"{"seq":2,"type":"note","workerId":"planner","timestamp":"t2","data":{}}"#,
+        ]
+        .join("\n");
+
+        let stats = evidence_stats(chain.as_bytes()).unwrap();
+        assert_eq!(stats.total_entries, 3);
+        assert_eq!((stats.min_seq, stats.max_seq), (0, 2));
+        assert_eq!(stats.entries_by_type["note"], 2);
+        assert_eq!(stats.entries_by_worker["planner"], 2);
+        assert_eq!(stats.first_timestamp, "t0");
+        assert_eq!(stats.last_timestamp, "t2");
+    }
+
+    #[test]
+    fn run_evidence_types_are_sorted_and_counted() {
+        let chain = [
+            r#"{"seq":0,"type":"run_start","data":{}}"#,
+            r#"{"seq":1,"type":"note","data":{}}"#,
+            r#"{"seq":2,"type":"gate_result","data":{}}"#,
+            r#"{"seq":3,"type":"note","data":{}}"#,
+        ]
+        .join("\n");
+
+        let found = run_evidence_types(chain.as_bytes()).unwrap();
+        assert_eq!(found.types, ["gate_result", "note", "run_start"]);
+        assert_eq!(found.counts["note"], 2);
+        assert_eq!(found.counts["run_start"], 1);
+    }
+
+    #[test]
+    fn generated_chain_reads_like_a_factory_chain() {
+        use crate::testing::{entry, EvidenceChainWriter};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EVIDENCE_CHAIN_FILE);
+        let mut writer = EvidenceChainWriter::new(&path).unwrap();
+        writer
+            .append_many(&[
+                entry("run_start", "master", "initializing"),
+                entry("worker_start", "planner", "planning"),
+                entry("worker_end", "planner", "planning"),
+            ])
+            .unwrap();
+
+        let chain = fs::read_to_string(&path).unwrap();
+        let count = count_evidence(chain.as_bytes()).unwrap();
+        assert_eq!(count, EvidenceCountResult { total: 3, max_seq: 2 });
+        let stats = evidence_stats(chain.as_bytes()).unwrap();
+        assert_eq!(stats.entries_by_worker["planner"], 2);
+        assert_eq!(worker_progress(&chain)["planner"].percent, 100.0);
+    }
+
+    #[test]
+    fn evidence_can_be_read_from_a_seq_to_the_end() {
+        let chain: Vec<String> = (0..10)
+            .map(|i| format!(r#"{{"seq":{i},"type":"note","data":{{}}}}"#))
+            .collect();
+        let chain = chain.join("\n");
+
+        let to_end = evidence_range(&chain, 7, u64::MAX);
+        let seqs: Vec<_> = to_end.iter().map(|e| e["seq"].as_u64().unwrap()).collect();
+        assert_eq!(seqs, [7, 8, 9]);
+
+        let page = evidence_from(chain.as_bytes(), 3, 4).unwrap();
+        assert_eq!(page.iter().map(|e| e.seq).collect::<Vec<_>>(), [3, 4, 5, 6]);
+        assert_eq!(evidence_from(chain.as_bytes(), 8, 100).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn evidence_is_found_by_its_own_hash() {
+        use crate::testing::{entry, EvidenceChainWriter};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EVIDENCE_CHAIN_FILE);
+        let mut writer = EvidenceChainWriter::new(&path).unwrap();
+        writer
+            .append_many(&[
+                entry("run_start", "master", "initializing"),
+                entry("note", "planner", "planning"),
+                entry("note", "builder", "building"),
+            ])
+            .unwrap();
+        let chain = fs::read_to_string(&path).unwrap();
+        let hashes: Vec<String> = chain
+            .lines()
+            .map(|l| serde_json::from_str::<EvidenceEntry>(l).unwrap().hash)
+            .collect();
+
+        // The second entry's hash also appears as the third's prevHash.
+        let found = evidence_by_hash(chain.as_bytes(), &hashes[1]).unwrap().unwrap();
+        assert_eq!((found.seq, found.worker_id.as_str()), (1, "planner"));
+        assert!(evidence_by_hash(chain.as_bytes(), &"0".repeat(64)).unwrap().is_none());
+    }
+
+    #[test]
+    fn checkpoint_is_current_only_through_the_last_entry() {
+        let checkpoint = |seq| CheckpointFile {
+            seq,
+            created_at: "2026-01-01T00:00:00Z".into(),
+            covers_entries: seq + 1,
+        };
+        let lines = "{\"seq\":0}\n{\"seq\":1}\n{\"seq\":2}\n";
+        let chain = count_evidence(lines.as_bytes()).unwrap();
+
+        let current = checkpoint_info(checkpoint(2), 120, &chain);
+        assert!(current.is_current);
+        assert_eq!((current.covers_entries, current.file_size_bytes), (3, 120));
+        assert!(!checkpoint_info(checkpoint(1), 120, &chain).is_current);
+    }
+
+    #[test]
+    fn evidence_count_skips_blank_lines() {
+        let chain = "{\"seq\":0}\n\n  \n{\"seq\":1}\n{\"seq\":2}\n";
+        let count = count_evidence(chain.as_bytes()).unwrap();
+        assert_eq!(count, EvidenceCountResult { total: 3, max_seq: 2 });
+
+        assert_eq!(count_evidence(&b""[..]).unwrap(), EvidenceCountResult::default());
+    }
+
+    #[test]
+    fn run_stages_follow_pipeline_order_with_chain_timing() {
+        let state = serde_json::json!({
+            "status": "aborted",
+            "currentStage": "planning",
+            "timestamps": {
+                "spec_validation": { "start": "2026-01-01T00:00:00.000Z" },
+                "planning": { "start": "2026-01-01T00:00:02.000Z" },
+            },
+        });
+        let note = |stage: &str, event: &str, at: &str| {
+            serde_json::json!({
+                "type": "note",
+                "stage": stage,
+                "timestamp": at,
+                "data": { "event": event },
+            })
+            .to_string()
+        };
+        let chain = [
+            note("spec_validation", "stage_start", "2026-01-01T00:00:00.500Z"),
+            note("spec_validation", "stage_end", "2026-01-01T00:00:01.500Z"),
+            r#"{"type":"gate_pass","stage":"spec_validation","data":{}}"#.to_string(),
+            r#"{"type":"gate_fail","stage":"planning","data":{}}"#.to_string(),
+        ]
+        .join("\n");
+
+        let stages = run_stages(&state, &chain);
+        let names: Vec<&str> = stages.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, STAGE_ORDER);
+
+        assert_eq!(stages[0].status, "completed");
+        assert_eq!(stages[0].started_at.as_deref(), Some("2026-01-01T00:00:00.500Z"));
+        assert_eq!(stages[0].duration_ms, Some(1000));
+        assert_eq!(stages[0].gates_passed, 1);
+        assert_eq!(stages[1].status, "failed");
+        assert_eq!(stages[1].gates_failed, 1);
+        assert_eq!(stages[1].duration_ms, None);
+        assert!(stages[2..].iter().all(|s| s.status == "skipped"));
+    }
+
+    #[test]
+    fn latest_run_is_picked_by_modification_time() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(latest_run_id(root.path()).unwrap(), None);
+
+        let base = SystemTime::now() - Duration::from_secs(600);
+        for (run_id, age_secs) in [("older", 0), ("newer", 60), ("no-state", 120)] {
+            let dir = root.path().join(run_id);
+            fs::create_dir_all(&dir).unwrap();
+            let file = if run_id == "no-state" { MANIFEST_FILE } else { STATE_FILE };
+            let path = dir.join(file);
+            fs::write(&path, "{}").unwrap();
+            let handle = fs::File::options().write(true).open(&path).unwrap();
+            handle.set_modified(base + Duration::from_secs(age_secs)).unwrap();
+        }
+
+        assert_eq!(latest_run_id(root.path()).unwrap().as_deref(), Some("newer"));
+    }
+
+    #[test]
+    fn chain_tail_reads_only_complete_new_lines() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EVIDENCE_CHAIN_FILE);
+        let mut writer = fs::File::create(&path).unwrap();
+        let mut reader = fs::File::open(&path).unwrap();
+        let mut tail = ChainTail::default();
+
+        writer.write_all(b"{\"seq\":0}\n{\"seq\":1}\n{\"seq\"").unwrap();
+        let seqs = |entries: Vec<EvidenceEntry>| -> Vec<u64> {
+            entries.into_iter().map(|e| e.seq).collect()
+        };
+        assert_eq!(seqs(tail.read_new(&mut reader).unwrap()), [0, 1]);
+
+        writer.write_all(b":2}\n").unwrap();
+        assert_eq!(seqs(tail.read_new(&mut reader).unwrap()), [2]);
+        assert!(tail.read_new(&mut reader).unwrap().is_empty());
+
+        // A rewrite from scratch replays nothing already returned.
+        fs::write(&path, "{\"seq\":0}\n").unwrap();
+        assert!(tail.read_new(&mut reader).unwrap().is_empty());
+    }
+
+    #[test]
+    fn worker_count_splits_active_from_finished_and_stale() {
+        let now = Utc::now();
+        let at = |secs_ago: i64| (now - chrono::Duration::seconds(secs_ago)).to_rfc3339();
+        let chain = [
+            serde_json::json!({"workerId": "planner", "type": "worker_end", "timestamp": at(5)}),
+            serde_json::json!({
+                "workerId": "builder",
+                "type": "note",
+                "timestamp": at(10),
+                "data": { "progress": 40 },
+            }),
+            serde_json::json!({"workerId": "tester", "type": "note", "timestamp": at(300)}),
+        ]
+        .map(|e| e.to_string())
+        .join("\n");
+
+        let count = worker_count(&worker_progress(&chain), now);
+        assert_eq!(
+            count,
+            WorkerCountInfo {
+                total_ever_seen: 3,
+                currently_active: 1,
+                completed: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn gate_trend_is_oldest_first_and_skips_runs_without_the_gate() {
+        let root = tempfile::tempdir().unwrap();
+        let runs = [
+            ("run-c", vec![("tests_pass", false), ("tests_pass", true)]),
+            ("run-b", vec![("spec_valid", true)]),
+            ("run-a", vec![("tests_pass", false)]),
+        ];
+        let mut history = Vec::new();
+        for (run_id, gates) in runs {
+            let gate_results: Vec<_> = gates
+                .iter()
+                .map(|(id, passed)| {
+                    serde_json::json!({
+                        "gateId": id,
+                        "passed": passed,
+                        "checks": [
+                            { "name": "c1", "passed": passed },
+                            { "name": "c2", "passed": true },
+                        ],
+                    })
+                })
+                .collect();
+            let dir = root.path().join(run_id);
+            fs::create_dir_all(&dir).unwrap();
+            let state = serde_json::json!({ "gateResults": gate_results });
+            fs::write(dir.join(STATE_FILE), state.to_string()).unwrap();
+            history.push(history_entry(&serde_json::json!({ "runId": run_id })));
+        }
+
+        let trend = gate_trend(root.path(), &history, "tests_pass");
+        let summary: Vec<_> = trend
+            .iter()
+            .map(|p| (p.run_id.as_str(), p.passed, p.checks_passed, p.checks_total))
+            .collect();
+        assert_eq!(summary, [("run-a", false, 1, 2), ("run-c", true, 2, 2)]);
+    }
+
+    #[test]
+    fn gate_history_keeps_runs_without_the_gate() {
+        let root = tempfile::tempdir().unwrap();
+        let runs = [
+            ("run-b", serde_json::json!([{ "gateId": "spec_valid", "passed": true }])),
+            (
+                "run-a",
+                serde_json::json!([
+                    { "gateId": "tests_pass", "passed": false },
+                    {
+                        "gateId": "tests_pass",
+                        "passed": true,
+                        "timestamp": "t1",
+                        "checks": [
+                            {
+                                "name": "coverage",
+                                "passed": true,
+                                "measured": 84.0,
+                                "threshold": 80.0,
+                            },
+                        ],
+                    },
+                ]),
+            ),
+        ];
+        let mut history = Vec::new();
+        for (run_id, gate_results) in runs {
+            let dir = root.path().join(run_id);
+            fs::create_dir_all(&dir).unwrap();
+            let state = serde_json::json!({ "gateResults": gate_results });
+            fs::write(dir.join(STATE_FILE), state.to_string()).unwrap();
+            history.push(history_entry(&serde_json::json!({ "runId": run_id })));
+        }
+
+        let entries = gate_history(root.path(), &history, "tests_pass");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].run_id, "run-b");
+        assert!(entries[0].gate_result.is_none());
+        let gate = entries[1].gate_result.as_ref().unwrap();
+        assert!(gate.passed);
+        assert_eq!(gate.timestamp, "t1");
+        assert_eq!(gate.checks[0].measured, 84.0);
+    }
+
+    #[test]
+    fn timeline_keeps_milestones_in_timestamp_order() {
+        let chain = [
+            r#"{"seq":0,"type":"run_start","timestamp":"t0","data":{}}"#,
+            r#"{"seq":2,"type":"gate_pass","timestamp":"t3","data":{"gateId":"plan_complete"}}"#,
+            r#"{"seq":1,"type":"note","timestamp":"t1","data":{"event":"stage_start"}}"#,
+            r#"{"seq":3,"type":"llm_call","timestamp":"t4","data":{}}"#,
+        ]
+        .join("\n");
+
+        let timeline = run_timeline(&chain);
+        let kinds: Vec<&str> = timeline.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(kinds, ["run_started", "stage_started", "gate_evaluated"]);
+        assert_eq!(timeline[2].description, "gate plan_complete passed");
+        assert_eq!(timeline[2].metadata["seq"], 2);
+    }
+
+    #[test]
+    fn long_timelines_keep_both_ends() {
+        let chain: Vec<String> = (0..1500)
+            .map(|i| format!(r#"{{"seq":{i},"type":"run_start","timestamp":"{i:05}"}}"#))
+            .collect();
+
+        let timeline = run_timeline(&chain.join("\n"));
+        assert_eq!(timeline.len(), MAX_TIMELINE_EVENTS);
+        assert_eq!(timeline[499].metadata["seq"], 499);
+        assert_eq!(timeline[500].metadata["seq"], 1000);
+    }
+
+    #[test]
+    fn corrupted_manifests_are_reported_and_skipped_by_history() {
+        let root = tempfile::tempdir().unwrap();
+        for (run_id, manifest) in [
+            (
+                "good",
+                r#"{"runId": "good", "status": "shipped", "startedAt": "t0", "durationMs": 5}"#,
+            ),
+            ("bad", "{ not json"),
+            (
+                "renamed",
+                r#"{"runId": "renamed", "status": "shipped", "started": "t0", "durationMs": 5}"#,
+            ),
+        ] {
+            let dir = root.path().join(run_id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+        }
+        fs::create_dir_all(root.path().join("in-progress")).unwrap();
+
+        let history = read_run_history(root.path(), true).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].run_id, "good");
+
+        let mut corrupted = corrupted_runs(root.path()).unwrap();
+        corrupted.sort_by(|a, b| a.run_id.cmp(&b.run_id));
+        assert_eq!(corrupted.len(), 2);
+        assert_eq!(corrupted[0].run_id, "bad");
+        assert!(corrupted[0].path.ends_with(MANIFEST_FILE));
+        assert!(!corrupted[0].error.is_empty());
+        assert_eq!(corrupted[1].run_id, "renamed");
+        assert!(corrupted[1].error.contains("startedAt"), "{}", corrupted[1].error);
+    }
+
+    #[test]
+    fn active_runs_are_included_unless_excluded() {
+        let root = tempfile::tempdir().unwrap();
+        TestRunBuilder::new()
+            .with_run_id("done")
+            .with_status("shipped")
+            .build(root.path())
+            .unwrap();
+        TestRunBuilder::new()
+            .with_run_id("active")
+            .with_started_at("2026-01-02T00:00:00Z")
+            .with_stages(&["spec_validation", "planning"])
+            .build(root.path())
+            .unwrap();
+
+        let history = read_run_history(root.path(), true).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].run_id, "active");
+        assert_eq!(history[0].status, "running");
+        assert_eq!(history[0].started_at, "2026-01-02T00:00:00.000Z");
+        assert_eq!(history[0].duration_ms, 0);
+
+        let completed = read_run_history(root.path(), false).unwrap();
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].run_id, "done");
+    }
+
+    #[test]
+    fn history_takes_spec_and_blueprint_from_run_inputs() {
+        let root = tempfile::tempdir().unwrap();
+        for run_id in ["launched", "external"] {
+            TestRunBuilder::new()
+                .with_run_id(run_id)
+                .with_status("shipped")
+                .build(root.path())
+                .unwrap();
+        }
+        let inputs = serde_json::json!({ "specPath": "specs/app.yaml", "blueprintPath": null });
+        fs::write(root.path().join("launched").join(RUN_INPUTS_FILE), inputs.to_string()).unwrap();
+
+        let history = read_run_history(root.path(), false).unwrap();
+        let entry = |id: &str| history.iter().find(|e| e.run_id == id).unwrap();
+        assert_eq!(entry("launched").spec_path.as_deref(), Some("specs/app.yaml"));
+        assert_eq!(entry("launched").blueprint_path, None);
+        assert_eq!(entry("external").spec_path, None);
+    }
+
+    #[test]
+    fn only_non_terminal_runs_are_active() {
+        let root = tempfile::tempdir().unwrap();
+        for (run_id, status) in [
+            ("a", "running"),
+            ("b", "paused"),
+            ("c", "shipped"),
+            ("d", "mystery"),
+        ] {
+            TestRunBuilder::new()
+                .with_run_id(run_id)
+                .with_status(status)
+                .build(root.path())
+                .unwrap();
+        }
+        fs::create_dir_all(root.path().join("no-state")).unwrap();
+
+        let mut active = active_run_ids(root.path()).unwrap();
+        active.sort();
+        assert_eq!(active, ["a", "b"]);
+    }
+
+    #[test]
+    fn run_count_groups_runs_by_status() {
+        let root = tempfile::tempdir().unwrap();
+        for (run_id, status) in [("a", "running"), ("b", "shipped"), ("c", "shipped")] {
+            TestRunBuilder::new()
+                .with_run_id(run_id)
+                .with_status(status)
+                .build(root.path())
+                .unwrap();
+        }
+        // Status past the prefix, and no status at all.
+        let padded = root.path().join("padded");
+        fs::create_dir_all(&padded).unwrap();
+        let padding = "x".repeat(RUN_STATUS_PREFIX_BYTES as usize);
+        let state = serde_json::json!({ "notes": padding, "status": "aborted" });
+        fs::write(padded.join(STATE_FILE), state.to_string()).unwrap();
+        fs::create_dir_all(root.path().join("blank")).unwrap();
+        fs::write(root.path().join("blank").join(STATE_FILE), "{}").unwrap();
+        fs::create_dir_all(root.path().join("no-state")).unwrap();
+
+        let count = run_count(root.path()).unwrap();
+        assert_eq!(count.total, 5);
+        assert_eq!(count.by_status["shipped"], 2);
+        assert_eq!(count.by_status["running"], 1);
+        assert_eq!(count.by_status["aborted"], 1);
+        assert_eq!(count.by_status["unknown"], 1);
+        assert_eq!(run_count(&root.path().join("missing")).unwrap(), RunCountSummary::default());
+    }
+
+    #[test]
+    fn latest_artifact_comes_from_the_newest_matching_run() {
+        let root = tempfile::tempdir().unwrap();
+        for (run_id, started_at, artifact) in [
+            ("old", "2026-01-01T00:00:00Z", "release/app-1.apk"),
+            ("new", "2026-01-03T00:00:00Z", "release/app-3.apk"),
+            ("web", "2026-01-04T00:00:00Z", "release/site.zip"),
+        ] {
+            TestRunBuilder::new()
+                .with_run_id(run_id)
+                .with_status("shipped")
+                .with_started_at(started_at)
+                .with_artifact(artifact, run_id)
+                .build(root.path())
+                .unwrap();
+        }
+
+        let found = latest_artifact(root.path(), "release/*.apk").unwrap().unwrap();
+        assert_eq!(found.run_id, "new");
+        assert_eq!(found.artifact.path, "release/app-3.apk");
+        assert_eq!(found.artifact.size_bytes, 3);
+        assert!(latest_artifact(root.path(), "*.ipa").unwrap().is_none());
+        assert!(matches!(
+            latest_artifact(root.path(), "../*.apk"),
+            Err(FactoryError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn run_logs_filter_by_minimum_level_and_keep_the_newest() {
+        let chain = [
+            r#"{"seq":0,"type":"run_start","workerId":"master","data":{}}"#,
+            r#"{"seq":1,"type":"llm_call","workerId":"planner","data":{}}"#,
+            r#"{"seq":2,"type":"note","workerId":"builder","data":{"level":"warn"}}"#,
+            r#"{"seq":3,"type":"gate_fail","workerId":"tester","data":{}}"#,
+            r#"{"seq":4,"type":"error","workerId":"builder","data":{}}"#,
+            r#"{"seq":5,"type":"note","workerId":"tester","data":{"level":"debug"}}"#,
+        ]
+        .join("\n");
+        let seqs = |level: &str, limit| {
+            let min = LOG_LEVELS.iter().position(|l| *l == level).unwrap();
+            run_logs(chain.as_bytes(), min, limit)
+                .unwrap()
+                .iter()
+                .map(|e| e.seq)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(seqs("debug", 1000), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(seqs("info", 1000), [0, 2, 3, 4]);
+        assert_eq!(seqs("warn", 1000), [2, 3, 4]);
+        assert_eq!(seqs("error", 1000), [4]);
+        assert_eq!(seqs("debug", 2), [4, 5]);
+    }
+
+    #[test]
+    fn artifact_content_truncates_text_and_refuses_large_binaries() {
+        use base64::Engine;
+        let decode = |c: &ArtifactContent| {
+            base64::engine::general_purpose::STANDARD.decode(&c.content_base64).unwrap()
+        };
+
+        let log = b"line one\nline two\n";
+        let whole = artifact_content("run.log".into(), &log[..], 18, 64).unwrap();
+        assert_eq!(decode(&whole), log);
+        assert!(!whole.truncated);
+
+        let head = artifact_content("run.log".into(), &log[..], 18, 4).unwrap();
+        assert_eq!(decode(&head), b"line");
+        assert!(head.truncated);
+        assert_eq!(head.total_bytes, 18);
+
+        let apk = [0x50, 0x4b, 0x03, 0x04, 0x00, 0x00, 0x08, 0x00];
+        let small = artifact_content("app.apk".into(), &apk[..], 8, 64).unwrap();
+        assert_eq!(decode(&small), apk);
+        let err = artifact_content("app.apk".into(), &apk[..], 8, 6).unwrap_err();
+        assert!(matches!(err, FactoryError::InvalidArgument(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn run_history_search_matches_chosen_fields_newest_first() {
+        let root = tempfile::tempdir().unwrap();
+        for (run_id, started_at, triggered_by, tags) in [
+            ("old", "2026-01-01T00:00:00Z", "Release Bot", vec!["nightly"]),
+            ("new", "2026-02-01T00:00:00Z", "alice", vec!["Release", "web"]),
+            ("other", "2026-03-01T00:00:00Z", "bob", vec![]),
+        ] {
+            let dir = root.path().join(run_id);
+            fs::create_dir_all(&dir).unwrap();
+            let manifest = serde_json::json!({
+                "runId": run_id,
+                "status": "shipped",
+                "startedAt": started_at,
+                "durationMs": 1,
+                "triggeredBy": triggered_by,
+                "tags": tags,
+            });
+            fs::write(dir.join(MANIFEST_FILE), manifest.to_string()).unwrap();
+            let inputs = serde_json::json!({ "specPath": "specs/app.yaml" });
+            fs::write(dir.join(RUN_INPUTS_FILE), inputs.to_string()).unwrap();
+        }
+        let search = |query: &str, fields: Option<&[String]>, max_results| {
+            let history = read_run_history(root.path(), false).unwrap();
+            search_history(root.path(), history, query, fields, max_results)
+        };
+
+        let found = search("RELEASE", None, 20);
+        let runs: Vec<_> = found.iter().map(|m| m.run_id.as_str()).collect();
+        assert_eq!(runs, ["new", "old"]);
+        assert_eq!(found[0].matched_fields, ["tags"]);
+        assert_eq!(found[1].matched_fields, ["triggered_by"]);
+        assert_eq!(found[0].entry.started_at, "2026-02-01T00:00:00Z");
+
+        let tags_only = ["tags".to_string()];
+        let found = search("release", Some(&tags_only), 20);
+        assert_eq!(found.len(), 1);
+        let found = search("app.yaml", None, 2);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].matched_fields, ["spec_path"]);
+    }
+
+    #[test]
+    fn evidence_search_spans_runs_newest_first() {
+        use crate::testing::{entry, EvidenceChainWriter};
+
+        let root = tempfile::tempdir().unwrap();
+        let runs = [("old", "2026-01-01T00:00:00Z"), ("new", "2026-01-02T00:00:00Z")];
+        for (run_id, started_at) in runs {
+            let run_dir = TestRunBuilder::new()
+                .with_run_id(run_id)
+                .with_status("aborted")
+                .with_started_at(started_at)
+                .build(root.path())
+                .unwrap();
+            let mut chain =
+                EvidenceChainWriter::new(&run_dir.path().join(EVIDENCE_CHAIN_FILE)).unwrap();
+            let mut failure = entry("error", "builder", "building");
+            failure.data = serde_json::json!({ "error": format!("gradle timeout in {}", run_id) });
+            chain.append_many(&[entry("note", "master", "planning"), failure]).unwrap();
+        }
+        let search = |query: &str, type_filter: Option<&str>, max: usize| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            search_runs_evidence(root.path(), query, type_filter, max, deadline).unwrap()
+        };
+
+        let hits = search("gradle", None, 10);
+        let runs: Vec<&str> = hits.iter().map(|m| m.run_id.as_str()).collect();
+        assert_eq!(runs, ["new", "old"]);
+        assert_eq!(hits[0].matched_field, "data.error");
+        assert_eq!(hits[0].entry.entry_type, "error");
+
+        assert_eq!(search("gradle", None, 1).len(), 1);
+        assert!(search("gradle", Some("note"), 10).is_empty());
+        assert_eq!(search("master", Some("note"), 10)[0].matched_field, "workerId");
+
+        let expired = Instant::now() - Duration::from_secs(1);
+        assert!(matches!(
+            search_runs_evidence(root.path(), "gradle", None, 10, expired),
+            Err(FactoryError::Timeout(_))
+        ));
+    }
+
+    #[test]
+    fn artifact_diff_compares_hashes_and_counts_changed_lines() {
+        let root = tempfile::tempdir().unwrap();
+        let runs = [("a", "one\ntwo\nthree\n"), ("b", "one\n2\nthree\nfour\n")];
+        let dirs: Vec<RunDir> = runs
+            .iter()
+            .map(|(run_id, notes)| {
+                TestRunBuilder::new()
+                    .with_run_id(run_id)
+                    .with_status("shipped")
+                    .with_artifact("notes.txt", *notes)
+                    .with_artifact("app.apk", "same bytes")
+                    .build(root.path())
+                    .unwrap()
+            })
+            .collect();
+        let diff = |name: &str| {
+            artifact_diff(
+                name.to_string(),
+                recorded_artifact(&dirs[0], name),
+                recorded_artifact(&dirs[1], name),
+            )
+        };
+
+        let notes = diff("notes.txt");
+        assert!(notes.changed);
+        assert_eq!((notes.size_a, notes.size_b), (Some(14), Some(17)));
+        let lines = notes.line_diff.unwrap();
+        assert_eq!((lines.lines_added, lines.lines_removed, lines.lines_unchanged), (2, 1, 2));
+
+        let apk = diff("app.apk");
+        assert!(!apk.changed);
+        assert_eq!(apk.hash_a, apk.hash_b);
+
+        let missing = diff("site.zip");
+        assert!(!missing.changed);
+        assert!(missing.hash_a.is_none() && missing.line_diff.is_none());
+    }
+
+    #[test]
+    fn runner_info_is_read_from_the_factory_json() {
+        let root = tempfile::tempdir().unwrap();
+        let run_dir = TestRunBuilder::new().build(root.path()).unwrap();
+        let missing = run_dir.read_typed::<RunnerInfo>(RUNNER_FILE);
+        assert!(matches!(missing, Err(FactoryError::IoError(_))));
+
+        let runner = serde_json::json!({
+            "hostname": "ci-7",
+            "os": "linux",
+            "arch": "x64",
+            "cpuCount": 8,
+            "memoryGb": 15.5,
+            "nodeVersion": "v20.11.0",
+            "factoryVersion": "0.4.0",
+        });
+        fs::write(run_dir.path().join(RUNNER_FILE), runner.to_string()).unwrap();
+        let info: RunnerInfo = run_dir.read_typed(RUNNER_FILE).unwrap();
+        assert_eq!((info.cpu_count, info.memory_gb), (8, 15.5));
+        assert_eq!(info.node_version, "v20.11.0");
+    }
+
+    #[test]
+    fn gates_filter_and_group_by_stage() {
+        let state = serde_json::json!({
+            "gateResults": [
+                { "gateId": "lint", "passed": true, "stage": "building" },
+                { "gateId": "e2e", "passed": false, "stage": "testing" },
+                { "gateId": "legacy", "passed": true },
+                { "gateId": "size", "passed": true, "stage": "building" },
+            ],
+        });
+
+        let building: Vec<String> =
+            stage_gates(&state, "building").into_iter().map(|g| g.gate_id).collect();
+        assert_eq!(building, ["lint", "size"]);
+        assert!(stage_gates(&state, "shipping").is_empty());
+        assert_eq!(gate_stages(&state), ["building", "testing"]);
+        assert!(gate_stages(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn run_errors_keeps_configured_types_with_their_messages() {
+        let chain = [
+            serde_json::json!({ "seq": 1, "type": "note", "data": { "message": "fine" } }),
+            serde_json::json!({ "seq": 2, "type": "error", "data": { "message": "build failed" } }),
+            serde_json::json!({ "seq": 3, "type": "gate-failure", "data": { "gateId": "e2e" } }),
+            serde_json::json!({ "seq": 4, "type": "fatal", "data": { "message": "oom" } }),
+        ]
+        .map(|e| e.to_string())
+        .join("\n");
+        let types = BridgeConfig::default().run_error_types;
+
+        let errors = run_errors(chain.as_bytes(), &types).unwrap();
+        let found: Vec<(u64, Option<&str>)> =
+            errors.iter().map(|e| (e.entry.seq, e.message.as_deref())).collect();
+        assert_eq!(found, [(2, Some("build failed")), (3, None), (4, Some("oom"))]);
+        assert!(run_errors(chain.as_bytes(), &["exception".to_string()]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn gate_anomalies_flag_checks_far_from_their_history() {
+        let check = |name: &str, measured: f64| GateCheck {
+            name: name.to_string(),
+            passed: true,
+            measured,
+            threshold: 0.0,
+        };
+        let result = |checks: Vec<GateCheck>| GateResult {
+            gate_id: "perf".to_string(),
+            passed: true,
+            timestamp: String::new(),
+            checks,
+            stage: None,
+        };
+        let past = |checks: Option<Vec<GateCheck>>| GateHistoryEntry {
+            run_id: "earlier".to_string(),
+            started_at: String::new(),
+            gate_result: checks.map(result),
+        };
+        let history = HashMap::from([(
+            "perf".to_string(),
+            vec![
+                past(Some(vec![check("lcp_ms", 1000.0), check("cls", 0.1), check("tti_ms", 5.0)])),
+                past(Some(vec![check("lcp_ms", 1200.0), check("cls", 0.1)])),
+                past(None),
+            ],
+        )]);
+        let current = [result(vec![
+            check("lcp_ms", 1500.0),
+            check("cls", 0.4),
+            check("tti_ms", 90.0),
+            check("new_check", 1.0),
+        ])];
+
+        let anomalies = gate_anomalies(&current, &history, 2.0);
+        assert_eq!(anomalies.len(), 1);
+        let lcp = &anomalies[0];
+        assert_eq!((lcp.gate_id.as_str(), lcp.check_name.as_str()), ("perf", "lcp_ms"));
+        assert_eq!((lcp.historical_mean, lcp.historical_stddev, lcp.z_score), (1100.0, 100.0, 4.0));
+        assert!(gate_anomalies(&current, &history, 5.0).is_empty());
+    }
+
+    #[test]
+    fn runner_comparison_flags_each_changed_field() {
+        let runner = RunnerInfo {
+            hostname: "ci-1".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            cpu_count: 8,
+            memory_gb: 16.0,
+            node_version: "v20.11.0".to_string(),
+            factory_version: "1.4.0".to_string(),
+        };
+        let moved = RunnerInfo {
+            hostname: "ci-2".to_string(),
+            cpu_count: 4,
+            memory_gb: 32.0,
+            ..runner.clone()
+        };
+
+        let same = runner_comparison(runner.clone(), runner.clone());
+        assert!(!same.hostname_changed && !same.os_changed && !same.cpu_count_changed);
+        assert!(!same.node_version_changed && !same.factory_version_changed);
+
+        let diff = runner_comparison(runner, moved.clone());
+        assert!(diff.hostname_changed && diff.cpu_count_changed);
+        assert!(!diff.os_changed && !diff.node_version_changed && !diff.factory_version_changed);
+        assert_eq!(diff.runner_b, moved);
+    }
+}
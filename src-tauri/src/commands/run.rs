@@ -1,39 +1,569 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 use std::sync::Mutex;
-use tauri::{AppHandle, State};
 
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, State, Webview};
+
+use crate::auth::{AuthContext, Role};
 use crate::bridge::FactoryBridge;
+use crate::commands::spec::{check_blueprint, check_spec};
+use crate::config::BridgeConfig;
 use crate::enforcer::FactoryCommand;
+use crate::envelope::CommandEnvelope;
+use crate::error::FactoryError;
+use crate::origin;
+use crate::rate_limit::RateLimiter;
+use crate::run_dir::{RunDir, ENVIRONMENT_FILE, RUN_INPUTS_FILE, SPEC_SNAPSHOT_FILE};
+use crate::types::{DryRunResult, RunInputs, StartRunOutcome};
+
+/// Where `clone_run` writes specs it patched with overrides.
+const CLONED_SPECS_DIR: &str = "ops/factory/cloned-specs";
 
+/// Input files larger than this are almost certainly a binary passed by
+/// mistake; the factory would reject them anyway.
+const MAX_INPUT_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// With `dry_run`, validates the spec and blueprint and reports whether the
+/// run would be accepted. The factory process is neither spawned nor sent
+/// anything.
+///
+/// Unless `skip_preflight` is set, the spec and blueprint files must exist,
+/// be readable and be at most 10 MB before anything else happens. Tests that
+/// drive a stand-in factory pass synthetic paths and set it.
+///
+/// `run_id`, typically from `generate_deterministic_run_id`, replaces the
+/// random ID; a run directory with that ID must not exist yet.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn start_run(
     app: AppHandle,
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    limiter: State<'_, RateLimiter>,
+    config: State<'_, BridgeConfig>,
     bridge: State<'_, Mutex<FactoryBridge>>,
     spec_path: String,
     blueprint_path: Option<String>,
-) -> Result<String, String> {
+    dry_run: Option<bool>,
+    skip_preflight: Option<bool>,
+    run_id: Option<String>,
+) -> Result<StartRunOutcome, FactoryError> {
+    let cmd = FactoryCommand::StartRun {
+        spec_path: spec_path.clone(),
+        blueprint_path: blueprint_path.clone(),
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    if !skip_preflight.unwrap_or(false) {
+        check_input_file(&config.working_dir, "spec", &spec_path)?;
+        if let Some(path) = &blueprint_path {
+            check_input_file(&config.working_dir, "blueprint", path)?;
+        }
+    }
+
+    if dry_run.unwrap_or(false) {
+        return dry_run_check(&config, spec_path, blueprint_path).map(StartRunOutcome::DryRun);
+    }
+    limiter.check(&cmd)?;
+
+    launch(&app, &config, &bridge, "start_run", run_id, spec_path, blueprint_path)
+        .map(StartRunOutcome::Started)
+}
+
+/// Spawn a factory process for a new run and send it the `StartRun`
+/// command on behalf of `caller`. Returns the new run ID: `run_id` if
+/// given, otherwise a random one.
+fn launch(
+    app: &AppHandle,
+    config: &BridgeConfig,
+    bridge: &Mutex<FactoryBridge>,
+    caller: &str,
+    run_id: Option<String>,
+    spec_path: String,
+    blueprint_path: Option<String>,
+) -> Result<String, FactoryError> {
+    let (run_id, claimed) = match run_id {
+        Some(id) => {
+            let run_dir = config.write_run_dir(&id)?;
+            run_dir.create()?;
+            (id, Some(run_dir))
+        }
+        None => (generate_run_id(), None),
+    };
+
+    let mut b = bridge
+        .lock()
+        .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+    if let Err(e) = b.spawn(app, &run_id) {
+        // Give the run ID back so the launch can be retried with it.
+        if let Some(run_dir) = claimed {
+            let _ = fs::remove_dir(run_dir.path());
+        }
+        return Err(e);
+    }
+    // Only a process that started gets the app's run files.
+    if let Err(e) = record_launch(config, &run_id, &spec_path, blueprint_path.as_deref()) {
+        let _ = b.kill_run(&run_id);
+        return Err(e);
+    }
     let cmd = FactoryCommand::StartRun {
         spec_path,
         blueprint_path,
     };
+    b.send_envelope(&run_id, &CommandEnvelope::traced(caller, cmd))?;
+    Ok(run_id)
+}
+
+/// Start a new run with the spec and blueprint of `source_run_id`. With
+/// `overrides`, the spec is first patched (JSON Merge Patch, RFC 7396),
+/// written under `ops/factory/cloned-specs/`; the source spec file is never
+/// modified. The clone only starts if its inputs pass a dry run.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn clone_run(
+    app: AppHandle,
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    limiter: State<'_, RateLimiter>,
+    config: State<'_, BridgeConfig>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+    source_run_id: String,
+    overrides: Option<serde_json::Value>,
+) -> Result<String, FactoryError> {
+    let clone = FactoryCommand::CloneRun {
+        source_run_id: source_run_id.clone(),
+        overrides: overrides.clone(),
+    };
+    auth.authorize(&clone)?;
+    origin::guard(&clone, &webview, &config.trusted_origins)?;
+    limiter.check(&clone)?;
+
+    let inputs = clone_inputs(&config, &source_run_id, overrides)?;
+    launch(&app, &config, &bridge, "clone_run", None, inputs.spec_path, inputs.blueprint_path)
+}
+
+/// What a clone of `source_run_id` is launched with: the source run's
+/// inputs, with the spec swapped for a patched copy if there are
+/// `overrides`. Either way the inputs get the preflight `start_run` does
+/// and must pass its dry-run validation.
+fn clone_inputs(
+    config: &BridgeConfig,
+    source_run_id: &str,
+    overrides: Option<serde_json::Value>,
+) -> Result<RunInputs, FactoryError> {
+    let source = clone_source(config, source_run_id)?;
+    let inputs = match overrides {
+        Some(patch) => RunInputs {
+            spec_path: write_patched_spec(&config.working_dir, &source.spec_path, &patch)?,
+            ..source
+        },
+        None => source,
+    };
+    let check = dry_run_check(config, inputs.spec_path.clone(), inputs.blueprint_path.clone())?;
+    if !check.would_start {
+        let errors: Vec<String> =
+            check.spec_errors.into_iter().chain(check.blueprint_errors).collect();
+        return Err(FactoryError::InvalidArgument(format!(
+            "cloned run would not start: {}",
+            errors.join("; ")
+        )));
+    }
+    Ok(inputs)
+}
+
+/// The inputs recorded for `source_run_id`, provided its spec and
+/// blueprint still pass `start_run`'s preflight.
+fn clone_source(config: &BridgeConfig, source_run_id: &str) -> Result<RunInputs, FactoryError> {
+    let source = required_run_inputs(&config.read_run_dir(source_run_id)?)?;
+    check_input_file(&config.working_dir, "spec", &source.spec_path)?;
+    if let Some(path) = &source.blueprint_path {
+        check_input_file(&config.working_dir, "blueprint", path)?;
+    }
+    Ok(source)
+}
+
+/// Write the files this app keeps about a new run into its directory.
+fn record_launch(
+    config: &BridgeConfig,
+    run_id: &str,
+    spec_path: &str,
+    blueprint_path: Option<&str>,
+) -> Result<(), FactoryError> {
+    record_inputs(config, run_id, spec_path, blueprint_path)?;
+    if config.snapshot_spec {
+        snapshot_spec(config, run_id, spec_path)?;
+    }
+    if config.capture_environment {
+        capture_environment(config, run_id)?;
+    }
+    Ok(())
+}
+
+/// Record what the new run is launched with, for `clone_run` and the
+/// queries that need the run's spec.
+fn record_inputs(
+    config: &BridgeConfig,
+    run_id: &str,
+    spec_path: &str,
+    blueprint_path: Option<&str>,
+) -> Result<(), FactoryError> {
+    let inputs = RunInputs {
+        spec_path: spec_path.to_string(),
+        blueprint_path: blueprint_path.map(String::from),
+    };
+    let file = config.write_run_dir(run_id)?.open_for_write(RUN_INPUTS_FILE)?;
+    serde_json::to_writer_pretty(file, &inputs).map_err(|e| {
+        FactoryError::IoError(format!("writing inputs for run {}: {}", run_id, e))
+    })
+}
+
+/// The run's `run-inputs.json`, or `None` for a run not launched from this
+/// app.
+pub(crate) fn run_inputs(run_dir: &RunDir) -> Result<Option<RunInputs>, FactoryError> {
+    if !run_dir.exists(RUN_INPUTS_FILE) {
+        return Ok(None);
+    }
+    run_dir.read_typed(RUN_INPUTS_FILE).map(Some)
+}
+
+/// Like `run_inputs`, for callers that cannot do without the spec.
+pub(crate) fn required_run_inputs(run_dir: &RunDir) -> Result<RunInputs, FactoryError> {
+    run_inputs(run_dir)?.ok_or_else(|| {
+        FactoryError::InvalidArgument(format!(
+            "run {} has no {}; it was not started from this app",
+            run_dir.run_id(),
+            RUN_INPUTS_FILE
+        ))
+    })
+}
+
+/// Copy the spec as it is now into the new run's directory.
+fn snapshot_spec(config: &BridgeConfig, run_id: &str, spec_path: &str) -> Result<(), FactoryError> {
+    let mut spec = fs::File::open(config.working_dir.join(spec_path))
+        .map_err(|e| FactoryError::IoError(format!("reading spec {}: {}", spec_path, e)))?;
+    let mut snapshot = config.write_run_dir(run_id)?.open_for_write(SPEC_SNAPSHOT_FILE)?;
+    std::io::copy(&mut spec, &mut snapshot)
+        .map(drop)
+        .map_err(|e| FactoryError::IoError(format!("snapshotting spec {}: {}", spec_path, e)))
+}
+
+/// Record this process's environment, which the factory process inherits,
+/// in the new run's directory.
+fn capture_environment(config: &BridgeConfig, run_id: &str) -> Result<(), FactoryError> {
+    let vars: BTreeMap<String, String> = std::env::vars_os()
+        .map(|(k, v)| (k.to_string_lossy().into_owned(), v.to_string_lossy().into_owned()))
+        .collect();
+    let file = config.write_run_dir(run_id)?.open_for_write(ENVIRONMENT_FILE)?;
+    serde_json::to_writer_pretty(file, &vars).map_err(|e| {
+        FactoryError::IoError(format!("writing environment for run {}: {}", run_id, e))
+    })
+}
+
+/// Apply `patch` to the YAML spec at `spec_path` and write the result to a
+/// new file, returning its path.
+fn write_patched_spec(
+    working_dir: &Path,
+    spec_path: &str,
+    patch: &serde_json::Value,
+) -> Result<String, FactoryError> {
+    let raw = fs::read_to_string(working_dir.join(spec_path))
+        .map_err(|e| FactoryError::IoError(format!("reading spec {}: {}", spec_path, e)))?;
+    let mut spec: serde_json::Value = serde_yaml::from_str(&raw)
+        .map_err(|e| FactoryError::ParseError(format!("spec {}: {}", spec_path, e)))?;
+    merge_patch(&mut spec, patch);
+
+    let yaml = serde_yaml::to_string(&spec)
+        .map_err(|e| FactoryError::ParseError(format!("serializing spec: {}", e)))?;
+    let dir = working_dir.join(CLONED_SPECS_DIR);
+    let path = dir.join(format!("{}.yaml", generate_run_id()));
+    fs::create_dir_all(&dir)
+        .and_then(|()| fs::write(&path, yaml))
+        .map_err(|e| FactoryError::IoError(format!("writing {}: {}", path.display(), e)))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// RFC 7396: objects merge recursively, `null` removes a member, and any
+/// other value replaces the target outright.
+pub(crate) fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(members) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(serde_json::Map::new());
+    }
+    if let serde_json::Value::Object(fields) = target {
+        for (key, value) in members {
+            if value.is_null() {
+                fields.remove(key);
+            } else {
+                merge_patch(fields.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+            }
+        }
+    }
+}
 
-    let mut b = bridge.lock().map_err(|e| e.to_string())?;
-    if !b.is_running() {
-        b.spawn(&app)?;
+/// `path` is resolved against the factory's working directory, as the
+/// factory process itself will resolve it.
+fn check_input_file(working_dir: &Path, label: &str, path: &str) -> Result<(), FactoryError> {
+    let resolved = working_dir.join(path);
+    let meta = fs::metadata(&resolved)
+        .map_err(|e| FactoryError::IoError(format!("{} file {}: {}", label, path, e)))?;
+    if !meta.is_file() {
+        return Err(FactoryError::IoError(format!("{} file {}: not a file", label, path)));
     }
-    b.send_command(cmd.to_bridge_json())?;
+    if meta.len() > MAX_INPUT_FILE_BYTES {
+        return Err(FactoryError::InvalidArgument(format!(
+            "{} file {} is {} bytes; the limit is {} (is it a binary?)",
+            label,
+            path,
+            meta.len(),
+            MAX_INPUT_FILE_BYTES
+        )));
+    }
+    fs::File::open(&resolved)
+        .map(drop)
+        .map_err(|e| FactoryError::IoError(format!("{} file {}: {}", label, path, e)))
+}
+
+fn dry_run_check(
+    config: &BridgeConfig,
+    spec_path: String,
+    blueprint_path: Option<String>,
+) -> Result<DryRunResult, FactoryError> {
+    let spec = check_spec(config, spec_path)?;
+    let (blueprint_valid, blueprint_errors) = match blueprint_path {
+        Some(path) => {
+            let blueprint = check_blueprint(config, path)?;
+            (blueprint.valid, blueprint.errors)
+        }
+        None => (true, vec![]),
+    };
 
-    Ok("run started".into())
+    Ok(DryRunResult {
+        would_start: spec.valid && blueprint_valid,
+        spec_valid: spec.valid,
+        blueprint_valid,
+        spec_errors: spec.errors,
+        blueprint_errors,
+    })
 }
 
 #[tauri::command]
 pub async fn abort_run(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    limiter: State<'_, RateLimiter>,
+    config: State<'_, BridgeConfig>,
     bridge: State<'_, Mutex<FactoryBridge>>,
     run_id: String,
-) -> Result<String, String> {
-    let cmd = FactoryCommand::AbortRun { run_id };
+) -> Result<String, FactoryError> {
+    let cmd = FactoryCommand::AbortRun {
+        run_id: run_id.clone(),
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    limiter.check(&cmd)?;
 
-    let b = bridge.lock().map_err(|e| e.to_string())?;
-    b.send_command(cmd.to_bridge_json())?;
+    let b = bridge
+        .lock()
+        .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+    b.send_envelope(&run_id, &CommandEnvelope::traced("abort_run", cmd))?;
 
     Ok("abort requested".into())
 }
+
+/// Random 12-hex-digit run ID, the same shape as the CLI's `generateRunId`.
+pub fn generate_run_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..12].to_string()
+}
+
+/// A run ID derived from the spec and blueprint contents and `timestamp`
+/// (RFC 3339), so the same inputs always give the same ID. Pass it as
+/// `start_run`'s `run_id`.
+#[tauri::command]
+pub async fn generate_deterministic_run_id(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    spec_path: String,
+    blueprint_path: Option<String>,
+    timestamp: String,
+) -> Result<String, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let read = |label: &str, path: &str| {
+        fs::read(config.working_dir.join(path))
+            .map_err(|e| FactoryError::IoError(format!("reading {} {}: {}", label, path, e)))
+    };
+    let spec = read("spec", &spec_path)?;
+    let blueprint = blueprint_path.map(|path| read("blueprint", &path)).transpose()?;
+    deterministic_run_id(&spec, blueprint.as_deref(), &timestamp)
+}
+
+/// `YYYYMMDD-` from `timestamp`'s date, then the first 16 bytes of the
+/// SHA-256 of the inputs in hex. Each input is length-prefixed so content
+/// can't shift from one into the next and hash the same.
+fn deterministic_run_id(
+    spec: &[u8],
+    blueprint: Option<&[u8]>,
+    timestamp: &str,
+) -> Result<String, FactoryError> {
+    let date = chrono::DateTime::parse_from_rfc3339(timestamp).map_err(|e| {
+        FactoryError::InvalidArgument(format!("timestamp {:?} is not RFC 3339: {}", timestamp, e))
+    })?;
+
+    let mut hasher = Sha256::new();
+    for part in [spec, blueprint.unwrap_or_default(), timestamp.as_bytes()] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher.update([blueprint.is_some() as u8]);
+    let digest = hasher.finalize();
+    let hash: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!("{}-{}", date.format("%Y%m%d"), hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_dir::MANIFEST_FILE;
+    use crate::testing::TestRunBuilder;
+
+    #[test]
+    fn input_files_must_exist_and_be_reasonably_small() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("spec.yaml"), "product:\n  name: x\n").unwrap();
+        let big = fs::File::create(root.path().join("big.bin")).unwrap();
+        big.set_len(MAX_INPUT_FILE_BYTES + 1).unwrap();
+
+        assert!(check_input_file(root.path(), "spec", "spec.yaml").is_ok());
+        assert!(matches!(
+            check_input_file(root.path(), "spec", "missing.yaml"),
+            Err(FactoryError::IoError(_))
+        ));
+        assert!(matches!(
+            check_input_file(root.path(), "spec", "."),
+            Err(FactoryError::IoError(_))
+        ));
+        assert!(matches!(
+            check_input_file(root.path(), "blueprint", "big.bin"),
+            Err(FactoryError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn merge_patch_follows_rfc_7396() {
+        let mut target = serde_json::json!({
+            "a": "b",
+            "c": { "d": "e", "f": "g" },
+            "list": [1, 2],
+        });
+        let patch = serde_json::json!({
+            "a": "z",
+            "c": { "f": null },
+            "list": [3],
+            "new": { "x": 1 },
+        });
+
+        merge_patch(&mut target, &patch);
+        assert_eq!(
+            target,
+            serde_json::json!({
+                "a": "z",
+                "c": { "d": "e" },
+                "list": [3],
+                "new": { "x": 1 },
+            })
+        );
+    }
+
+    #[test]
+    fn patched_spec_is_written_beside_not_over_the_source() {
+        let root = tempfile::tempdir().unwrap();
+        let source = "product:\n  name: Notes\n  version: 1.0.0\n";
+        fs::write(root.path().join("spec.yaml"), source).unwrap();
+
+        let patch = serde_json::json!({ "product": { "version": "1.1.0" } });
+        let path = write_patched_spec(root.path(), "spec.yaml", &patch).unwrap();
+
+        assert!(path.contains(CLONED_SPECS_DIR));
+        let patched: serde_json::Value =
+            serde_yaml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(patched["product"]["name"], "Notes");
+        assert_eq!(patched["product"]["version"], "1.1.0");
+        assert_eq!(fs::read_to_string(root.path().join("spec.yaml")).unwrap(), source);
+    }
+
+    #[test]
+    fn environment_is_captured_into_the_run_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let config = BridgeConfig {
+            working_dir: root.path().to_path_buf(),
+            ..BridgeConfig::default()
+        };
+        capture_environment(&config, "run-1").unwrap();
+
+        let captured: BTreeMap<String, String> =
+            config.read_run_dir("run-1").unwrap().read_typed(ENVIRONMENT_FILE).unwrap();
+        let path = std::env::var("PATH").unwrap();
+        assert_eq!(captured.get("PATH"), Some(&path));
+    }
+
+    #[test]
+    fn deterministic_run_ids_depend_only_on_their_inputs() {
+        let at = "2026-03-01T12:00:00Z";
+        let id = deterministic_run_id(b"spec", None, at).unwrap();
+        assert_eq!(id, deterministic_run_id(b"spec", None, at).unwrap());
+        assert!(id.starts_with("20260301-"));
+        assert_eq!(id.len(), "20260301-".len() + 32);
+        assert!(RunDir::for_write(Path::new("runs"), &id).is_ok());
+
+        assert_ne!(id, deterministic_run_id(b"spec", Some(b""), at).unwrap());
+        assert_ne!(id, deterministic_run_id(b"spec2", None, at).unwrap());
+        assert_ne!(id, deterministic_run_id(b"spec", None, "2026-03-01T12:00:01Z").unwrap());
+        assert_ne!(
+            deterministic_run_id(b"ab", Some(b"c"), at).unwrap(),
+            deterministic_run_id(b"a", Some(b"bc"), at).unwrap()
+        );
+        assert!(matches!(
+            deterministic_run_id(b"spec", None, "yesterday"),
+            Err(FactoryError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn clones_take_the_inputs_recorded_at_launch() {
+        let root = tempfile::tempdir().unwrap();
+        let config = BridgeConfig {
+            working_dir: root.path().to_path_buf(),
+            ..BridgeConfig::default()
+        };
+        for run_id in ["source", "external"] {
+            TestRunBuilder::new()
+                .with_run_id(run_id)
+                .with_status("shipped")
+                .build(&config.runs_root())
+                .unwrap();
+        }
+        record_inputs(&config, "source", "specs/app.yaml", Some("blueprints/web.yaml")).unwrap();
+
+        let manifest = config.read_run_dir("source").unwrap().read_json(MANIFEST_FILE).unwrap();
+        assert!(manifest.get("specPath").is_none());
+        assert!(matches!(clone_source(&config, "source"), Err(FactoryError::IoError(_))));
+
+        for dir in ["specs", "blueprints"] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+        fs::write(root.path().join("specs/app.yaml"), "product:\n  name: x\n").unwrap();
+        fs::write(root.path().join("blueprints/web.yaml"), "name: web\n").unwrap();
+        let inputs = clone_source(&config, "source").unwrap();
+        assert_eq!(inputs.spec_path, "specs/app.yaml");
+        assert_eq!(inputs.blueprint_path.as_deref(), Some("blueprints/web.yaml"));
+
+        assert!(matches!(
+            clone_source(&config, "external"),
+            Err(FactoryError::InvalidArgument(_))
+        ));
+    }
+}
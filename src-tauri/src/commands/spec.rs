@@ -1,8 +1,67 @@
-use crate::types::SpecValidationResult;
+use crate::auth::{AuthContext, Role};
+use crate::bridge::{FactoryBridge, PendingBatch};
+use crate::commands::query::STAGE_ORDER;
+use crate::commands::run::{merge_patch, required_run_inputs};
+use crate::config::BridgeConfig;
+use crate::enforcer::FactoryCommand;
+use crate::envelope::CommandEnvelope;
+use crate::error::FactoryError;
+use crate::origin;
+use crate::rate_limit::RateLimiter;
+use crate::run_dir::{RunDir, SPEC_SNAPSHOT_FILE};
+use crate::spec_migration;
+use crate::spec_schema;
+use crate::spec_template;
+use crate::types::{
+    BlueprintContent, BlueprintVariable, MigrationResult, SpecDiff, SpecValidationError,
+    SpecValidationResult, StageDependencyGraph,
+};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{State, Webview};
+
+/// How long to wait for the factory to answer a validation request.
+const BRIDGE_RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Optional spec fields the docs and store pipelines rely on. Missing
+/// ones are reported as warnings; they never make a spec invalid.
+const RECOMMENDED_FIELDS: &[&str] = &[
+    "product.contactEmail",
+    "product.website",
+    "product.legalEntity",
+    "product.dataCollectionSummary",
+];
 
 #[tauri::command]
-pub async fn validate_spec(spec_path: String) -> Result<SpecValidationResult, String> {
+pub async fn validate_spec(
+    auth: State<'_, AuthContext>,
+    limiter: State<'_, RateLimiter>,
+    config: State<'_, BridgeConfig>,
+    spec_path: String,
+) -> Result<SpecValidationResult, FactoryError> {
+    let cmd = FactoryCommand::ValidateSpec {
+        spec_path: spec_path.clone(),
+    };
+    auth.authorize(&cmd)?;
+    limiter.check(&cmd)?;
+    check_spec(&config, spec_path)
+}
+
+/// Run the factory's spec validator on `spec_path`, from the factory's
+/// working directory so relative paths resolve as they will for the run.
+/// Without Node.js on the PATH, the spec is checked against the embedded
+/// JSON Schema instead, which covers structure but not the factory's
+/// semantic rules.
+pub(crate) fn check_spec(
+    config: &BridgeConfig,
+    spec_path: String,
+) -> Result<SpecValidationResult, FactoryError> {
+    let recommended = serde_json::to_string(RECOMMENDED_FIELDS)
+        .map_err(|e| FactoryError::ParseError(e.to_string()))?;
     let output = Command::new("node")
         .args(["-e", &format!(
             r#"
@@ -12,21 +71,922 @@ pub async fn validate_spec(spec_path: String) -> Result<SpecValidationResult, St
             const raw = fs.readFileSync('{}', 'utf-8');
             const parsed = yaml.load(raw);
             const result = validateSpec(parsed);
+            const lookup = (obj, path) =>
+                path.split('.').reduce((v, k) => (v == null ? undefined : v[k]), obj);
+            const missing = {}
+                .filter(path => lookup(parsed, path) === undefined)
+                .map(path => `recommended field '${{path}}' is missing`);
+            const pointer = path => path === undefined ? null : path === '' ? '' :
+                '/' + String(path).split('.')
+                    .map(k => k.replace(/~/g, '~0').replace(/\//g, '~1')).join('/');
             console.log(JSON.stringify({{
                 valid: result.valid,
-                errors: result.errors ? result.errors.map(e => e.message) : []
+                errors: result.errors ? result.errors.map(e => e.message) : [],
+                warnings: (result.warnings || []).map(w => w.message).concat(missing),
+                error_details: (result.errors || []).map(e => ({{
+                    message: e.message,
+                    json_pointer: pointer(e.path),
+                    line: null,
+                    column: null
+                }}))
             }}));
             "#,
-            spec_path.replace('\\', "\\\\").replace('\'', "\\'")
+            spec_path.replace('\\', "\\\\").replace('\'', "\\'"),
+            recommended
         )])
-        .output()
-        .map_err(|e| format!("spawn error: {}", e))?;
+        .current_dir(&config.working_dir)
+        .output();
+    let output = match output {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return check_spec_against_schema(&config.working_dir, &spec_path);
+        }
+        Err(e) => {
+            return Err(FactoryError::ProcessFailed {
+                exit_code: None,
+                stderr: format!("spawn error: {}", e),
+            })
+        }
+    };
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("validation process failed: {}", stderr));
+        return Err(FactoryError::ProcessFailed {
+            exit_code: output.status.code(),
+            stderr: format!("validation process failed: {}", stderr),
+        });
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(stdout.trim()).map_err(|e| format!("parse error: {}", e))
+    serde_json::from_str(stdout.trim()).map_err(|e| {
+        FactoryError::ParseError(format!("validator output for {}: {}", spec_path, e))
+    })
+}
+
+/// Validate `spec_path`, relative to `working_dir`, against the embedded
+/// schema, in process.
+fn check_spec_against_schema(
+    working_dir: &Path,
+    spec_path: &str,
+) -> Result<SpecValidationResult, FactoryError> {
+    let raw = std::fs::read_to_string(working_dir.join(spec_path))
+        .map_err(|e| FactoryError::IoError(format!("reading spec {}: {}", spec_path, e)))?;
+    let (error_details, warnings) = match serde_yaml::from_str::<serde_json::Value>(&raw) {
+        Ok(spec) => (spec_schema::validate(&spec)?, missing_recommended_fields(&spec)),
+        Err(e) => {
+            let error = SpecValidationError {
+                message: format!("invalid YAML: {}", e),
+                json_pointer: None,
+                line: e.location().map(|l| l.line() as u32),
+                column: e.location().map(|l| l.column() as u32),
+            };
+            (vec![error], vec![])
+        }
+    };
+
+    Ok(SpecValidationResult {
+        valid: error_details.is_empty(),
+        errors: error_details.iter().map(|e| e.message.clone()).collect(),
+        warnings,
+        error_details,
+    })
+}
+
+/// A warning for each `RECOMMENDED_FIELDS` path the spec lacks, worded as
+/// the Node.js validator words them. A field set to `null` is present.
+fn missing_recommended_fields(spec: &serde_json::Value) -> Vec<String> {
+    RECOMMENDED_FIELDS
+        .iter()
+        .filter(|path| {
+            path.split('.')
+                .try_fold(spec, |value, key| match value {
+                    serde_json::Value::Null => None,
+                    value => value.get(key),
+                })
+                .is_none()
+        })
+        .map(|path| format!("recommended field '{}' is missing", path))
+        .collect()
+}
+
+/// The embedded spec JSON Schema for `version` (the current one by
+/// default), for editors that validate specs as they are typed.
+#[tauri::command]
+pub async fn get_spec_schema(
+    auth: State<'_, AuthContext>,
+    version: Option<String>,
+) -> Result<serde_json::Value, FactoryError> {
+    auth.authorize(&FactoryCommand::GetSpecSchema {
+        version: version.clone(),
+    })?;
+    let schema = spec_schema::schema_for(version.as_deref()).ok_or_else(|| {
+        FactoryError::InvalidArgument(format!(
+            "no spec schema for version {} (known: {})",
+            version.as_deref().unwrap_or_default(),
+            spec_schema::versions().join(", ")
+        ))
+    })?;
+    serde_json::from_str(schema)
+        .map_err(|e| FactoryError::ParseError(format!("embedded spec schema: {}", e)))
+}
+
+/// While a factory process is attached the blueprint is validated by the
+/// factory; otherwise it is checked here.
+#[tauri::command]
+pub async fn validate_blueprint(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+    blueprint_path: String,
+) -> Result<SpecValidationResult, FactoryError> {
+    let cmd = FactoryCommand::ValidateBlueprint {
+        blueprint_path: blueprint_path.clone(),
+    };
+    auth.authorize(&cmd)?;
+
+    let pending = {
+        let b = bridge
+            .lock()
+            .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+        let Some(run_id) = b.active_run_ids().into_iter().next() else {
+            return check_blueprint(&config, blueprint_path);
+        };
+        let envelope = CommandEnvelope::traced("validate_blueprint", cmd);
+        b.send_envelopes(&run_id, vec![envelope])?
+    };
+    let response = wait_for_response(pending).await?;
+    serde_json::from_value(response).map_err(|e| {
+        FactoryError::ParseError(format!("blueprint validation for {}: {}", blueprint_path, e))
+    })
+}
+
+/// Wait for a bridge response off the async runtime. Callers release the
+/// bridge lock first so other commands aren't blocked for the timeout.
+async fn wait_for_response(pending: PendingBatch) -> Result<serde_json::Value, FactoryError> {
+    tokio::task::spawn_blocking(move || pending.wait_one(BRIDGE_RESPONSE_TIMEOUT))
+        .await
+        .map_err(|e| FactoryError::BridgeError(format!("waiting for bridge response: {}", e)))?
+}
+
+/// `blueprint_path` is resolved against the factory's working directory.
+pub(crate) fn check_blueprint(
+    config: &BridgeConfig,
+    blueprint_path: String,
+) -> Result<SpecValidationResult, FactoryError> {
+    let raw = std::fs::read_to_string(config.working_dir.join(&blueprint_path)).map_err(|e| {
+        FactoryError::IoError(format!("reading blueprint {}: {}", blueprint_path, e))
+    })?;
+    Ok(check_blueprint_content(&raw))
+}
+
+/// Blueprints are YAML overlays applied on top of a spec, so the only
+/// structural requirement is a top-level mapping.
+fn check_blueprint_content(raw: &str) -> SpecValidationResult {
+    let errors = match serde_yaml::from_str::<serde_yaml::Value>(raw) {
+        Ok(serde_yaml::Value::Mapping(_)) => vec![],
+        Ok(_) => vec!["blueprint must be a YAML mapping at the top level".to_string()],
+        Err(e) => vec![format!("invalid YAML: {}", e)],
+    };
+
+    SpecValidationResult {
+        valid: errors.is_empty(),
+        errors,
+        warnings: vec![],
+        error_details: vec![],
+    }
+}
+
+/// A blueprint's YAML and whether it is valid, for reviewing it in the
+/// UI. Only files under `BridgeConfig::blueprint_dirs` can be read.
+#[tauri::command]
+pub async fn get_blueprint_content(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    blueprint_path: String,
+) -> Result<BlueprintContent, FactoryError> {
+    auth.authorize(&FactoryCommand::GetBlueprintContent {
+        blueprint_path: blueprint_path.clone(),
+    })?;
+    let resolved =
+        allowed_blueprint_path(&config.working_dir, &config.blueprint_dirs, &blueprint_path)?;
+    let content = std::fs::read_to_string(&resolved).map_err(|e| {
+        FactoryError::IoError(format!("reading blueprint {}: {}", blueprint_path, e))
+    })?;
+
+    Ok(BlueprintContent {
+        path: blueprint_path,
+        size_bytes: content.len() as u64,
+        validation: check_blueprint_content(&content),
+        content,
+    })
+}
+
+/// The variables a blueprint can be parameterized with, by name.
+#[tauri::command]
+pub async fn get_blueprint_variables(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    blueprint_path: String,
+) -> Result<Vec<BlueprintVariable>, FactoryError> {
+    auth.authorize(&FactoryCommand::GetBlueprintVariables {
+        blueprint_path: blueprint_path.clone(),
+    })?;
+    let resolved =
+        allowed_blueprint_path(&config.working_dir, &config.blueprint_dirs, &blueprint_path)?;
+    let content = std::fs::read_to_string(&resolved).map_err(|e| {
+        FactoryError::IoError(format!("reading blueprint {}: {}", blueprint_path, e))
+    })?;
+    blueprint_variables(&content, &blueprint_path)
+}
+
+/// Variables declared in the `variables:` mapping, where each value is
+/// either the default itself or a mapping of `default`, `description` and
+/// `required`, plus any `{{ name }}` marker not declared there. A declared
+/// variable is required if it has no default, unless it says otherwise; an
+/// undeclared marker always is. A blueprint with markers needn't be valid
+/// YAML until rendered, so it is only parsed if it has none.
+fn blueprint_variables(
+    content: &str,
+    source: &str,
+) -> Result<Vec<BlueprintVariable>, FactoryError> {
+    let markers: Vec<&str> = template_marker_re()
+        .captures_iter(content)
+        .map(|c| c.get(1).map_or("", |m| m.as_str()))
+        .collect();
+    let parsed = match serde_yaml::from_str::<serde_yaml::Value>(content) {
+        Ok(parsed) => parsed,
+        Err(_) if !markers.is_empty() => serde_yaml::Value::Null,
+        Err(e) => {
+            return Err(FactoryError::ParseError(format!("blueprint {}: {}", source, e)))
+        }
+    };
+
+    let mut variables: BTreeMap<String, BlueprintVariable> = BTreeMap::new();
+    if let Some(declared) = parsed.get("variables").and_then(|v| v.as_mapping()) {
+        for (name, spec) in declared {
+            let Some(name) = name.as_str() else { continue };
+            let default = match spec.get("default") {
+                Some(d) if spec.is_mapping() => Some(d),
+                _ if spec.is_mapping() => None,
+                _ => Some(spec),
+            }
+            .filter(|d| !d.is_null())
+            .and_then(|d| serde_json::to_value(d).ok());
+            let required = spec
+                .get("required")
+                .and_then(|r| r.as_bool())
+                .unwrap_or(default.is_none());
+            variables.insert(
+                name.to_string(),
+                BlueprintVariable {
+                    name: name.to_string(),
+                    description: spec.get("description").and_then(|d| d.as_str()).map(String::from),
+                    default_value: default,
+                    required,
+                },
+            );
+        }
+    }
+    for name in markers {
+        variables.entry(name.to_string()).or_insert_with(|| BlueprintVariable {
+            name: name.to_string(),
+            default_value: None,
+            description: None,
+            required: true,
+        });
+    }
+    Ok(variables.into_values().collect())
+}
+
+fn template_marker_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap())
+}
+
+/// `blueprint_path`, resolved against `working_dir` with symlinks
+/// followed, if it lies inside one of `allowed_dirs`.
+fn allowed_blueprint_path(
+    working_dir: &Path,
+    allowed_dirs: &[PathBuf],
+    blueprint_path: &str,
+) -> Result<PathBuf, FactoryError> {
+    let resolved = std::fs::canonicalize(working_dir.join(blueprint_path)).map_err(|e| {
+        FactoryError::IoError(format!("blueprint {}: {}", blueprint_path, e))
+    })?;
+    let allowed = allowed_dirs
+        .iter()
+        .filter_map(|dir| std::fs::canonicalize(working_dir.join(dir)).ok())
+        .any(|dir| resolved.starts_with(dir));
+    if !allowed {
+        return Err(FactoryError::InvalidArgument(format!(
+            "blueprint {} is outside the allowed blueprint directories",
+            blueprint_path
+        )));
+    }
+    Ok(resolved)
+}
+
+/// Turn plan text into spec YAML. The factory's planner does this while a
+/// process is attached; otherwise a stub is generated here from keywords
+/// in the plan.
+#[tauri::command]
+pub async fn derive_spec_from_plan(
+    auth: State<'_, AuthContext>,
+    limiter: State<'_, RateLimiter>,
+    bridge: State<'_, Mutex<FactoryBridge>>,
+    plan_text: String,
+) -> Result<String, FactoryError> {
+    let cmd = FactoryCommand::DeriveSpecFromPlan {
+        plan_text: plan_text.clone(),
+    };
+    auth.authorize(&cmd)?;
+    limiter.check(&cmd)?;
+
+    let pending = {
+        let b = bridge
+            .lock()
+            .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+        let Some(run_id) = b.active_run_ids().into_iter().next() else {
+            return spec_template::derive_spec(&plan_text);
+        };
+        let envelope = CommandEnvelope::traced("derive_spec_from_plan", cmd);
+        b.send_envelopes(&run_id, vec![envelope])?
+    };
+    let response = wait_for_response(pending).await?;
+    response["specYaml"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| FactoryError::ParseError("derived spec response has no specYaml".into()))
+}
+
+/// Upgrade a spec file to `target_version`, writing the result to
+/// `output_path` or, without one, back over `spec_path`. Both paths are
+/// resolved against the factory's working directory and must stay inside
+/// it; an `output_path` that already exists is never overwritten.
+#[tauri::command]
+pub async fn migrate_spec(
+    webview: Webview,
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    spec_path: String,
+    target_version: String,
+    output_path: Option<String>,
+) -> Result<MigrationResult, FactoryError> {
+    let cmd = FactoryCommand::MigrateSpec {
+        spec_path: spec_path.clone(),
+        target_version: target_version.clone(),
+        output_path: output_path.clone(),
+    };
+    auth.authorize(&cmd)?;
+    origin::guard(&cmd, &webview, &config.trusted_origins)?;
+    let source = working_dir_path(&config.working_dir, &spec_path)?;
+    let raw = std::fs::read_to_string(&source)
+        .map_err(|e| FactoryError::IoError(format!("reading spec {}: {}", spec_path, e)))?;
+    let spec: serde_yaml::Mapping = serde_yaml::from_str(&raw)
+        .map_err(|e| FactoryError::ParseError(format!("spec {}: {}", spec_path, e)))?;
+
+    let original_version = spec_migration::spec_version(&spec)?;
+    let (migrated, changes_made) =
+        spec_migration::migrate(spec, &target_version, &spec_migration::migrations())?;
+    let yaml = serde_yaml::to_string(&migrated)
+        .map_err(|e| FactoryError::ParseError(format!("serializing spec: {}", e)))?;
+
+    let output_path = match output_path {
+        Some(path) => {
+            write_new_file(&working_dir_path(&config.working_dir, &path)?, &yaml)
+                .map_err(|e| FactoryError::IoError(format!("writing spec {}: {}", path, e)))?;
+            path
+        }
+        None => {
+            std::fs::write(&source, yaml).map_err(|e| {
+                FactoryError::IoError(format!("writing spec {}: {}", spec_path, e))
+            })?;
+            spec_path
+        }
+    };
+
+    Ok(MigrationResult {
+        original_version,
+        target_version,
+        changes_made,
+        output_path,
+    })
+}
+
+/// `path` resolved against `working_dir`, refused if it leads outside it.
+/// The file itself need not exist, but its directory must; symlinks are
+/// resolved before the check.
+fn working_dir_path(working_dir: &Path, path: &str) -> Result<PathBuf, FactoryError> {
+    let joined = working_dir.join(path);
+    let (Some(dir), Some(name)) = (joined.parent(), joined.file_name()) else {
+        return Err(FactoryError::InvalidArgument(format!("{} is not a file path", path)));
+    };
+    let canonical = |p: &Path| {
+        std::fs::canonicalize(p).map_err(|e| FactoryError::IoError(format!("{}: {}", path, e)))
+    };
+    let resolved = match std::fs::symlink_metadata(&joined) {
+        Ok(_) => canonical(&joined)?,
+        Err(_) => canonical(dir)?.join(name),
+    };
+    if !resolved.starts_with(canonical(working_dir)?) {
+        return Err(FactoryError::InvalidArgument(format!(
+            "{} is outside the working directory",
+            path
+        )));
+    }
+    Ok(resolved)
+}
+
+/// Write `contents` to `path`, failing if something is already there.
+fn write_new_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(contents.as_bytes())
+}
+
+/// Compare a past run's spec, as snapshotted when it started, with the
+/// file at the same path today.
+#[tauri::command]
+pub async fn get_run_spec_diff(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<SpecDiff, FactoryError> {
+    auth.require(Role::ReadOnly)?;
+    let run_dir = config.read_run_dir(&run_id)?;
+    if !run_dir.exists(SPEC_SNAPSHOT_FILE) {
+        return Err(FactoryError::InvalidArgument(format!(
+            "run {} has no spec snapshot; spec snapshots were not being saved \
+             when it started (enable snapshot_spec)",
+            run_id
+        )));
+    }
+    let snapshot = run_dir.read_to_string(SPEC_SNAPSHOT_FILE)?;
+
+    let spec_path = required_run_inputs(&run_dir)?.spec_path;
+    let current = std::fs::read_to_string(config.working_dir.join(&spec_path))
+        .map_err(|e| FactoryError::IoError(format!("reading spec {}: {}", spec_path, e)))?;
+
+    let diff = line_diff(&snapshot, &current);
+    Ok(SpecDiff {
+        run_id,
+        spec_path,
+        changed: !diff.is_empty(),
+        diff,
+    })
+}
+
+/// The spec a run used, parsed from YAML into JSON. The snapshot taken at
+/// run start is preferred; without one, the file the run was started with
+/// is read as it is now.
+#[tauri::command]
+pub async fn get_spec(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<serde_json::Value, FactoryError> {
+    auth.authorize(&FactoryCommand::GetSpec {
+        run_id: run_id.clone(),
+    })?;
+    let (content, source) = run_spec(&config, &config.read_run_dir(&run_id)?)?;
+    spec_to_json(&content, &source)
+}
+
+/// A run's spec YAML and where it was read from: the snapshot if there is
+/// one, otherwise the spec file from `run-inputs.json` as it is now.
+fn run_spec(config: &BridgeConfig, run_dir: &RunDir) -> Result<(String, String), FactoryError> {
+    if run_dir.exists(SPEC_SNAPSHOT_FILE) {
+        let content = run_dir.read_to_string(SPEC_SNAPSHOT_FILE)?;
+        return Ok((content, SPEC_SNAPSHOT_FILE.to_string()));
+    }
+    let spec_path = required_run_inputs(run_dir)?.spec_path;
+    let content = std::fs::read_to_string(config.working_dir.join(&spec_path))
+        .map_err(|e| FactoryError::IoError(format!("reading spec {}: {}", spec_path, e)))?;
+    Ok((content, spec_path))
+}
+
+/// The spec at `spec_path` with the blueprint at `blueprint_path` applied
+/// as a JSON Merge Patch, checked against the spec schema. The blueprint
+/// must lie under `BridgeConfig::blueprint_dirs`.
+#[tauri::command]
+pub async fn get_blueprint_spec(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    spec_path: String,
+    blueprint_path: String,
+) -> Result<serde_json::Value, FactoryError> {
+    auth.authorize(&FactoryCommand::GetBlueprintSpec {
+        spec_path: spec_path.clone(),
+        blueprint_path: blueprint_path.clone(),
+    })?;
+    let spec = std::fs::read_to_string(config.working_dir.join(&spec_path))
+        .map_err(|e| FactoryError::IoError(format!("reading spec {}: {}", spec_path, e)))?;
+    let resolved =
+        allowed_blueprint_path(&config.working_dir, &config.blueprint_dirs, &blueprint_path)?;
+    let blueprint = std::fs::read_to_string(&resolved).map_err(|e| {
+        FactoryError::IoError(format!("reading blueprint {}: {}", blueprint_path, e))
+    })?;
+    blueprint_spec(&spec, &spec_path, &blueprint, &blueprint_path)
+}
+
+/// Merge the `blueprint` YAML into the `spec` YAML and validate the result.
+/// A merged spec that fails the schema is an `InvalidArgument` listing
+/// every violation.
+fn blueprint_spec(
+    spec: &str,
+    spec_source: &str,
+    blueprint: &str,
+    blueprint_source: &str,
+) -> Result<serde_json::Value, FactoryError> {
+    let mut merged = spec_to_json(spec, spec_source)?;
+    merge_patch(&mut merged, &spec_to_json(blueprint, blueprint_source)?);
+
+    let errors = spec_schema::validate(&merged)?;
+    if !errors.is_empty() {
+        return Err(FactoryError::InvalidArgument(format!(
+            "{} with blueprint {} is not a valid spec: {}",
+            spec_source,
+            blueprint_source,
+            errors.iter().map(|e| e.message.as_str()).collect::<Vec<_>>().join("; ")
+        )));
+    }
+    Ok(merged)
+}
+
+fn spec_to_json(content: &str, source: &str) -> Result<serde_json::Value, FactoryError> {
+    let spec: serde_yaml::Value = serde_yaml::from_str(content)
+        .map_err(|e| FactoryError::ParseError(format!("spec {}: {}", source, e)))?;
+    serde_json::to_value(spec)
+        .map_err(|e| FactoryError::ParseError(format!("spec {} as JSON: {}", source, e)))
+}
+
+/// The lines that differ between `old` and `new`, in order. Removed lines
+/// are prefixed `-`, added lines `+`.
+fn line_diff(old: &str, new: &str) -> Vec<String> {
+    similar::TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .filter_map(|change| {
+            let sign = match change.tag() {
+                similar::ChangeTag::Delete => '-',
+                similar::ChangeTag::Insert => '+',
+                similar::ChangeTag::Equal => return None,
+            };
+            Some(format!("{}{}", sign, change.value().trim_end_matches(['\r', '\n'])))
+        })
+        .collect()
+}
+
+/// The run's stage dependency graph. A spec can replace the default
+/// pipeline under `extensions.pipeline`, in the shape
+/// `PipelineConfig.create` takes (`stages: [{ id, dependsOn }]`); without
+/// one the run used the default pipeline, one stage after another. The
+/// spec snapshot is read if there is one, as `get_spec` does.
+#[tauri::command]
+pub async fn get_stage_dependencies(
+    auth: State<'_, AuthContext>,
+    config: State<'_, BridgeConfig>,
+    run_id: String,
+) -> Result<StageDependencyGraph, FactoryError> {
+    auth.authorize(&FactoryCommand::GetStageDependencies {
+        run_id: run_id.clone(),
+    })?;
+    let (content, source) = run_spec(&config, &config.read_run_dir(&run_id)?)?;
+    let spec: serde_yaml::Value = serde_yaml::from_str(&content)
+        .map_err(|e| FactoryError::ParseError(format!("spec {}: {}", source, e)))?;
+    stage_dependencies(&spec)
+}
+
+fn stage_dependencies(spec: &serde_yaml::Value) -> Result<StageDependencyGraph, FactoryError> {
+    let stages: Vec<(String, Vec<String>)> =
+        match spec["extensions"]["pipeline"]["stages"].as_sequence() {
+            Some(stages) => stages
+                .iter()
+                .map(|stage| {
+                    let id = stage["id"].as_str().ok_or_else(|| {
+                        FactoryError::ParseError("pipeline stage without an id".into())
+                    })?;
+                    let depends_on = stage["dependsOn"]
+                        .as_sequence()
+                        .map(Vec::as_slice)
+                        .unwrap_or_default()
+                        .iter()
+                        .filter_map(|dep| dep.as_str().map(String::from))
+                        .collect();
+                    Ok((id.to_string(), depends_on))
+                })
+                .collect::<Result<_, FactoryError>>()?,
+            None => STAGE_ORDER
+                .iter()
+                .enumerate()
+                .map(|(i, id)| {
+                    let previous = i.checked_sub(1).map(|p| STAGE_ORDER[p].to_string());
+                    (id.to_string(), previous.into_iter().collect())
+                })
+                .collect(),
+        };
+
+    let index = |id: &str| stages.iter().position(|(stage, _)| stage == id);
+    for (i, (id, depends_on)) in stages.iter().enumerate() {
+        if index(id) != Some(i) {
+            return Err(FactoryError::ParseError(format!("duplicate stage id: '{}'", id)));
+        }
+        if let Some(dep) = depends_on.iter().find(|dep| index(dep).is_none()) {
+            return Err(FactoryError::ParseError(format!(
+                "unknown stage dependency: '{}' (referenced by '{}')",
+                dep, id
+            )));
+        }
+    }
+
+    // Kahn's algorithm, taking ready stages in id order as the factory
+    // does, so `nodes` matches its execution order.
+    let mut remaining: Vec<usize> = stages.iter().map(|(_, deps)| deps.len()).collect();
+    let mut ready: Vec<usize> = (0..stages.len()).filter(|i| remaining[*i] == 0).collect();
+    let mut order = Vec::with_capacity(stages.len());
+    while !ready.is_empty() {
+        ready.sort_by(|a, b| stages[*b].0.cmp(&stages[*a].0));
+        let next = ready.pop().unwrap();
+        order.push(next);
+        for (i, (_, deps)) in stages.iter().enumerate() {
+            for _ in deps.iter().filter(|dep| **dep == stages[next].0) {
+                remaining[i] -= 1;
+                if remaining[i] == 0 {
+                    ready.push(i);
+                }
+            }
+        }
+    }
+    if order.len() < stages.len() {
+        let cyclic: Vec<&str> = (0..stages.len())
+            .filter(|i| !order.contains(i))
+            .map(|i| stages[i].0.as_str())
+            .collect();
+        return Err(FactoryError::ParseError(format!(
+            "cycle detected among stages: {}",
+            cyclic.join(", ")
+        )));
+    }
+
+    // Longest path by stage count: each stage extends the longest chain
+    // among its dependencies, the first listed on a tie.
+    let mut length = vec![0usize; stages.len()];
+    let mut via: Vec<Option<usize>> = vec![None; stages.len()];
+    for &stage in &order {
+        for dep in stages[stage].1.iter().filter_map(|dep| index(dep)) {
+            let better = match via[stage] {
+                None => true,
+                Some(current) => length[dep] > length[current],
+            };
+            if better {
+                length[stage] = length[dep];
+                via[stage] = Some(dep);
+            }
+        }
+        length[stage] += 1;
+    }
+    let mut critical_path = Vec::new();
+    let mut at = order.iter().copied().reduce(|a, b| if length[b] > length[a] { b } else { a });
+    while let Some(stage) = at {
+        critical_path.push(stages[stage].0.clone());
+        at = via[stage];
+    }
+    critical_path.reverse();
+
+    Ok(StageDependencyGraph {
+        nodes: order.iter().map(|&i| stages[i].0.clone()).collect(),
+        edges: order
+            .iter()
+            .flat_map(|&i| {
+                let (stage, depends_on) = &stages[i];
+                depends_on.iter().map(move |dep| (dep.clone(), stage.clone()))
+            })
+            .collect(),
+        critical_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_diff_reports_only_changed_lines() {
+        let old = "product:\n  name: Demo\n  version: 1.0.0\nfeatures: []\n";
+        let new = "product:\n  name: Demo\n  version: 1.1.0\nfeatures: []\njourneys: []\n";
+
+        assert_eq!(
+            line_diff(old, new),
+            ["-  version: 1.0.0", "+  version: 1.1.0", "+journeys: []"]
+        );
+        assert!(line_diff(old, old).is_empty());
+    }
+
+    #[test]
+    fn spec_yaml_converts_to_json_and_back() {
+        let yaml = concat!(
+            "product:\n  name: Demo\n  version: 1.0.0\n",
+            "delivery:\n  targets: [web, android]\n",
+        );
+        let json = spec_to_json(yaml, "spec.yaml").unwrap();
+        assert_eq!(json["product"]["version"], "1.0.0");
+        assert_eq!(json["delivery"]["targets"][1], "android");
+
+        let back: serde_yaml::Value =
+            serde_yaml::from_str(&serde_yaml::to_string(&json).unwrap()).unwrap();
+        assert_eq!(back, serde_yaml::from_str::<serde_yaml::Value>(yaml).unwrap());
+        assert!(matches!(spec_to_json("a: [", "bad.yaml"), Err(FactoryError::ParseError(_))));
+    }
+
+    #[test]
+    fn migration_paths_stay_inside_the_working_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let working_dir = root.path().join("work");
+        std::fs::create_dir_all(working_dir.join("specs")).unwrap();
+        std::fs::write(working_dir.join("specs/app.yaml"), "product: {}\n").unwrap();
+
+        let spec = working_dir_path(&working_dir, "specs/app.yaml").unwrap();
+        assert!(spec.ends_with("specs/app.yaml"));
+        assert!(working_dir_path(&working_dir, "specs/new.yaml").is_ok());
+        for outside in ["../elsewhere.yaml", "specs/../../elsewhere.yaml", "/tmp/x.yaml"] {
+            assert!(matches!(
+                working_dir_path(&working_dir, outside),
+                Err(FactoryError::InvalidArgument(_))
+            ));
+        }
+
+        assert!(write_new_file(&spec, "overwritten").is_err());
+        assert_eq!(std::fs::read_to_string(&spec).unwrap(), "product: {}\n");
+    }
+
+    #[test]
+    fn blueprints_are_read_only_from_allowed_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        let blueprints = root.path().join("blueprints");
+        std::fs::create_dir_all(&blueprints).unwrap();
+        std::fs::write(blueprints.join("web.yaml"), "quality:\n  min_coverage: 90\n").unwrap();
+        std::fs::write(root.path().join("secrets.yaml"), "token: x\n").unwrap();
+        let allowed = [PathBuf::from("blueprints")];
+
+        let path = allowed_blueprint_path(root.path(), &allowed, "blueprints/web.yaml").unwrap();
+        assert!(check_blueprint_content(&std::fs::read_to_string(path).unwrap()).valid);
+        for outside in ["secrets.yaml", "blueprints/../secrets.yaml"] {
+            assert!(matches!(
+                allowed_blueprint_path(root.path(), &allowed, outside),
+                Err(FactoryError::InvalidArgument(_))
+            ));
+        }
+        assert!(!check_blueprint_content("- just\n- a list\n").valid);
+    }
+
+    #[test]
+    fn critical_path_follows_the_longest_dependency_chain() {
+        let spec: serde_yaml::Value = serde_yaml::from_str(
+            "
+extensions:
+  pipeline:
+    stages:
+      - { id: spec_validation }
+      - { id: docs, dependsOn: [spec_validation] }
+      - { id: planning, dependsOn: [spec_validation] }
+      - { id: building, dependsOn: [planning] }
+      - { id: release, dependsOn: [building, docs] }
+",
+        )
+        .unwrap();
+
+        let graph = stage_dependencies(&spec).unwrap();
+        assert_eq!(graph.nodes, ["spec_validation", "docs", "planning", "building", "release"]);
+        assert_eq!(graph.edges.len(), 5);
+        assert!(graph.edges.contains(&("docs".into(), "release".into())));
+        assert_eq!(graph.critical_path, ["spec_validation", "planning", "building", "release"]);
+    }
+
+    #[test]
+    fn default_pipeline_is_one_chain_and_cycles_are_rejected() {
+        let graph = stage_dependencies(&serde_yaml::Value::Null).unwrap();
+        assert_eq!(graph.nodes, STAGE_ORDER);
+        assert_eq!(graph.critical_path, STAGE_ORDER);
+        assert_eq!(graph.edges.len(), STAGE_ORDER.len() - 1);
+
+        let cyclic: serde_yaml::Value = serde_yaml::from_str(
+            "extensions: { pipeline: { stages: [\
+             { id: a, dependsOn: [b] }, { id: b, dependsOn: [a] }, { id: c }] } }",
+        )
+        .unwrap();
+        let err = stage_dependencies(&cyclic).unwrap_err();
+        assert!(err.to_string().contains("a, b"), "{}", err);
+    }
+
+    #[test]
+    fn blueprint_spec_merges_and_validates_the_result() {
+        let spec = concat!(
+            "schema_version: \"2026.1\"\n",
+            "product:\n  name: Demo\n  version: 1.0.0\n",
+            "  description: A demo app\n  platforms: [web]\n",
+            "features: []\n",
+            "journeys:\n  - id: j1\n    name: Open\n    exercisesFeatures: [f1]\n",
+            "    steps:\n      - action: navigate\n",
+            "quality:\n  coverage: 80\n",
+            "delivery:\n  targets: [web]\n",
+        );
+        let blueprint = concat!(
+            "product:\n  version: 2.0.0\n",
+            "features:\n  - id: f1\n    description: Open the app\n",
+            "    acceptanceCriteria: [It opens]\n",
+            "quality:\n  coverage: null\n",
+        );
+
+        let merged = blueprint_spec(spec, "spec.yaml", blueprint, "bp.yaml").unwrap();
+        assert_eq!(merged["product"]["name"], "Demo");
+        assert_eq!(merged["product"]["version"], "2.0.0");
+        assert_eq!(merged["features"][0]["id"], "f1");
+        assert!(merged["quality"].as_object().unwrap().is_empty());
+
+        let err = blueprint_spec(spec, "spec.yaml", "quality: {}\n", "bp.yaml").unwrap_err();
+        assert!(matches!(err, FactoryError::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn blueprint_variables_come_from_the_section_and_markers() {
+        let blueprint = concat!(
+            "variables:\n",
+            "  api_url:\n    default: https://api.example.com\n",
+            "    description: Backend the app talks to\n",
+            "  coverage: 80\n",
+            "  region:\n    description: Deployment region\n",
+            "  tier:\n    default: free\n    required: true\n",
+            "product:\n  website: \"{{ api_url }}/docs\"\n  name: \"{{app_name}}\"\n",
+        );
+
+        let variables = blueprint_variables(blueprint, "bp.yaml").unwrap();
+        let summary: Vec<(&str, Option<serde_json::Value>, bool)> = variables
+            .iter()
+            .map(|v| (v.name.as_str(), v.default_value.clone(), v.required))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("api_url", Some(serde_json::json!("https://api.example.com")), false),
+                ("app_name", None, true),
+                ("coverage", Some(serde_json::json!(80)), false),
+                ("region", None, true),
+                ("tier", Some(serde_json::json!("free")), true),
+            ]
+        );
+        assert_eq!(variables[0].description.as_deref(), Some("Backend the app talks to"));
+
+        let unquoted = blueprint_variables("url: {{ base }}/v1\n", "bp.yaml").unwrap();
+        assert_eq!(unquoted[0].name, "base");
+        assert!(blueprint_variables("a: [", "bp.yaml").is_err());
+    }
+
+    #[test]
+    fn spec_and_blueprint_paths_resolve_against_the_working_dir() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("specs")).unwrap();
+        std::fs::write(root.path().join("specs/app.yaml"), "product: [\n").unwrap();
+        std::fs::write(root.path().join("specs/bp.yaml"), "product: {}\n").unwrap();
+        let config = BridgeConfig {
+            working_dir: root.path().to_path_buf(),
+            ..BridgeConfig::default()
+        };
+
+        let spec = check_spec_against_schema(&config.working_dir, "specs/app.yaml").unwrap();
+        assert!(!spec.valid);
+        assert!(spec.errors[0].starts_with("invalid YAML"), "{:?}", spec.errors);
+        assert!(check_blueprint(&config, "specs/bp.yaml".into()).unwrap().valid);
+        let elsewhere = BridgeConfig {
+            working_dir: root.path().join("specs"),
+            ..BridgeConfig::default()
+        };
+        assert!(matches!(
+            check_blueprint(&elsewhere, "specs/bp.yaml".into()),
+            Err(FactoryError::IoError(_))
+        ));
+    }
+
+    #[test]
+    fn schema_fallback_warns_about_missing_recommended_fields() {
+        let root = tempfile::tempdir().unwrap();
+        let spec = concat!(
+            "schema_version: \"2026.1\"\n",
+            "product:\n  name: Demo\n  version: 1.0.0\n",
+            "  description: A demo app\n  platforms: [web]\n",
+            "  website: https://example.com\n  legalEntity: null\n",
+        );
+        std::fs::write(root.path().join("spec.yaml"), spec).unwrap();
+
+        let result = check_spec_against_schema(root.path(), "spec.yaml").unwrap();
+        assert_eq!(
+            result.warnings,
+            vec![
+                "recommended field 'product.contactEmail' is missing",
+                "recommended field 'product.dataCollectionSummary' is missing",
+            ]
+        );
+        assert_eq!(missing_recommended_fields(&serde_json::json!({})).len(), 4);
+    }
 }
@@ -0,0 +1,238 @@
+//! BridgeConfig — tunables for how the UI talks to the factory.
+//!
+//! Managed as Tauri state; every field has a default that preserves the
+//! behavior the app had before the option existed.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audit::AUDIT_LOG_FILE;
+use crate::bridge::CRASH_LOG_FILE;
+use crate::consequence::{CONSEQUENCE_MEMORY_FILE, CONSEQUENCE_METADATA_FILE};
+use crate::error::FactoryError;
+use crate::origin::{BUNDLED_ORIGINS, DEV_SERVER_ORIGIN};
+use crate::event_store::EVENT_STORE_FILE;
+use crate::pid_file::PID_FILE;
+use crate::rate_limit::RateLimits;
+use crate::run_dir::{RunDir, RUNS_ROOT};
+
+/// Paths whose presence marks a directory as the factory project root.
+const WORKING_DIR_MARKERS: &[&str] = &["ops/factory", "dist/cli/index.js"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeConfig {
+    pub run_history_source: RunHistorySource,
+    /// How many factory processes may run at once, one per run.
+    pub max_concurrent_runs: usize,
+    /// Project root. The factory process is spawned here and every
+    /// `ops/factory/...` path is resolved against it.
+    pub working_dir: PathBuf,
+    /// Per-second command limits enforced before anything is sent to
+    /// the factory process.
+    pub rate_limits: RateLimits,
+    /// On startup, re-attach to factory processes a previous session left
+    /// running (live PIDs in the PID file) rather than leaving them orphaned.
+    pub auto_reconnect: bool,
+    /// Oldest Node.js release the factory may be spawned with, such as
+    /// `"18.0.0"`. Checked with `node --version` before every spawn.
+    pub min_node_version: Option<String>,
+    /// Copy the spec into the run directory when a run starts, so
+    /// `get_run_spec_diff` can later show how the file has changed.
+    pub snapshot_spec: bool,
+    /// Write the app's environment variables into the run directory when
+    /// a run starts, for `get_run_environment`. Off by default: the
+    /// environment often holds tokens.
+    pub capture_environment: bool,
+    /// How commands reach a spawned factory process.
+    pub ipc_mode: IpcMode,
+    /// Playback rate for `IpcMode::SimulationMode`: 2.0 replays a session
+    /// twice as fast as it was recorded.
+    pub replay_speed: f32,
+    /// Write every event line a factory process prints to a recording
+    /// under `ops/factory/sessions`, for later replay.
+    pub record_session: bool,
+    /// Mutual TLS on the Unix socket a reconnecting bridge attaches
+    /// through, for a factory running as another user. Unencrypted when
+    /// unset.
+    pub tls: Option<TlsConfig>,
+    /// Directories `get_blueprint_content` may read from. Relative paths
+    /// are resolved against `working_dir`; by default the whole project.
+    pub blueprint_dirs: Vec<PathBuf>,
+    /// Purge consequence memory domains past their TTL when the app
+    /// starts (see `set_consequence_memory_ttl`).
+    pub auto_expire_consequence_memory: bool,
+    /// Event names, such as `factory://worker-progress`, not forwarded to
+    /// the frontend. They are still counted and tracked for progress.
+    /// Replaced at runtime by `update_suppressed_events`.
+    pub suppressed_events: Vec<String>,
+    /// Emit `factory://bridge-error` when a spawned factory process writes
+    /// more than this many stderr lines within 10 seconds. 0 turns the
+    /// warning off; stderr is still logged at debug level.
+    pub stderr_warning_threshold: u32,
+    /// Largest serialized command, in bytes, written to a factory's stdin.
+    /// A bigger one is refused before anything is written, so a huge
+    /// parameter can't block the pipe.
+    pub max_command_bytes: usize,
+    /// Insert routed factory events into the SQLite store at
+    /// `ops/factory/event-store.db`, for `query_event_store`.
+    pub persist_events: bool,
+    /// Evidence entry types `get_run_errors` treats as errors.
+    pub run_error_types: Vec<String>,
+    /// Origins a sensitive command such as `StartRun` is accepted from.
+    /// The bundled frontend's, plus the dev server's in debug builds.
+    pub trusted_origins: Vec<String>,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            run_history_source: RunHistorySource::default(),
+            max_concurrent_runs: 1,
+            working_dir: auto_detect_working_dir().unwrap_or_else(|| PathBuf::from(".")),
+            rate_limits: RateLimits::default(),
+            auto_reconnect: false,
+            min_node_version: None,
+            snapshot_spec: false,
+            capture_environment: false,
+            ipc_mode: IpcMode::default(),
+            replay_speed: 1.0,
+            record_session: false,
+            tls: None,
+            blueprint_dirs: vec![PathBuf::from(".")],
+            auto_expire_consequence_memory: false,
+            suppressed_events: Vec::new(),
+            stderr_warning_threshold: 0,
+            max_command_bytes: 1024 * 1024,
+            persist_events: false,
+            run_error_types: ["error", "exception", "fatal", "gate-failure"]
+                .map(String::from)
+                .to_vec(),
+            trusted_origins: default_trusted_origins(),
+        }
+    }
+}
+
+fn default_trusted_origins() -> Vec<String> {
+    let mut origins: Vec<String> = BUNDLED_ORIGINS.iter().map(|o| o.to_string()).collect();
+    if cfg!(debug_assertions) {
+        origins.push(DEV_SERVER_ORIGIN.to_string());
+    }
+    origins
+}
+
+impl BridgeConfig {
+    pub fn runs_root(&self) -> PathBuf {
+        self.working_dir.join(RUNS_ROOT)
+    }
+
+    /// Read-only handle on a run directory; what every query gets.
+    pub fn read_run_dir(&self, run_id: &str) -> Result<RunDir, FactoryError> {
+        RunDir::for_read(&self.runs_root(), run_id)
+    }
+
+    /// Handle for the few commands that legitimately write into a run.
+    pub fn write_run_dir(&self, run_id: &str) -> Result<RunDir, FactoryError> {
+        RunDir::for_write(&self.runs_root(), run_id)
+    }
+
+    pub fn consequence_memory_path(&self) -> PathBuf {
+        self.working_dir.join(CONSEQUENCE_MEMORY_FILE)
+    }
+
+    pub fn consequence_metadata_path(&self) -> PathBuf {
+        self.working_dir.join(CONSEQUENCE_METADATA_FILE)
+    }
+
+    pub fn audit_log_path(&self) -> PathBuf {
+        self.working_dir.join(AUDIT_LOG_FILE)
+    }
+
+    pub fn event_store_path(&self) -> PathBuf {
+        self.working_dir.join(EVENT_STORE_FILE)
+    }
+
+    pub fn crash_log_path(&self) -> PathBuf {
+        self.working_dir.join(CRASH_LOG_FILE)
+    }
+
+    pub fn pid_file_path(&self) -> PathBuf {
+        self.working_dir.join(PID_FILE)
+    }
+
+    /// Socket a factory process listens on for a reconnecting bridge.
+    pub fn socket_path(&self, pid: u32) -> PathBuf {
+        self.working_dir.join(format!("{}-{}.sock", PID_FILE.trim_end_matches(".pid"), pid))
+    }
+}
+
+/// The nearest ancestor of the current directory that looks like the
+/// factory project root, so the app works when launched from `src-tauri/`
+/// or anywhere else inside the checkout.
+pub fn auto_detect_working_dir() -> Option<PathBuf> {
+    detect_working_dir_from(&std::env::current_dir().ok()?)
+}
+
+fn detect_working_dir_from(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| WORKING_DIR_MARKERS.iter().any(|m| dir.join(m).exists()))
+        .map(Path::to_path_buf)
+}
+
+/// PEM files for `BridgeConfig::tls`: the bridge's own certificate and key,
+/// and the CA the factory's certificate must chain to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    pub ca_cert_path: PathBuf,
+}
+
+/// Where `get_run_history` reads from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunHistorySource {
+    /// Read run manifests directly from `ops/factory/runs`.
+    #[default]
+    FileSystem,
+    /// Ask the factory process and wait for its `factory://run-history`
+    /// response. Falls back to the filesystem when no process is running.
+    Bridge,
+}
+
+/// The channel `FactoryBridge` writes commands to. Events are always read
+/// from the process's stdout.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IpcMode {
+    /// JSON lines on the process's stdin.
+    #[default]
+    Stdio,
+    /// A named pipe, `\\.\pipe\{name}`, that the factory connects to as a
+    /// client. Windows only; spawning fails elsewhere.
+    NamedPipe { name: String },
+    /// No process at all: events are replayed from a recorded session (see
+    /// `record_session`) and commands are discarded. For working on the UI
+    /// without Node.js. A relative path is resolved against `working_dir`.
+    SimulationMode { recording_path: PathBuf },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn working_dir_is_found_from_a_nested_directory() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("ops/factory")).unwrap();
+        let nested = root.path().join("src-tauri/src");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(detect_working_dir_from(&nested).as_deref(), Some(root.path()));
+    }
+
+    #[test]
+    fn working_dir_is_none_without_a_marker() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(detect_working_dir_from(root.path()), None);
+    }
+}
@@ -0,0 +1,616 @@
+//! Consequence memory — the app's view of `ops/factory/consequence-memory.ndjson`.
+//!
+//! The factory owns the record format (see `consequence-memory.ts`); this
+//! module only loads, validates and rewrites records as JSON values, so
+//! fields added on the TypeScript side survive a round trip untouched.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FactoryError;
+use crate::types::{ConsequenceMemoryAgeEntry, ConsequenceMemoryChange, DomainSize, ImportReport};
+
+pub const CONSEQUENCE_MEMORY_FILE: &str = "ops/factory/consequence-memory.ndjson";
+
+/// Per-domain settings the app keeps beside the memory file, so the
+/// factory's record format is left alone. A JSON object keyed by domain.
+pub const CONSEQUENCE_METADATA_FILE: &str = "ops/factory/consequence-memory-meta.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainMetadata {
+    /// Days after its newest record that a domain expires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_days: Option<u32>,
+}
+
+/// Fields every record must carry, with the JSON type they must have.
+const REQUIRED_FIELDS: &[(&str, FieldKind)] = &[
+    ("id", FieldKind::String),
+    ("createdAt", FieldKind::String),
+    ("sourceRunId", FieldKind::String),
+    ("domain", FieldKind::String),
+    ("stage", FieldKind::String),
+    ("specHash", FieldKind::String),
+    ("pattern", FieldKind::Object),
+    ("failure", FieldKind::Object),
+    ("resolution", FieldKind::Object),
+    ("confidence", FieldKind::Number),
+    ("occurrences", FieldKind::Number),
+    ("hash", FieldKind::String),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum FieldKind {
+    String,
+    Number,
+    Object,
+}
+
+/// How an import treats a record whose ID already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The incoming record replaces the existing one.
+    Overwrite,
+    /// Fields missing from the existing record are filled in from the
+    /// incoming one; fields present in both keep the existing value.
+    Merge,
+    /// The existing record is left as is.
+    SkipExisting,
+}
+
+impl FromStr for MergeStrategy {
+    type Err = FactoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "overwrite" => Ok(Self::Overwrite),
+            "merge" => Ok(Self::Merge),
+            "skip_existing" => Ok(Self::SkipExisting),
+            other => Err(FactoryError::InvalidArgument(format!(
+                "unknown merge strategy {:?} (expected overwrite, merge or skip_existing)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Check a record against the shape the factory writes.
+pub fn validate_record(record: &serde_json::Value) -> Result<(), String> {
+    let fields = record.as_object().ok_or("record is not a JSON object")?;
+    for (name, kind) in REQUIRED_FIELDS {
+        let ok = match (fields.get(*name), kind) {
+            (Some(v), FieldKind::String) => v.is_string(),
+            (Some(v), FieldKind::Number) => v.is_number(),
+            (Some(v), FieldKind::Object) => v.is_object(),
+            (None, _) => false,
+        };
+        if !ok {
+            return Err(format!("field '{}' is missing or has the wrong type", name));
+        }
+    }
+    Ok(())
+}
+
+/// Load every record from an NDJSON file. A missing file is an empty memory.
+pub fn load(path: &Path) -> Result<Vec<serde_json::Value>, FactoryError> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let data = fs::read_to_string(path)
+        .map_err(|e| FactoryError::IoError(format!("reading {}: {}", path.display(), e)))?;
+    parse_records(&data, path)
+}
+
+/// Parse an import source: either a JSON array of records or the NDJSON
+/// format `factory consequences export` writes. Every record is validated.
+pub fn parse_records(data: &str, source: &Path) -> Result<Vec<serde_json::Value>, FactoryError> {
+    let trimmed = data.trim();
+    let records: Vec<serde_json::Value> = if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed)
+            .map_err(|e| FactoryError::ParseError(format!("{}: {}", source.display(), e)))?
+    } else {
+        trimmed
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .enumerate()
+            .map(|(i, l)| {
+                serde_json::from_str(l).map_err(|e| {
+                    FactoryError::ParseError(format!("{} line {}: {}", source.display(), i + 1, e))
+                })
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    for (i, record) in records.iter().enumerate() {
+        validate_record(record).map_err(|e| {
+            FactoryError::ParseError(format!("{} record {}: {}", source.display(), i + 1, e))
+        })?;
+    }
+    Ok(records)
+}
+
+/// IDs of the records in `domain`, in file order.
+pub fn keys(records: &[serde_json::Value], domain: &str) -> Vec<String> {
+    records
+        .iter()
+        .filter(|r| r["domain"].as_str() == Some(domain))
+        .filter_map(|r| r["id"].as_str().map(str::to_string))
+        .collect()
+}
+
+/// The records `run_id` wrote, as changes to their domain and key (the
+/// record ID), oldest `createdAt` first. The file keeps no separate history,
+/// so a change's `old_value` is the previous record in file order with the
+/// same domain and ID, if there is one.
+pub fn run_changes(records: &[serde_json::Value], run_id: &str) -> Vec<ConsequenceMemoryChange> {
+    let mut latest: HashMap<(&str, &str), &serde_json::Value> = HashMap::new();
+    let mut changes = Vec::new();
+    for r in records {
+        let (Some(domain), Some(key)) = (r["domain"].as_str(), r["id"].as_str()) else {
+            continue;
+        };
+        let previous = latest.insert((domain, key), r);
+        if r["sourceRunId"].as_str() == Some(run_id) {
+            changes.push(ConsequenceMemoryChange {
+                domain: domain.to_string(),
+                key: key.to_string(),
+                old_value: previous.cloned(),
+                new_value: r.clone(),
+                written_at: r["createdAt"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.written_at.cmp(&b.written_at));
+    changes
+}
+
+/// The record in `domain` whose ID is `key`.
+pub fn find<'a>(
+    records: &'a [serde_json::Value],
+    domain: &str,
+    key: &str,
+) -> Option<&'a serde_json::Value> {
+    records
+        .iter()
+        .find(|r| r["domain"].as_str() == Some(domain) && r["id"].as_str() == Some(key))
+}
+
+/// Every record grouped by domain, each domain an object keyed by record ID.
+/// A later record with the same domain and ID replaces an earlier one.
+pub fn by_domain(records: &[serde_json::Value]) -> HashMap<String, serde_json::Value> {
+    let mut domains: HashMap<String, serde_json::Map<String, serde_json::Value>> = HashMap::new();
+    for r in records {
+        let (Some(domain), Some(id)) = (r["domain"].as_str(), r["id"].as_str()) else {
+            continue;
+        };
+        domains.entry(domain.to_string()).or_default().insert(id.to_string(), r.clone());
+    }
+    domains
+        .into_iter()
+        .map(|(domain, keyed)| (domain, serde_json::Value::Object(keyed)))
+        .collect()
+}
+
+/// Remove the records in `domain` from `records`, returning how many were
+/// removed and how many bytes they took up in the memory file.
+pub fn purge_domain(records: &mut Vec<serde_json::Value>, domain: &str) -> (u64, u64) {
+    let mut removed = 0;
+    let mut bytes = 0;
+    records.retain(|r| {
+        if r["domain"].as_str() != Some(domain) {
+            return true;
+        }
+        removed += 1;
+        bytes += record_size(r);
+        false
+    });
+    (removed, bytes)
+}
+
+/// Bytes `record` takes up as one line of the memory file, newline
+/// included.
+pub fn record_size(record: &serde_json::Value) -> u64 {
+    serde_json::to_string(record).map_or(0, |line| line.len() as u64 + 1)
+}
+
+/// Size and key count of every domain, largest first and then by name.
+pub fn domain_sizes(records: &[serde_json::Value]) -> Vec<DomainSize> {
+    let mut sizes: BTreeMap<&str, DomainSize> = BTreeMap::new();
+    for r in records {
+        let Some(domain) = r["domain"].as_str() else {
+            continue;
+        };
+        let size = sizes.entry(domain).or_insert_with(|| DomainSize {
+            domain: domain.to_string(),
+            size_bytes: 0,
+            key_count: 0,
+        });
+        size.size_bytes += record_size(r);
+        size.key_count += 1;
+    }
+    let mut sizes: Vec<DomainSize> = sizes.into_values().collect();
+    sizes.sort_by_key(|s| std::cmp::Reverse(s.size_bytes));
+    sizes
+}
+
+/// Domain metadata from `path`. A missing file means no domain has any.
+pub fn load_metadata(path: &Path) -> Result<BTreeMap<String, DomainMetadata>, FactoryError> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let data = fs::read_to_string(path)
+        .map_err(|e| FactoryError::IoError(format!("reading {}: {}", path.display(), e)))?;
+    serde_json::from_str(&data)
+        .map_err(|e| FactoryError::ParseError(format!("{}: {}", path.display(), e)))
+}
+
+pub fn save_metadata(
+    path: &Path,
+    metadata: &BTreeMap<String, DomainMetadata>,
+) -> Result<(), FactoryError> {
+    let io_err =
+        |e: std::io::Error| FactoryError::IoError(format!("writing {}: {}", path.display(), e));
+    let json = serde_json::to_string_pretty(metadata)
+        .map_err(|e| FactoryError::ParseError(format!("serializing domain metadata: {}", e)))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_err)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json).map_err(io_err)?;
+    fs::rename(&tmp, path).map_err(io_err)
+}
+
+/// Domains in `records` whose TTL has run out at `now`, counted from the
+/// newest record's `createdAt`. A domain with no parseable timestamps
+/// never expires.
+pub fn expired_domains(
+    records: &[serde_json::Value],
+    metadata: &BTreeMap<String, DomainMetadata>,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    newest_by_domain(records)
+        .into_iter()
+        .filter(|(domain, latest)| {
+            let ttl = metadata.get(*domain).and_then(|m| m.ttl_days);
+            match (ttl, latest) {
+                (Some(days), Some(latest)) => now - *latest > chrono::Duration::days(days.into()),
+                _ => false,
+            }
+        })
+        .map(|(domain, _)| domain.to_string())
+        .collect()
+}
+
+/// How long ago each domain in `records` last changed, oldest first and
+/// then by name. The memory is a single file, so a domain's own change
+/// time is its newest record's `createdAt`; a domain with no parseable
+/// timestamps falls back to `file_modified`, the file's mtime.
+pub fn domain_ages(
+    records: &[serde_json::Value],
+    file_modified: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<ConsequenceMemoryAgeEntry> {
+    let mut ages: Vec<ConsequenceMemoryAgeEntry> = newest_by_domain(records)
+        .into_iter()
+        .map(|(domain, latest)| {
+            let modified = latest.unwrap_or(file_modified);
+            ConsequenceMemoryAgeEntry {
+                domain: domain.to_string(),
+                last_modified: modified.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                age_days: (now - modified).num_seconds() as f32 / 86_400.0,
+            }
+        })
+        .collect();
+    ages.sort_by(|a, b| b.age_days.total_cmp(&a.age_days));
+    ages
+}
+
+/// The newest `createdAt` of every domain in `records`, `None` where no
+/// record's timestamp parses.
+fn newest_by_domain(records: &[serde_json::Value]) -> BTreeMap<&str, Option<DateTime<Utc>>> {
+    let mut newest: BTreeMap<&str, Option<DateTime<Utc>>> = BTreeMap::new();
+    for r in records {
+        let Some(domain) = r["domain"].as_str() else {
+            continue;
+        };
+        let created = r["createdAt"]
+            .as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc));
+        let latest = newest.entry(domain).or_default();
+        *latest = (*latest).max(created);
+    }
+    newest
+}
+
+/// Rewrite the memory file via a temporary sibling and a rename, so a
+/// reader never sees a half-written file.
+pub fn save(path: &Path, records: &[serde_json::Value]) -> Result<(), FactoryError> {
+    let io_err =
+        |e: std::io::Error| FactoryError::IoError(format!("writing {}: {}", path.display(), e));
+
+    let mut ndjson = String::new();
+    for record in records {
+        let line = serde_json::to_string(record)
+            .map_err(|e| FactoryError::ParseError(format!("serializing record: {}", e)))?;
+        ndjson.push_str(&line);
+        ndjson.push('\n');
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_err)?;
+    }
+    let tmp = path.with_extension("ndjson.tmp");
+    fs::write(&tmp, ndjson).map_err(io_err)?;
+    fs::rename(&tmp, path).map_err(io_err)
+}
+
+/// Apply `incoming` to `existing` in place and report what changed. A
+/// record conflicts with an existing one only if both its domain and its
+/// ID match.
+pub fn merge_records(
+    existing: &mut Vec<serde_json::Value>,
+    incoming: Vec<serde_json::Value>,
+    strategy: MergeStrategy,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    let mut domains = BTreeSet::new();
+
+    for record in incoming {
+        let id = record["id"].as_str().unwrap_or_default().to_string();
+        let domain = record["domain"].as_str().unwrap_or_default().to_string();
+        let current = existing.iter().position(|r| {
+            r["id"].as_str() == Some(id.as_str()) && r["domain"].as_str() == Some(domain.as_str())
+        });
+
+        let imported = match (current, strategy) {
+            (None, _) => {
+                existing.push(record);
+                true
+            }
+            (Some(i), MergeStrategy::Overwrite) => {
+                existing[i] = record;
+                true
+            }
+            (Some(i), MergeStrategy::Merge) => {
+                let (Some(current), serde_json::Value::Object(fields)) =
+                    (existing[i].as_object_mut(), record)
+                else {
+                    continue;
+                };
+                let mut added = false;
+                for (key, value) in fields {
+                    if !current.contains_key(&key) {
+                        current.insert(key, value);
+                        added = true;
+                    }
+                }
+                added
+            }
+            (Some(_), MergeStrategy::SkipExisting) => false,
+        };
+
+        if imported {
+            report.imported_keys += 1;
+            domains.insert(domain);
+        } else {
+            report.skipped_keys += 1;
+        }
+    }
+
+    report.domains_touched = domains.into_iter().collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, domain: &str, confidence: f64) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "createdAt": "2026-01-01T00:00:00.000Z",
+            "sourceRunId": "run-1",
+            "domain": domain,
+            "stage": "building",
+            "specHash": "abc",
+            "pattern": {},
+            "failure": { "description": "build failed" },
+            "resolution": { "description": "pin dependency" },
+            "confidence": confidence,
+            "occurrences": 1,
+            "hash": "h",
+        })
+    }
+
+    #[test]
+    fn strategies_differ_only_on_conflicting_ids() {
+        let base = vec![record("a", "web", 0.5)];
+        let incoming = || vec![record("a", "web", 0.9), record("b", "android", 0.7)];
+
+        let mut overwritten = base.clone();
+        let report = merge_records(&mut overwritten, incoming(), MergeStrategy::Overwrite);
+        assert_eq!((report.imported_keys, report.skipped_keys), (2, 0));
+        assert_eq!(overwritten[0]["confidence"], 0.9);
+        assert_eq!(report.domains_touched, ["android", "web"]);
+
+        let mut skipped = base.clone();
+        let report = merge_records(&mut skipped, incoming(), MergeStrategy::SkipExisting);
+        assert_eq!((report.imported_keys, report.skipped_keys), (1, 1));
+        assert_eq!(skipped[0]["confidence"], 0.5);
+        assert_eq!(report.domains_touched, ["android"]);
+    }
+
+    #[test]
+    fn merge_keeps_existing_values_and_fills_gaps() {
+        let mut existing = vec![record("a", "web", 0.5)];
+        let mut newer = record("a", "web", 0.9);
+        newer["appliedFix"] = serde_json::json!("v2");
+
+        let report = merge_records(&mut existing, vec![newer], MergeStrategy::Merge);
+        assert_eq!(report.imported_keys, 1);
+        assert_eq!(existing[0]["confidence"], 0.5);
+        assert_eq!(existing[0]["appliedFix"], "v2");
+    }
+
+    #[test]
+    fn the_same_id_in_another_domain_is_not_a_conflict() {
+        let mut existing = vec![record("a", "web", 0.5)];
+
+        let incoming = vec![record("a", "android", 0.9)];
+        let report = merge_records(&mut existing, incoming, MergeStrategy::SkipExisting);
+        assert_eq!(report.imported_keys, 1);
+        assert_eq!(report.domains_touched, ["android"]);
+        assert_eq!(find(&existing, "web", "a").unwrap()["confidence"], 0.5);
+        assert_eq!(find(&existing, "android", "a").unwrap()["confidence"], 0.9);
+    }
+
+    #[test]
+    fn parse_rejects_records_missing_required_fields() {
+        let mut bad = record("a", "web", 0.5);
+        bad.as_object_mut().unwrap().remove("domain");
+        let data = serde_json::to_string(&vec![bad]).unwrap();
+
+        let err = parse_records(&data, Path::new("dump.json")).unwrap_err();
+        assert!(err.to_string().contains("'domain'"));
+    }
+
+    #[test]
+    fn keys_and_lookups_are_scoped_to_a_domain() {
+        let records = vec![record("a", "web", 0.5), record("b", "android", 0.6)];
+
+        assert_eq!(keys(&records, "web"), ["a"]);
+        assert!(keys(&records, "ios").is_empty());
+        assert_eq!(find(&records, "android", "b").unwrap()["confidence"], 0.6);
+        assert!(find(&records, "web", "b").is_none());
+    }
+
+    #[test]
+    fn domains_are_sized_largest_first() {
+        let mut long = record("c", "android", 0.5);
+        long["appliedFix"] = serde_json::json!("x".repeat(2000));
+        let records = vec![record("a", "web", 0.5), record("b", "web", 0.6), long];
+
+        let sizes = domain_sizes(&records);
+        let order: Vec<(&str, u64)> =
+            sizes.iter().map(|d| (d.domain.as_str(), d.key_count)).collect();
+        assert_eq!(order, [("android", 1), ("web", 2)]);
+        let total: u64 = sizes.iter().map(|d| d.size_bytes).sum();
+        assert_eq!(total, records.iter().map(record_size).sum::<u64>());
+    }
+
+    #[test]
+    fn records_group_by_domain_and_id() {
+        let records = vec![
+            record("a", "web", 0.5),
+            record("b", "android", 0.6),
+            record("c", "web", 0.7),
+        ];
+
+        let domains = by_domain(&records);
+        assert_eq!(domains.len(), 2);
+        assert_eq!(domains["web"]["c"]["confidence"], 0.7);
+        assert_eq!(domains["android"].as_object().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn purging_a_domain_leaves_the_others() {
+        let mut records = vec![record("a", "web", 0.5), record("b", "android", 0.6)];
+        let web_bytes = serde_json::to_string(&records[0]).unwrap().len() as u64 + 1;
+
+        assert_eq!(purge_domain(&mut records, "web"), (1, web_bytes));
+        assert_eq!(keys(&records, "android"), ["b"]);
+        assert_eq!(purge_domain(&mut records, "web"), (0, 0));
+    }
+
+    #[test]
+    fn save_then_load_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory").join("consequence-memory.ndjson");
+        let records = vec![record("a", "web", 0.5), record("b", "web", 0.6)];
+
+        save(&path, &records).unwrap();
+        assert_eq!(load(&path).unwrap(), records);
+        assert!(!path.with_extension("ndjson.tmp").exists());
+    }
+
+    #[test]
+    fn domains_expire_a_ttl_after_their_newest_record() {
+        let mut old = record("a", "web", 0.5);
+        old["createdAt"] = serde_json::json!("2026-01-01T00:00:00.000Z");
+        let mut recent = record("b", "android", 0.5);
+        recent["createdAt"] = serde_json::json!("2026-01-01T00:00:00.000Z");
+        let mut newer = record("c", "android", 0.5);
+        newer["createdAt"] = serde_json::json!("2026-01-25T00:00:00.000Z");
+        let records = vec![old, recent, newer, record("d", "ios", 0.5)];
+        let ttl = |days| DomainMetadata { ttl_days: Some(days) };
+        let metadata = BTreeMap::from([
+            ("web".to_string(), ttl(30)),
+            ("android".to_string(), ttl(30)),
+            ("unused".to_string(), ttl(1)),
+        ]);
+
+        let now: DateTime<Utc> = "2026-02-05T00:00:00Z".parse().unwrap();
+        assert_eq!(expired_domains(&records, &metadata, now), ["web"]);
+        assert!(expired_domains(&records, &BTreeMap::new(), now).is_empty());
+    }
+
+    #[test]
+    fn metadata_round_trips_and_defaults_to_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("consequence-memory-meta.json");
+        assert!(load_metadata(&path).unwrap().is_empty());
+
+        let metadata = BTreeMap::from([("web".to_string(), DomainMetadata { ttl_days: Some(7) })]);
+        save_metadata(&path, &metadata).unwrap();
+        assert_eq!(load_metadata(&path).unwrap(), metadata);
+        assert!(fs::read_to_string(&path).unwrap().contains("\"ttl_days\": 7"));
+    }
+
+    #[test]
+    fn domains_age_from_their_newest_record_oldest_first() {
+        let mut old = record("a", "web", 0.5);
+        old["createdAt"] = serde_json::json!("2026-01-01T00:00:00.000Z");
+        let mut newer = record("b", "android", 0.5);
+        newer["createdAt"] = serde_json::json!("2026-01-31T12:00:00.000Z");
+        let mut undated = record("c", "ios", 0.5);
+        undated["createdAt"] = serde_json::json!("unknown");
+        let records = vec![newer, old, undated];
+
+        let file_modified: DateTime<Utc> = "2026-02-01T00:00:00Z".parse().unwrap();
+        let now: DateTime<Utc> = "2026-02-01T00:00:00Z".parse().unwrap();
+        let ages = domain_ages(&records, file_modified, now);
+        let order: Vec<(&str, f32)> =
+            ages.iter().map(|a| (a.domain.as_str(), a.age_days)).collect();
+        assert_eq!(order, [("web", 31.0), ("android", 0.5), ("ios", 0.0)]);
+        assert_eq!(ages[0].last_modified, "2026-01-01T00:00:00.000Z");
+    }
+
+    #[test]
+    fn run_changes_pair_each_record_with_the_version_it_replaced() {
+        let mut first = record("a", "web", 0.5);
+        first["createdAt"] = serde_json::json!("2026-01-02T00:00:00.000Z");
+        let mut replaced = record("a", "web", 0.9);
+        replaced["sourceRunId"] = serde_json::json!("run-2");
+        replaced["createdAt"] = serde_json::json!("2026-01-03T00:00:00.000Z");
+        let mut added = record("b", "android", 0.5);
+        added["sourceRunId"] = serde_json::json!("run-2");
+        let records = vec![first.clone(), replaced.clone(), record("c", "web", 0.5), added];
+
+        let changes = run_changes(&records, "run-2");
+        let summary: Vec<(&str, &str, bool)> = changes
+            .iter()
+            .map(|c| (c.domain.as_str(), c.key.as_str(), c.old_value.is_some()))
+            .collect();
+        assert_eq!(summary, [("android", "b", false), ("web", "a", true)]);
+        assert_eq!(changes[1].old_value.as_ref(), Some(&first));
+        assert_eq!(changes[1].new_value, replaced);
+        assert_eq!(changes[1].written_at, "2026-01-03T00:00:00.000Z");
+        assert!(run_changes(&records, "run-9").is_empty());
+    }
+}
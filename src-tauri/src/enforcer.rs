@@ -1,31 +1,93 @@
-/// FactoryCommand — the exhaustive whitelist of allowed operations.
-///
-/// This enum IS the security boundary. Commands not in this enum
-/// do not exist. The Rust compiler enforces exhaustiveness.
-/// No SkipGate, ForceShip, OverrideGate, ModifyCode, ModifyRunState,
-/// SetGateResult, or DeleteEvidence variants exist. They cannot be called.
+//! FactoryCommand — the exhaustive whitelist of allowed operations.
+//!
+//! This enum IS the security boundary. Commands not in this enum
+//! do not exist. The Rust compiler enforces exhaustiveness.
+//! No SkipGate, ForceShip, OverrideGate, ModifyCode, ModifyRunState,
+//! SetGateResult, or DeleteEvidence variants exist. They cannot be called.
 
 use serde::{Deserialize, Serialize};
 
+use crate::auth::Role;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "params")]
 pub enum FactoryCommand {
     // Queries (read-only, always allowed)
     GetRunStatus,
     GetGateResults,
+    GetGateSummary { run_id: String },
+    GetStageGates { run_id: String, stage: String },
+    GetGateStages { run_id: String },
     GetEvidenceEntry { seq: u64 },
     GetEvidenceRange { from: u64, to: u64 },
+    GetEvidenceByHash { run_id: String, hash: String },
+    ListRunEvidenceTypes { run_id: String },
+    GetEvidenceTypes { run_id: Option<String> },
+    GetCheckpoint { run_id: String },
     GetArtifact { path: String },
+    GetArtifactContent { run_id: String, path: String, max_bytes: u64 },
+    GetArtifactDiff { run_id_a: String, run_id_b: String, artifact_name: String },
+    GetLatestArtifact { pattern: String },
     GetConsequenceMemory { domain: Option<String> },
-    GetRunHistory,
+    GetConsequenceMemoryKeys { domain: String },
+    GetConsequenceMemorySnapshot,
+    GetConsequenceMemorySize,
+    GetConsequenceMemoryAge,
+    GetConsequenceMemoryChanges { run_id: String },
+    GetLastError,
+    GetRunHistory { include_active: bool },
+    SearchRunHistory { query: String, fields: Option<Vec<String>>, max_results: Option<u32> },
+    GetLatestRun,
+    GetCurrentRunId,
+    GetRunCount,
+    ListRunStages { run_id: String },
+    GetWorkerCount { run_id: String },
+    GetWorkerTimeline { run_id: String, worker_id: String },
+    GetRunErrors { run_id: String },
+    GetRunLogs { run_id: String, level: Option<String>, limit: Option<u32> },
+    GetGateHistory { gate_id: String, last_n_runs: u32 },
+    GetRunAnomalies { run_id: String, z_score_threshold: Option<f64> },
+    GetStageDependencies { run_id: String },
+    GetRunEnvironment { run_id: String },
+    GetRunnerInfo { run_id: String },
+    GetRunnerComparison { run_id_a: String, run_id_b: String },
 
     // Run control (state-changing, audit-logged)
     StartRun { spec_path: String, blueprint_path: Option<String> },
     AbortRun { run_id: String },
+    CloneRun { source_run_id: String, overrides: Option<serde_json::Value> },
+    ReindexRuns,
+
+    // Run maintenance and export (handled here, never sent to the factory)
+    CompactEvidenceChain { run_id: String },
+    RepairRunDirectory { run_id: String },
+    ExportRun { run_id: String, output_path: String },
+    ExportEvidenceSarif { run_id: String, output_path: String },
+    ExportRunHtml { run_id: String, output_path: String },
+
+    // Bridge settings
+    UpdateSuppressedEvents { events: Vec<String> },
 
     // Spec management (pre-run only)
     ValidateSpec { spec_path: String },
+    ValidateBlueprint { blueprint_path: String },
+    GetBlueprintContent { blueprint_path: String },
+    GetBlueprintVariables { blueprint_path: String },
+    GetSpec { run_id: String },
+    GetBlueprintSpec { spec_path: String, blueprint_path: String },
+    GetSpecSchema { version: Option<String> },
     DeriveSpecFromPlan { plan_text: String },
+    MigrateSpec {
+        spec_path: String,
+        target_version: String,
+        output_path: Option<String>,
+    },
+
+    // Memory management (admin only, audit-logged)
+    PurgeConsequenceMemory { domain: String, confirm: bool },
+    ApplyConsequenceMemoryExpiration,
+    ImportConsequenceMemory { source_path: String, merge_strategy: String },
+    SetConsequenceMemoryTtl { domain: String, ttl_days: u32 },
 }
 
 impl FactoryCommand {
@@ -34,14 +96,141 @@ impl FactoryCommand {
             self,
             FactoryCommand::GetRunStatus
                 | FactoryCommand::GetGateResults
+                | FactoryCommand::GetGateSummary { .. }
+                | FactoryCommand::GetStageGates { .. }
+                | FactoryCommand::GetGateStages { .. }
                 | FactoryCommand::GetEvidenceEntry { .. }
                 | FactoryCommand::GetEvidenceRange { .. }
+                | FactoryCommand::GetEvidenceByHash { .. }
+                | FactoryCommand::ListRunEvidenceTypes { .. }
+                | FactoryCommand::GetEvidenceTypes { .. }
+                | FactoryCommand::GetCheckpoint { .. }
                 | FactoryCommand::GetArtifact { .. }
+                | FactoryCommand::GetArtifactContent { .. }
+                | FactoryCommand::GetArtifactDiff { .. }
+                | FactoryCommand::GetLatestArtifact { .. }
                 | FactoryCommand::GetConsequenceMemory { .. }
-                | FactoryCommand::GetRunHistory
+                | FactoryCommand::GetConsequenceMemoryKeys { .. }
+                | FactoryCommand::GetConsequenceMemorySnapshot
+                | FactoryCommand::GetConsequenceMemorySize
+                | FactoryCommand::GetConsequenceMemoryAge
+                | FactoryCommand::GetConsequenceMemoryChanges { .. }
+                | FactoryCommand::GetLastError
+                | FactoryCommand::GetRunHistory { .. }
+                | FactoryCommand::SearchRunHistory { .. }
+                | FactoryCommand::GetLatestRun
+                | FactoryCommand::GetCurrentRunId
+                | FactoryCommand::GetRunCount
+                | FactoryCommand::ListRunStages { .. }
+                | FactoryCommand::GetWorkerCount { .. }
+                | FactoryCommand::GetWorkerTimeline { .. }
+                | FactoryCommand::GetRunErrors { .. }
+                | FactoryCommand::GetRunLogs { .. }
+                | FactoryCommand::GetGateHistory { .. }
+                | FactoryCommand::GetRunAnomalies { .. }
+                | FactoryCommand::GetStageDependencies { .. }
+                | FactoryCommand::GetRunEnvironment { .. }
+                | FactoryCommand::GetRunnerInfo { .. }
+                | FactoryCommand::GetRunnerComparison { .. }
+                | FactoryCommand::ValidateBlueprint { .. }
+                | FactoryCommand::GetBlueprintContent { .. }
+                | FactoryCommand::GetBlueprintVariables { .. }
+                | FactoryCommand::GetSpec { .. }
+                | FactoryCommand::GetBlueprintSpec { .. }
+                | FactoryCommand::GetSpecSchema { .. }
+        )
+    }
+
+    /// Commands that start, stop, overwrite or destroy something, which are
+    /// only accepted from the app's own UI (see `origin::guard`) whatever
+    /// the caller's role.
+    pub fn is_sensitive(&self) -> bool {
+        matches!(
+            self,
+            FactoryCommand::StartRun { .. }
+                | FactoryCommand::AbortRun { .. }
+                | FactoryCommand::CloneRun { .. }
+                | FactoryCommand::PurgeConsequenceMemory { .. }
+                | FactoryCommand::ApplyConsequenceMemoryExpiration
+                | FactoryCommand::ImportConsequenceMemory { .. }
+                | FactoryCommand::CompactEvidenceChain { .. }
+                | FactoryCommand::RepairRunDirectory { .. }
+                | FactoryCommand::ExportRun { .. }
+                | FactoryCommand::ExportEvidenceSarif { .. }
+                | FactoryCommand::ExportRunHtml { .. }
+                | FactoryCommand::SetConsequenceMemoryTtl { .. }
+                | FactoryCommand::MigrateSpec { .. }
         )
     }
 
+    /// The least privileged role allowed to issue this command. Exhaustive
+    /// on purpose: a new variant has to pick its role.
+    pub fn required_role(&self) -> Role {
+        match self {
+            FactoryCommand::GetRunStatus
+            | FactoryCommand::GetGateResults
+            | FactoryCommand::GetGateSummary { .. }
+            | FactoryCommand::GetStageGates { .. }
+            | FactoryCommand::GetGateStages { .. }
+            | FactoryCommand::GetEvidenceEntry { .. }
+            | FactoryCommand::GetEvidenceRange { .. }
+            | FactoryCommand::GetEvidenceByHash { .. }
+            | FactoryCommand::ListRunEvidenceTypes { .. }
+            | FactoryCommand::GetEvidenceTypes { .. }
+            | FactoryCommand::GetCheckpoint { .. }
+            | FactoryCommand::GetArtifact { .. }
+            | FactoryCommand::GetArtifactContent { .. }
+            | FactoryCommand::GetArtifactDiff { .. }
+            | FactoryCommand::GetLatestArtifact { .. }
+            | FactoryCommand::GetConsequenceMemory { .. }
+            | FactoryCommand::GetConsequenceMemoryKeys { .. }
+            | FactoryCommand::GetConsequenceMemorySnapshot
+            | FactoryCommand::GetConsequenceMemorySize
+            | FactoryCommand::GetConsequenceMemoryAge
+            | FactoryCommand::GetConsequenceMemoryChanges { .. }
+            | FactoryCommand::GetLastError
+            | FactoryCommand::GetRunHistory { .. }
+            | FactoryCommand::SearchRunHistory { .. }
+            | FactoryCommand::GetLatestRun
+            | FactoryCommand::GetCurrentRunId
+            | FactoryCommand::GetRunCount
+            | FactoryCommand::ListRunStages { .. }
+            | FactoryCommand::GetWorkerCount { .. }
+            | FactoryCommand::GetWorkerTimeline { .. }
+            | FactoryCommand::GetRunErrors { .. }
+            | FactoryCommand::GetRunLogs { .. }
+            | FactoryCommand::GetGateHistory { .. }
+            | FactoryCommand::GetRunAnomalies { .. }
+            | FactoryCommand::GetStageDependencies { .. }
+            | FactoryCommand::GetRunEnvironment { .. }
+            | FactoryCommand::GetRunnerInfo { .. }
+            | FactoryCommand::GetRunnerComparison { .. }
+            | FactoryCommand::ValidateSpec { .. }
+            | FactoryCommand::ValidateBlueprint { .. }
+            | FactoryCommand::GetBlueprintContent { .. }
+            | FactoryCommand::GetBlueprintVariables { .. }
+            | FactoryCommand::GetSpec { .. }
+            | FactoryCommand::GetBlueprintSpec { .. }
+            | FactoryCommand::GetSpecSchema { .. } => Role::ReadOnly,
+            FactoryCommand::StartRun { .. }
+            | FactoryCommand::AbortRun { .. }
+            | FactoryCommand::CloneRun { .. }
+            | FactoryCommand::ReindexRuns
+            | FactoryCommand::DeriveSpecFromPlan { .. }
+            | FactoryCommand::CompactEvidenceChain { .. }
+            | FactoryCommand::RepairRunDirectory { .. }
+            | FactoryCommand::ExportRun { .. }
+            | FactoryCommand::ExportEvidenceSarif { .. }
+            | FactoryCommand::ExportRunHtml { .. }
+            | FactoryCommand::UpdateSuppressedEvents { .. }
+            | FactoryCommand::MigrateSpec { .. } => Role::Operator,
+            FactoryCommand::PurgeConsequenceMemory { .. }
+            | FactoryCommand::ApplyConsequenceMemoryExpiration
+            | FactoryCommand::ImportConsequenceMemory { .. }
+            | FactoryCommand::SetConsequenceMemoryTtl { .. } => Role::Admin,
+        }
+    }
+
     pub fn to_bridge_json(&self) -> serde_json::Value {
         match self {
             FactoryCommand::StartRun { spec_path, blueprint_path } => {
@@ -57,25 +246,347 @@ impl FactoryCommand {
                     "runId": run_id,
                 })
             }
+            FactoryCommand::CloneRun { source_run_id, overrides } => {
+                serde_json::json!({
+                    "type": "clone_run",
+                    "sourceRunId": source_run_id,
+                    "overrides": overrides,
+                })
+            }
+            FactoryCommand::ReindexRuns => {
+                serde_json::json!({
+                    "type": "reindex_runs",
+                })
+            }
             FactoryCommand::ValidateSpec { spec_path } => {
                 serde_json::json!({
                     "type": "validate_spec",
                     "specPath": spec_path,
                 })
             }
+            FactoryCommand::ValidateBlueprint { blueprint_path } => {
+                serde_json::json!({
+                    "type": "validate_blueprint",
+                    "blueprintPath": blueprint_path,
+                })
+            }
+            FactoryCommand::GetBlueprintContent { blueprint_path } => {
+                serde_json::json!({
+                    "type": "get_blueprint_content",
+                    "blueprintPath": blueprint_path,
+                })
+            }
+            FactoryCommand::GetBlueprintVariables { blueprint_path } => {
+                serde_json::json!({
+                    "type": "get_blueprint_variables",
+                    "blueprintPath": blueprint_path,
+                })
+            }
+            FactoryCommand::GetSpec { run_id } => {
+                serde_json::json!({
+                    "type": "get_spec",
+                    "runId": run_id,
+                })
+            }
+            FactoryCommand::GetBlueprintSpec {
+                spec_path,
+                blueprint_path,
+            } => {
+                serde_json::json!({
+                    "type": "get_blueprint_spec",
+                    "specPath": spec_path,
+                    "blueprintPath": blueprint_path,
+                })
+            }
+            FactoryCommand::GetSpecSchema { version } => {
+                serde_json::json!({
+                    "type": "get_spec_schema",
+                    "version": version,
+                })
+            }
             FactoryCommand::DeriveSpecFromPlan { plan_text } => {
                 serde_json::json!({
                     "type": "derive_spec",
                     "planText": plan_text,
                 })
             }
+            FactoryCommand::PurgeConsequenceMemory { domain, confirm } => {
+                serde_json::json!({
+                    "type": "purge_consequence_memory",
+                    "domain": domain,
+                    "confirm": confirm,
+                })
+            }
+            FactoryCommand::ApplyConsequenceMemoryExpiration => {
+                serde_json::json!({
+                    "type": "apply_consequence_memory_expiration",
+                })
+            }
+            FactoryCommand::ImportConsequenceMemory {
+                source_path,
+                merge_strategy,
+            } => {
+                serde_json::json!({
+                    "type": "import_consequence_memory",
+                    "sourcePath": source_path,
+                    "mergeStrategy": merge_strategy,
+                })
+            }
+            FactoryCommand::CompactEvidenceChain { run_id } => {
+                serde_json::json!({
+                    "type": "compact_evidence_chain",
+                    "runId": run_id,
+                })
+            }
+            FactoryCommand::RepairRunDirectory { run_id } => {
+                serde_json::json!({
+                    "type": "repair_run_directory",
+                    "runId": run_id,
+                })
+            }
+            FactoryCommand::ExportRun { run_id, output_path } => {
+                serde_json::json!({
+                    "type": "export_run",
+                    "runId": run_id,
+                    "outputPath": output_path,
+                })
+            }
+            FactoryCommand::ExportEvidenceSarif { run_id, output_path } => {
+                serde_json::json!({
+                    "type": "export_evidence_sarif",
+                    "runId": run_id,
+                    "outputPath": output_path,
+                })
+            }
+            FactoryCommand::ExportRunHtml { run_id, output_path } => {
+                serde_json::json!({
+                    "type": "export_run_html",
+                    "runId": run_id,
+                    "outputPath": output_path,
+                })
+            }
+            FactoryCommand::UpdateSuppressedEvents { events } => {
+                serde_json::json!({
+                    "type": "update_suppressed_events",
+                    "events": events,
+                })
+            }
+            FactoryCommand::SetConsequenceMemoryTtl { domain, ttl_days } => {
+                serde_json::json!({
+                    "type": "set_consequence_memory_ttl",
+                    "domain": domain,
+                    "ttlDays": ttl_days,
+                })
+            }
+            FactoryCommand::MigrateSpec {
+                spec_path,
+                target_version,
+                output_path,
+            } => {
+                serde_json::json!({
+                    "type": "migrate_spec",
+                    "specPath": spec_path,
+                    "targetVersion": target_version,
+                    "outputPath": output_path,
+                })
+            }
             FactoryCommand::GetRunStatus => serde_json::json!({"type": "query", "queryType": "run_status"}),
             FactoryCommand::GetGateResults => serde_json::json!({"type": "query", "queryType": "gate_results"}),
+            FactoryCommand::GetGateSummary { run_id } => serde_json::json!({"type": "query", "queryType": "gate_summary", "params": {"runId": run_id}}),
+            FactoryCommand::GetStageGates { run_id, stage } => serde_json::json!({"type": "query", "queryType": "stage_gates", "params": {"runId": run_id, "stage": stage}}),
+            FactoryCommand::GetGateStages { run_id } => serde_json::json!({"type": "query", "queryType": "gate_stages", "params": {"runId": run_id}}),
             FactoryCommand::GetEvidenceEntry { seq } => serde_json::json!({"type": "query", "queryType": "evidence_entry", "params": {"seq": seq}}),
             FactoryCommand::GetEvidenceRange { from, to } => serde_json::json!({"type": "query", "queryType": "evidence_range", "params": {"from": from, "to": to}}),
+            FactoryCommand::GetEvidenceByHash { run_id, hash } => serde_json::json!({"type": "query", "queryType": "evidence_by_hash", "params": {"runId": run_id, "hash": hash}}),
+            FactoryCommand::ListRunEvidenceTypes { run_id } => serde_json::json!({"type": "query", "queryType": "run_evidence_types", "params": {"runId": run_id}}),
+            FactoryCommand::GetEvidenceTypes { run_id } => serde_json::json!({"type": "query", "queryType": "evidence_types", "params": {"runId": run_id}}),
+            FactoryCommand::GetCheckpoint { run_id } => serde_json::json!({"type": "query", "queryType": "checkpoint", "params": {"runId": run_id}}),
             FactoryCommand::GetArtifact { path } => serde_json::json!({"type": "query", "queryType": "artifact", "params": {"path": path}}),
+            FactoryCommand::GetArtifactContent { run_id, path, max_bytes } => serde_json::json!({"type": "query", "queryType": "artifact_content", "params": {"runId": run_id, "path": path, "maxBytes": max_bytes}}),
+            FactoryCommand::GetArtifactDiff { run_id_a, run_id_b, artifact_name } => serde_json::json!({"type": "query", "queryType": "artifact_diff", "params": {"runIdA": run_id_a, "runIdB": run_id_b, "artifactName": artifact_name}}),
+            FactoryCommand::GetLatestArtifact { pattern } => serde_json::json!({"type": "query", "queryType": "latest_artifact", "params": {"pattern": pattern}}),
             FactoryCommand::GetConsequenceMemory { domain } => serde_json::json!({"type": "query", "queryType": "consequence_memory", "params": {"domain": domain}}),
-            FactoryCommand::GetRunHistory => serde_json::json!({"type": "query", "queryType": "run_history"}),
+            FactoryCommand::GetConsequenceMemoryKeys { domain } => serde_json::json!({"type": "query", "queryType": "consequence_memory_keys", "params": {"domain": domain}}),
+            FactoryCommand::GetConsequenceMemorySnapshot => serde_json::json!({"type": "query", "queryType": "consequence_memory_snapshot"}),
+            FactoryCommand::GetConsequenceMemorySize => serde_json::json!({"type": "query", "queryType": "consequence_memory_size"}),
+            FactoryCommand::GetConsequenceMemoryAge => serde_json::json!({"type": "query", "queryType": "consequence_memory_age"}),
+            FactoryCommand::GetConsequenceMemoryChanges { run_id } => serde_json::json!({"type": "query", "queryType": "consequence_memory_changes", "params": {"runId": run_id}}),
+            FactoryCommand::GetLastError => serde_json::json!({"type": "query", "queryType": "last_error"}),
+            FactoryCommand::GetRunHistory { include_active } => serde_json::json!({"type": "query", "queryType": "run_history", "params": {"includeActive": include_active}}),
+            FactoryCommand::SearchRunHistory { query, fields, max_results } => serde_json::json!({"type": "query", "queryType": "search_run_history", "params": {"query": query, "fields": fields, "maxResults": max_results}}),
+            FactoryCommand::GetLatestRun => serde_json::json!({"type": "query", "queryType": "latest_run"}),
+            FactoryCommand::GetCurrentRunId => serde_json::json!({"type": "query", "queryType": "current_run_id"}),
+            FactoryCommand::GetRunCount => serde_json::json!({"type": "query", "queryType": "run_count"}),
+            FactoryCommand::ListRunStages { run_id } => serde_json::json!({"type": "query", "queryType": "run_stages", "params": {"runId": run_id}}),
+            FactoryCommand::GetWorkerCount { run_id } => serde_json::json!({"type": "query", "queryType": "worker_count", "params": {"runId": run_id}}),
+            FactoryCommand::GetWorkerTimeline { run_id, worker_id } => serde_json::json!({"type": "query", "queryType": "worker_timeline", "params": {"runId": run_id, "workerId": worker_id}}),
+            FactoryCommand::GetRunErrors { run_id } => serde_json::json!({"type": "query", "queryType": "run_errors", "params": {"runId": run_id}}),
+            FactoryCommand::GetRunLogs { run_id, level, limit } => serde_json::json!({"type": "query", "queryType": "run_logs", "params": {"runId": run_id, "level": level, "limit": limit}}),
+            FactoryCommand::GetGateHistory { gate_id, last_n_runs } => serde_json::json!({"type": "query", "queryType": "gate_history", "params": {"gateId": gate_id, "lastNRuns": last_n_runs}}),
+            FactoryCommand::GetRunAnomalies { run_id, z_score_threshold } => serde_json::json!({"type": "query", "queryType": "run_anomalies", "params": {"runId": run_id, "zScoreThreshold": z_score_threshold}}),
+            FactoryCommand::GetStageDependencies { run_id } => serde_json::json!({"type": "query", "queryType": "stage_dependencies", "params": {"runId": run_id}}),
+            FactoryCommand::GetRunEnvironment { run_id } => serde_json::json!({"type": "query", "queryType": "run_environment", "params": {"runId": run_id}}),
+            FactoryCommand::GetRunnerInfo { run_id } => serde_json::json!({"type": "query", "queryType": "runner_info", "params": {"runId": run_id}}),
+            FactoryCommand::GetRunnerComparison { run_id_a, run_id_b } => serde_json::json!({"type": "query", "queryType": "runner_comparison", "params": {"runIdA": run_id_a, "runIdB": run_id_b}}),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One of every variant. Adding a variant without listing it here is
+    /// not a compile error, so keep this in step with the enum.
+    fn every_command() -> Vec<FactoryCommand> {
+        vec![
+            FactoryCommand::GetRunStatus,
+            FactoryCommand::GetGateResults,
+            FactoryCommand::GetGateSummary { run_id: "r".into() },
+            FactoryCommand::GetStageGates { run_id: "r".into(), stage: "building".into() },
+            FactoryCommand::GetGateStages { run_id: "r".into() },
+            FactoryCommand::GetEvidenceEntry { seq: 0 },
+            FactoryCommand::GetEvidenceRange { from: 0, to: 1 },
+            FactoryCommand::GetEvidenceByHash {
+                run_id: "r".into(),
+                hash: "h".into(),
+            },
+            FactoryCommand::ListRunEvidenceTypes { run_id: "r".into() },
+            FactoryCommand::GetEvidenceTypes { run_id: None },
+            FactoryCommand::GetCheckpoint { run_id: "r".into() },
+            FactoryCommand::GetArtifact { path: "a".into() },
+            FactoryCommand::GetArtifactContent {
+                run_id: "r".into(),
+                path: "a".into(),
+                max_bytes: 1,
+            },
+            FactoryCommand::GetArtifactDiff {
+                run_id_a: "a".into(),
+                run_id_b: "b".into(),
+                artifact_name: "x".into(),
+            },
+            FactoryCommand::GetLatestArtifact { pattern: "*.apk".into() },
+            FactoryCommand::GetConsequenceMemory { domain: None },
+            FactoryCommand::GetConsequenceMemoryKeys { domain: "web".into() },
+            FactoryCommand::GetConsequenceMemorySnapshot,
+            FactoryCommand::GetConsequenceMemorySize,
+            FactoryCommand::GetConsequenceMemoryAge,
+            FactoryCommand::GetConsequenceMemoryChanges { run_id: "r".into() },
+            FactoryCommand::GetLastError,
+            FactoryCommand::GetRunHistory { include_active: true },
+            FactoryCommand::SearchRunHistory {
+                query: "q".into(),
+                fields: None,
+                max_results: None,
+            },
+            FactoryCommand::GetLatestRun,
+            FactoryCommand::GetCurrentRunId,
+            FactoryCommand::GetRunCount,
+            FactoryCommand::ListRunStages { run_id: "r".into() },
+            FactoryCommand::GetWorkerCount { run_id: "r".into() },
+            FactoryCommand::GetWorkerTimeline {
+                run_id: "r".into(),
+                worker_id: "w".into(),
+            },
+            FactoryCommand::GetRunErrors { run_id: "r".into() },
+            FactoryCommand::GetRunLogs {
+                run_id: "r".into(),
+                level: None,
+                limit: None,
+            },
+            FactoryCommand::GetGateHistory {
+                gate_id: "tests_pass".into(),
+                last_n_runs: 10,
+            },
+            FactoryCommand::GetRunAnomalies {
+                run_id: "r".into(),
+                z_score_threshold: None,
+            },
+            FactoryCommand::GetStageDependencies { run_id: "r".into() },
+            FactoryCommand::GetRunEnvironment { run_id: "r".into() },
+            FactoryCommand::GetRunnerInfo { run_id: "r".into() },
+            FactoryCommand::GetRunnerComparison {
+                run_id_a: "a".into(),
+                run_id_b: "b".into(),
+            },
+            FactoryCommand::StartRun {
+                spec_path: "s".into(),
+                blueprint_path: None,
+            },
+            FactoryCommand::AbortRun { run_id: "r".into() },
+            FactoryCommand::CloneRun {
+                source_run_id: "r".into(),
+                overrides: None,
+            },
+            FactoryCommand::ReindexRuns,
+            FactoryCommand::ValidateSpec { spec_path: "s".into() },
+            FactoryCommand::ValidateBlueprint { blueprint_path: "b".into() },
+            FactoryCommand::GetBlueprintContent { blueprint_path: "b".into() },
+            FactoryCommand::GetBlueprintVariables { blueprint_path: "b".into() },
+            FactoryCommand::GetSpec { run_id: "r".into() },
+            FactoryCommand::GetBlueprintSpec {
+                spec_path: "s".into(),
+                blueprint_path: "b".into(),
+            },
+            FactoryCommand::GetSpecSchema { version: None },
+            FactoryCommand::DeriveSpecFromPlan { plan_text: "p".into() },
+            FactoryCommand::PurgeConsequenceMemory {
+                domain: "web".into(),
+                confirm: false,
+            },
+            FactoryCommand::ApplyConsequenceMemoryExpiration,
+            FactoryCommand::ImportConsequenceMemory {
+                source_path: "dump.json".into(),
+                merge_strategy: "merge".into(),
+            },
+            FactoryCommand::CompactEvidenceChain { run_id: "r".into() },
+            FactoryCommand::RepairRunDirectory { run_id: "r".into() },
+            FactoryCommand::ExportRun {
+                run_id: "r".into(),
+                output_path: "out".into(),
+            },
+            FactoryCommand::ExportEvidenceSarif {
+                run_id: "r".into(),
+                output_path: "out".into(),
+            },
+            FactoryCommand::ExportRunHtml {
+                run_id: "r".into(),
+                output_path: "out".into(),
+            },
+            FactoryCommand::UpdateSuppressedEvents { events: vec!["run:progress".into()] },
+            FactoryCommand::SetConsequenceMemoryTtl {
+                domain: "web".into(),
+                ttl_days: 1,
+            },
+            FactoryCommand::MigrateSpec {
+                spec_path: "s".into(),
+                target_version: "2.0".into(),
+                output_path: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn read_only_commands_need_only_the_read_only_role() {
+        for cmd in every_command() {
+            if cmd.is_read_only() {
+                assert_eq!(cmd.required_role(), Role::ReadOnly, "{:?}", cmd);
+            }
+        }
+    }
+
+    #[test]
+    fn every_command_has_a_bridge_type() {
+        for cmd in every_command() {
+            assert!(cmd.to_bridge_json()["type"].is_string(), "{:?}", cmd);
         }
     }
+
+    #[test]
+    fn sensitive_commands_are_never_read_only() {
+        let sensitive: Vec<FactoryCommand> =
+            every_command().into_iter().filter(FactoryCommand::is_sensitive).collect();
+        assert_eq!(sensitive.len(), 13);
+        assert!(sensitive.iter().all(|cmd| !cmd.is_read_only()));
+    }
 }
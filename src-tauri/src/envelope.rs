@@ -0,0 +1,116 @@
+//! CommandEnvelope — a `FactoryCommand` plus the metadata needed to trace
+//! it end to end.
+//!
+//! The envelope's fields are merged into the command's bridge JSON. The
+//! factory may log or ignore `sentAt` and `caller`, but echoes
+//! `correlationId` in the events it sends in response. Envelopes issued by
+//! Tauri commands also carry a `traceId`, one per invocation, for external
+//! tracing; a response that carries only the trace ID is still routed back.
+
+use std::fmt;
+use std::time::SystemTime;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use uuid::Uuid;
+
+use crate::enforcer::FactoryCommand;
+
+#[derive(Debug, Clone)]
+pub struct CommandEnvelope {
+    pub correlation_id: Uuid,
+    pub sent_at: SystemTime,
+    /// The Tauri command that issued this one, e.g. `abort_run`.
+    pub caller: String,
+    /// Shared by every command one Tauri command invocation sends.
+    pub trace_id: Option<String>,
+    pub command: FactoryCommand,
+}
+
+impl CommandEnvelope {
+    pub fn new(caller: &str, command: FactoryCommand) -> Self {
+        Self {
+            correlation_id: Uuid::new_v4(),
+            sent_at: SystemTime::now(),
+            caller: caller.to_string(),
+            trace_id: None,
+            command,
+        }
+    }
+
+    /// An envelope with a fresh trace ID, for a command sent straight from
+    /// a Tauri command handler.
+    pub fn traced(caller: &str, command: FactoryCommand) -> Self {
+        Self {
+            trace_id: Some(new_trace_id()),
+            ..Self::new(caller, command)
+        }
+    }
+
+    pub fn to_bridge_json(&self) -> serde_json::Value {
+        let mut json = self.command.to_bridge_json();
+        let sent_at =
+            DateTime::<Utc>::from(self.sent_at).to_rfc3339_opts(SecondsFormat::Millis, true);
+        json["correlationId"] = serde_json::Value::String(self.correlation_id.to_string());
+        json["sentAt"] = serde_json::Value::String(sent_at);
+        json["caller"] = serde_json::Value::String(self.caller.clone());
+        if let Some(trace_id) = &self.trace_id {
+            json["traceId"] = serde_json::Value::String(trace_id.clone());
+        }
+        json
+    }
+}
+
+/// A new trace ID: a UUID v4, as Jaeger and Zipkin accept.
+pub fn new_trace_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+impl fmt::Display for CommandEnvelope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = self.command.to_bridge_json();
+        let kind = kind["queryType"].as_str().or(kind["type"].as_str()).unwrap_or("unknown");
+        write!(f, "{} from {} [{}]", kind, self.caller, self.correlation_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_is_merged_into_the_command_json() {
+        let envelope = CommandEnvelope::new(
+            "abort_run",
+            FactoryCommand::AbortRun {
+                run_id: "run-1".into(),
+            },
+        );
+
+        let json = envelope.to_bridge_json();
+        assert_eq!(json["type"], "abort_run");
+        assert_eq!(json["runId"], "run-1");
+        assert_eq!(json["caller"], "abort_run");
+        assert_eq!(json["correlationId"], envelope.correlation_id.to_string());
+        assert!(json["sentAt"].as_str().unwrap().ends_with('Z'));
+        assert!(json.get("traceId").is_none());
+    }
+
+    #[test]
+    fn traced_envelopes_carry_a_trace_id() {
+        let envelope = CommandEnvelope::traced("get_run_history", FactoryCommand::GetRunHistory { include_active: true });
+
+        let trace_id = envelope.trace_id.clone().unwrap();
+        assert!(Uuid::parse_str(&trace_id).is_ok());
+        assert_eq!(envelope.to_bridge_json()["traceId"], trace_id);
+    }
+
+    #[test]
+    fn display_names_the_command_and_caller() {
+        let envelope = CommandEnvelope::new("get_run_history", FactoryCommand::GetRunHistory { include_active: true });
+
+        assert_eq!(
+            envelope.to_string(),
+            format!("run_history from get_run_history [{}]", envelope.correlation_id)
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! FactoryError — the single error type returned by every Tauri command.
+//!
+//! Serialized as its display string, so the frontend receives the same
+//! plain-text rejection it always has from `invoke`. Messages carry the
+//! context they were raised in (run ID, file, operation) so they can be
+//! acted on without a debugger.
+
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+use crate::auth::Role;
+
+#[derive(Debug, Clone, Error)]
+pub enum FactoryError {
+    /// The factory process could not be started or exited abnormally.
+    #[error("factory process failed{}: {stderr}", exit_code_suffix(.exit_code))]
+    ProcessFailed {
+        exit_code: Option<i32>,
+        stderr: String,
+    },
+    /// No factory process is attached to the bridge.
+    #[error("factory process not running")]
+    BridgeNotRunning,
+    /// Writing to or locking the bridge failed.
+    #[error("bridge error: {0}")]
+    BridgeError(String),
+    /// Filesystem read or write failed.
+    #[error("io error: {0}")]
+    IoError(String),
+    /// A file or bridge message could not be parsed.
+    #[error("parse error: {0}")]
+    ParseError(String),
+    /// A caller-supplied argument was rejected before any work was done.
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    /// The factory did not answer within the allowed time.
+    #[error("timed out: {0}")]
+    Timeout(String),
+    /// The command was refused; the reason says why.
+    #[error("unauthorized: {0}")]
+    Unauthorized(Denial),
+    /// Too many commands of this kind in a short window.
+    #[error("rate limited: retry in {retry_after_ms} ms")]
+    RateLimited { retry_after_ms: u64 },
+}
+
+/// Why a command was `Unauthorized`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Denial {
+    /// The session's role is below what the command requires.
+    #[error("requires {required} role, session has {actual}")]
+    Role { required: Role, actual: Role },
+    /// A sensitive command came from a window or page the app doesn't
+    /// trust; see `origin`.
+    #[error("{0}")]
+    UntrustedOrigin(String),
+}
+
+fn exit_code_suffix(exit_code: &Option<i32>) -> String {
+    exit_code
+        .map(|code| format!(" (exit code {})", code))
+        .unwrap_or_default()
+}
+
+impl Serialize for FactoryError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
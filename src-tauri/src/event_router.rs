@@ -0,0 +1,279 @@
+//! RunEventRouter — turns one factory process's stdout lines into Tauri
+//! emissions.
+//!
+//! One router per run. It tags events with the run ID, resolves anyone
+//! waiting on an event or a correlation ID, and drops evidence entries
+//! whose `seq` it has already forwarded, so a retrying factory can't make
+//! the frontend render an entry twice.
+//!
+//! `EventSubscriptions` sits alongside: glob-pattern subscribers that are
+//! handed every matching event from any run.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+
+use crate::bridge::{BridgeMetrics, EventWaiters, PendingResponses};
+use crate::error::FactoryError;
+use crate::events::EVENT_RAW;
+use crate::progress::ProgressTracker;
+use crate::types::FactoryEvent;
+
+const EVIDENCE_ENTRY_EVENT: &str = "evidence-entry";
+
+/// How many recent sequence numbers are remembered for deduplication.
+/// Duplicates come from retries, so they arrive close together.
+const SEEN_SEQ_CAPACITY: usize = 4096;
+
+pub struct RunEventRouter {
+    run_id: String,
+    waiters: EventWaiters,
+    pending_responses: PendingResponses,
+    metrics: Arc<BridgeMetrics>,
+    progress: ProgressTracker,
+    seen_seqs: HashSet<u64>,
+    seen_order: VecDeque<u64>,
+}
+
+impl RunEventRouter {
+    pub fn new(
+        run_id: &str,
+        waiters: EventWaiters,
+        pending_responses: PendingResponses,
+        metrics: Arc<BridgeMetrics>,
+        progress: ProgressTracker,
+    ) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            waiters,
+            pending_responses,
+            metrics,
+            progress,
+            seen_seqs: HashSet::new(),
+            seen_order: VecDeque::new(),
+        }
+    }
+
+    /// Route one stdout line. Returns the `(event name, payload)` pairs to
+    /// emit, in order: the parsed event, unless it is a duplicate, then the
+    /// line itself on `factory://raw`. Empty only for blank lines.
+    pub fn route(&mut self, line: &str) -> Vec<(String, serde_json::Value)> {
+        if line.trim().is_empty() {
+            return vec![];
+        }
+
+        let mut emissions = Vec::with_capacity(2);
+        if let Ok(mut event) = FactoryEvent::from_line(line) {
+            event.tag_run(&self.run_id);
+            if self.is_duplicate(&event) {
+                self.metrics.duplicates_dropped.fetch_add(1, Ordering::Relaxed);
+            } else {
+                self.emit_event(event, &mut emissions);
+            }
+        }
+        emissions.push((EVENT_RAW.to_string(), serde_json::Value::from(line)));
+        emissions
+    }
+
+    fn emit_event(&self, event: FactoryEvent, emissions: &mut Vec<(String, serde_json::Value)>) {
+        self.metrics.record_event(&event.event);
+        let data = event.payload.into_value();
+        self.progress.record(&self.run_id, &event.event, &data);
+        let event_name = format!("factory://{}", event.event);
+        let pending = self.waiters.lock().ok().and_then(|mut w| w.remove(&event_name));
+        for tx in pending.into_iter().flatten() {
+            let _ = tx.send(data.clone());
+        }
+        route_response(&self.pending_responses, &data);
+        emissions.push((event_name, data));
+    }
+
+    /// Records the `seq` of an evidence entry and reports whether it was
+    /// already seen. Other events are never duplicates.
+    fn is_duplicate(&mut self, event: &FactoryEvent) -> bool {
+        if event.event != EVIDENCE_ENTRY_EVENT {
+            return false;
+        }
+        let Some(seq) = event.payload.as_value()["seq"].as_u64() else {
+            return false;
+        };
+        if !self.seen_seqs.insert(seq) {
+            return true;
+        }
+        self.seen_order.push_back(seq);
+        if self.seen_order.len() > SEEN_SEQ_CAPACITY {
+            if let Some(oldest) = self.seen_order.pop_front() {
+                self.seen_seqs.remove(&oldest);
+            }
+        }
+        false
+    }
+}
+
+/// Receives `(event name, payload)` for each matching event. Returning
+/// `false` (the receiver is gone) ends the subscription.
+pub type EventSink = Box<dyn Fn(&str, &serde_json::Value) -> bool + Send>;
+
+struct Subscription {
+    pattern: glob::Pattern,
+    sink: EventSink,
+}
+
+/// Subscribers by token, each with a glob such as `factory://*/gate-result`.
+/// An event is matched both by its name and by its run-qualified name,
+/// `factory://run/{run_id}/{event}`, so `factory://run/abc123/*` follows
+/// one run. A subscriber whose pattern matches gets the event once.
+#[derive(Clone, Default)]
+pub struct EventSubscriptions {
+    subscribers: Arc<Mutex<HashMap<String, Subscription>>>,
+}
+
+impl EventSubscriptions {
+    /// Register `sink` for events matching `pattern`; returns the token
+    /// to unsubscribe with.
+    pub fn subscribe(&self, pattern: &str, sink: EventSink) -> Result<String, FactoryError> {
+        let pattern = glob::Pattern::new(pattern).map_err(|e| {
+            FactoryError::InvalidArgument(format!("invalid event pattern {:?}: {}", pattern, e))
+        })?;
+        let token = uuid::Uuid::new_v4().to_string();
+        self.subscribers
+            .lock()
+            .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?
+            .insert(token.clone(), Subscription { pattern, sink });
+        Ok(token)
+    }
+
+    /// Whether `token` was subscribed.
+    pub fn unsubscribe(&self, token: &str) -> bool {
+        self.subscribers.lock().is_ok_and(|mut s| s.remove(token).is_some())
+    }
+
+    /// Hand one of `run_id`'s events to every subscriber whose pattern
+    /// matches, dropping those whose receiver has gone away.
+    pub fn dispatch(&self, run_id: &str, event_name: &str, payload: &serde_json::Value) {
+        let Ok(mut subscribers) = self.subscribers.lock() else {
+            return;
+        };
+        if subscribers.is_empty() {
+            return;
+        }
+        let suffix = event_name.strip_prefix("factory://").unwrap_or(event_name);
+        let qualified = format!("factory://run/{}/{}", run_id, suffix);
+        subscribers.retain(|_, s| {
+            let matches = s.pattern.matches(event_name) || s.pattern.matches(&qualified);
+            !matches || (s.sink)(event_name, payload)
+        });
+    }
+}
+
+/// Hand a response event to the caller waiting on its correlation ID, or
+/// on its trace ID when the factory echoed only that.
+pub(crate) fn route_response(pending: &PendingResponses, data: &serde_json::Value) {
+    let Some(id) = data["correlationId"].as_str().or(data["traceId"].as_str()) else {
+        return;
+    };
+    let waiter = pending.lock().ok().and_then(|mut p| p.remove(id));
+    if let Some(tx) = waiter {
+        let _ = tx.send(data.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router(metrics: &Arc<BridgeMetrics>) -> RunEventRouter {
+        RunEventRouter::new(
+            "run-1",
+            Arc::new(Mutex::new(HashMap::new())),
+            Arc::new(Mutex::new(HashMap::new())),
+            Arc::clone(metrics),
+            ProgressTracker::default(),
+        )
+    }
+
+    #[test]
+    fn duplicate_evidence_seq_is_emitted_once() {
+        let metrics = Arc::new(BridgeMetrics::default());
+        let mut router = router(&metrics);
+        let line = r#"{"event": "evidence-entry", "seq": 4, "type": "note"}"#;
+
+        let first = router.route(line);
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].0, "factory://evidence-entry");
+        assert_eq!(first[0].1["runId"], "run-1");
+
+        let again = router.route(line);
+        assert_eq!(again, vec![(EVENT_RAW.to_string(), serde_json::Value::from(line))]);
+        assert_eq!(metrics.duplicates_dropped(), 1);
+        assert_eq!(metrics.events_by_type()["evidence-entry"], 1);
+    }
+
+    #[test]
+    fn other_events_with_seq_are_not_deduplicated() {
+        let metrics = Arc::new(BridgeMetrics::default());
+        let mut router = router(&metrics);
+        let line = r#"{"event": "worker-progress", "seq": 4}"#;
+
+        assert_eq!(router.route(line).len(), 2);
+        assert_eq!(router.route(line).len(), 2);
+        assert_eq!(metrics.duplicates_dropped(), 0);
+    }
+
+    #[test]
+    fn unparsable_lines_are_forwarded_raw() {
+        let metrics = Arc::new(BridgeMetrics::default());
+        let emissions = router(&metrics).route("npm WARN deprecated");
+
+        assert_eq!(emissions.len(), 1);
+        assert_eq!(emissions[0].0, EVENT_RAW);
+        assert_eq!(emissions[0].1, "npm WARN deprecated");
+    }
+
+    fn collecting(seen: &Arc<Mutex<Vec<String>>>) -> EventSink {
+        let seen = Arc::clone(seen);
+        Box::new(move |name, _| {
+            seen.lock().unwrap().push(name.to_string());
+            true
+        })
+    }
+
+    #[test]
+    fn wildcard_subscribers_get_each_matching_event_once() {
+        let subs = EventSubscriptions::default();
+        let gates = Arc::new(Mutex::new(Vec::new()));
+        let one_run = Arc::new(Mutex::new(Vec::new()));
+        let everything = Arc::new(Mutex::new(Vec::new()));
+        subs.subscribe("factory://*/gate-result", collecting(&gates)).unwrap();
+        subs.subscribe("factory://run/abc123/*", collecting(&one_run)).unwrap();
+        let all = subs.subscribe("factory://*", collecting(&everything)).unwrap();
+
+        let payload = serde_json::json!({});
+        subs.dispatch("abc123", "factory://gate-result", &payload);
+        subs.dispatch("abc123", "factory://stage-changed", &payload);
+        subs.dispatch("other", "factory://gate-result", &payload);
+        assert!(subs.unsubscribe(&all));
+        assert!(!subs.unsubscribe(&all));
+        subs.dispatch("other", "factory://stage-changed", &payload);
+
+        assert_eq!(*gates.lock().unwrap(), ["factory://gate-result"; 2]);
+        assert_eq!(
+            *one_run.lock().unwrap(),
+            ["factory://gate-result", "factory://stage-changed"]
+        );
+        assert_eq!(everything.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn closed_subscribers_are_dropped_and_bad_patterns_refused() {
+        let subs = EventSubscriptions::default();
+        let token = subs.subscribe("factory://*", Box::new(|_, _| false)).unwrap();
+        subs.dispatch("run-1", "factory://gate-result", &serde_json::json!({}));
+
+        assert!(!subs.unsubscribe(&token));
+        assert!(matches!(
+            subs.subscribe("factory://[", Box::new(|_, _| true)),
+            Err(FactoryError::InvalidArgument(_))
+        ));
+    }
+}
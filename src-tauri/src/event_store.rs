@@ -0,0 +1,176 @@
+//! Event store — factory events kept in SQLite at `ops/factory/event-store.db`
+//! when `persist_events` is on, so they outlive the app and its in-memory
+//! progress tracking.
+//!
+//! Each reader thread opens its own connection and inserts the events it
+//! routes; `query_event_store` opens a read-only one.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::{params, Connection, OpenFlags};
+
+use crate::error::FactoryError;
+use crate::events::{EVENT_RAW, EVENT_WORKER_PROGRESS};
+use crate::types::StoredEvent;
+
+pub const EVENT_STORE_FILE: &str = "ops/factory/event-store.db";
+
+/// Results from `query` when the caller gives no limit, and the most it
+/// returns whatever the caller asks for.
+pub const DEFAULT_QUERY_LIMIT: u32 = 100;
+pub const MAX_QUERY_LIMIT: u32 = 10_000;
+
+/// How long a statement waits on another connection's lock (one per
+/// reader thread, plus queries) before failing with `SQLITE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Not stored: raw lines never parsed as events, and per-worker progress,
+/// which is frequent and summarized by `factory://run-progress-summary`.
+const UNSTORED_EVENTS: &[&str] = &[EVENT_RAW, EVENT_WORKER_PROGRESS];
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS factory_events (
+    id INTEGER PRIMARY KEY,
+    run_id TEXT,
+    event_type TEXT,
+    seq INTEGER,
+    payload TEXT,
+    received_at TEXT
+)";
+
+pub struct EventStore {
+    conn: Connection,
+}
+
+impl EventStore {
+    /// Open the store at `path` for writing, creating the file and table
+    /// if needed. The journal is switched to WAL so readers don't block
+    /// the writers.
+    pub fn open(path: &Path) -> Result<Self, FactoryError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| FactoryError::IoError(format!("creating {}: {}", dir.display(), e)))?;
+        }
+        let conn = Connection::open(path).map_err(|e| store_err(path, e))?;
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(|e| store_err(path, e))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+            .map_err(|e| store_err(path, e))?;
+        conn.execute(SCHEMA, []).map_err(|e| store_err(path, e))?;
+        Ok(Self { conn })
+    }
+
+    /// Open an existing store read-only; `None` if there isn't one yet.
+    pub fn open_read_only(path: &Path) -> Result<Option<Self>, FactoryError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| store_err(path, e))?;
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(|e| store_err(path, e))?;
+        Ok(Some(Self { conn }))
+    }
+
+    /// Store one routed event, unless it is of a kind not kept. `seq` is
+    /// taken from the payload when it has one.
+    pub fn insert(
+        &self,
+        run_id: &str,
+        event_type: &str,
+        payload: &serde_json::Value,
+    ) -> rusqlite::Result<()> {
+        if UNSTORED_EVENTS.contains(&event_type) {
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT INTO factory_events (run_id, event_type, seq, payload, received_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                run_id,
+                event_type,
+                payload["seq"].as_i64(),
+                payload.to_string(),
+                chrono::Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Stored events matching the filters that are given, newest first.
+    pub fn query(
+        &self,
+        run_id: Option<&str>,
+        event_type: Option<&str>,
+        limit: Option<u32>,
+    ) -> rusqlite::Result<Vec<StoredEvent>> {
+        let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT);
+        let mut stmt = self.conn.prepare(
+            "SELECT id, run_id, event_type, seq, payload, received_at FROM factory_events
+             WHERE (?1 IS NULL OR run_id = ?1) AND (?2 IS NULL OR event_type = ?2)
+             ORDER BY id DESC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![run_id, event_type, limit], |row| {
+            let payload: String = row.get(4)?;
+            Ok(StoredEvent {
+                id: row.get(0)?,
+                run_id: row.get(1)?,
+                event_type: row.get(2)?,
+                seq: row.get(3)?,
+                payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null),
+                received_at: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+pub fn store_err(path: &Path, e: rusqlite::Error) -> FactoryError {
+    FactoryError::IoError(format!("event store {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EVENT_GATE_RESULT, EVENT_STAGE_CHANGED};
+
+    #[test]
+    fn stores_and_filters_events_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EVENT_STORE_FILE);
+        assert!(EventStore::open_read_only(&path).unwrap().is_none());
+
+        let store = EventStore::open(&path).unwrap();
+        let stage = serde_json::json!({ "seq": 1, "stage": "building" });
+        store.insert("run-1", EVENT_STAGE_CHANGED, &stage).unwrap();
+        store.insert("run-1", EVENT_GATE_RESULT, &serde_json::json!({ "seq": 2 })).unwrap();
+        store.insert("run-2", EVENT_GATE_RESULT, &serde_json::json!({})).unwrap();
+        store.insert("run-1", EVENT_WORKER_PROGRESS, &serde_json::json!({})).unwrap();
+
+        let reader = EventStore::open_read_only(&path).unwrap().unwrap();
+        let all = reader.query(None, None, None).unwrap();
+        let order: Vec<(&str, Option<i64>)> =
+            all.iter().map(|e| (e.run_id.as_str(), e.seq)).collect();
+        assert_eq!(order, [("run-2", None), ("run-1", Some(2)), ("run-1", Some(1))]);
+        assert_eq!(all[2].payload, stage);
+
+        let gates = reader.query(Some("run-1"), Some(EVENT_GATE_RESULT), None).unwrap();
+        assert_eq!(gates.len(), 1);
+        assert_eq!(gates[0].seq, Some(2));
+        assert_eq!(reader.query(Some("run-1"), None, Some(1)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn writers_share_the_store_in_wal_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EVENT_STORE_FILE);
+        let first = EventStore::open(&path).unwrap();
+        let second = EventStore::open(&path).unwrap();
+
+        let mode: String =
+            first.conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+        first.insert("run-1", EVENT_GATE_RESULT, &serde_json::json!({})).unwrap();
+        second.insert("run-2", EVENT_GATE_RESULT, &serde_json::json!({})).unwrap();
+        assert_eq!(first.query(None, None, None).unwrap().len(), 2);
+    }
+}
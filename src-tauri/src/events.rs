@@ -1,11 +1,26 @@
-/// Factory event constants — match the frontend event subscriptions.
+//! Factory event constants — match the frontend event subscriptions.
 
 pub const EVENT_RUN_STARTED: &str = "factory://run-started";
 pub const EVENT_STAGE_CHANGED: &str = "factory://stage-changed";
 pub const EVENT_WORKER_PROGRESS: &str = "factory://worker-progress";
+pub const EVENT_RUN_PROGRESS_SUMMARY: &str = "factory://run-progress-summary";
 pub const EVENT_GATE_RESULT: &str = "factory://gate-result";
 pub const EVENT_EVIDENCE_ENTRY: &str = "factory://evidence-entry";
 pub const EVENT_ARTIFACT_PRODUCED: &str = "factory://artifact-produced";
 pub const EVENT_RUN_COMPLETED: &str = "factory://run-completed";
+pub const EVENT_RUN_HISTORY: &str = "factory://run-history";
+pub const EVENT_RUN_POLL: &str = "factory://run-poll";
 pub const EVENT_ERROR: &str = "factory://error";
+/// Sent by the factory once it is initialized and reading commands.
+pub const EVENT_READY: &str = "factory://ready";
+/// Emitted by the bridge after a spawned process's ready event, with how
+/// long the process took to get there.
+pub const EVENT_BRIDGE_STARTED: &str = "factory://bridge-started";
+/// A spawned process is writing to stderr faster than
+/// `stderr_warning_threshold` allows, or a process's stdout reader
+/// thread panicked.
+pub const EVENT_BRIDGE_ERROR: &str = "factory://bridge-error";
+/// The bridge reattached to a factory process left running by a previous
+/// session.
+pub const EVENT_BRIDGE_RECONNECTED: &str = "factory://bridge-reconnected";
 pub const EVENT_RAW: &str = "factory://raw";
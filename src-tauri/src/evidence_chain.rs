@@ -0,0 +1,177 @@
+//! Evidence chain — integrity checks and maintenance for a run's
+//! `evidence-chain.ndjson`.
+//!
+//! Entries are handled as JSON values so fields the factory adds survive
+//! a rewrite. Hashes are computed the way `EvidenceChain.append` computes
+//! them: SHA-256 over the entry without its `hash`, keys sorted.
+
+use std::fs;
+use std::io::Write;
+
+use sha2::{Digest, Sha256};
+
+use crate::error::FactoryError;
+use crate::evidence_types::KNOWN_EVIDENCE_TYPES;
+use crate::run_dir::{RunDir, EVIDENCE_CHAIN_FILE};
+use crate::types::CompactionReport;
+
+/// Fields covered by an entry's hash, in `stableStringify` order.
+const HASHED_FIELDS: &[&str] =
+    &["data", "prevHash", "seq", "stage", "timestamp", "type", "workerId"];
+
+const COMPACTION_TMP_FILE: &str = "evidence-chain.ndjson.tmp";
+
+/// The hash the factory records for `entry`. Any `hash` already on the
+/// entry is ignored.
+pub fn entry_hash(entry: &serde_json::Value) -> String {
+    // serde_json's default map is sorted, which matches the key order of
+    // the factory's stableStringify at every depth.
+    let hashed: serde_json::Map<String, serde_json::Value> = HASHED_FIELDS
+        .iter()
+        .filter_map(|f| entry.get(*f).map(|v| (f.to_string(), v.clone())))
+        .collect();
+    let digest = Sha256::digest(serde_json::Value::Object(hashed).to_string().as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Check that each entry's hash matches its contents and that it links to
+/// the entry before it. `entries` must be in `seq` order.
+pub fn verify(entries: &[serde_json::Value]) -> Result<(), String> {
+    let mut prev_hash = serde_json::Value::Null;
+    for entry in entries {
+        let seq = &entry["seq"];
+        if entry["prevHash"] != prev_hash {
+            return Err(format!("entry {} does not link to the entry before it", seq));
+        }
+        if entry["hash"].as_str() != Some(entry_hash(entry).as_str()) {
+            return Err(format!("entry {} does not match its hash", seq));
+        }
+        let entry_type = entry["type"].as_str().unwrap_or_default();
+        if !KNOWN_EVIDENCE_TYPES.contains_key(entry_type) {
+            // Still valid: the hash covers it, and a newer factory may
+            // have added the type.
+            tracing::warn!("evidence entry {} has unknown type {:?}", seq, entry_type);
+        }
+        prev_hash = entry["hash"].clone();
+    }
+    Ok(())
+}
+
+/// Merge every `evidence-chain*.ndjson` file in `run_dir` into
+/// `evidence-chain.ndjson`, ordered and deduplicated by `seq`. Nothing is
+/// written unless the merged chain verifies. The result is written to a
+/// temporary file and renamed into place; the other shards are removed
+/// after that. Refused unless the run has finished.
+pub fn compact(run_dir: &RunDir) -> Result<CompactionReport, FactoryError> {
+    run_dir.require_terminal("compaction")?;
+    let dir = run_dir.path();
+    let io_err = |e: std::io::Error| FactoryError::IoError(format!("{}: {}", dir.display(), e));
+
+    let mut shards: Vec<String> = fs::read_dir(dir)
+        .map_err(io_err)?
+        .flatten()
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|name| name.starts_with("evidence-chain") && name.ends_with(".ndjson"))
+        .collect();
+    shards.sort();
+
+    let mut entries = Vec::new();
+    for shard in &shards {
+        let data = run_dir.read_to_string(shard)?;
+        for (i, line) in data.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let entry: serde_json::Value = serde_json::from_str(line).map_err(|e| {
+                FactoryError::ParseError(format!("{} line {}: {}", shard, i + 1, e))
+            })?;
+            entries.push(entry);
+        }
+    }
+
+    let read = entries.len();
+    entries.sort_by_key(|e| e["seq"].as_u64().unwrap_or(u64::MAX));
+    entries.dedup_by(|a, b| a["seq"] == b["seq"]);
+    let duplicates_removed = (read - entries.len()) as u64;
+    verify(&entries).map_err(|e| {
+        FactoryError::ParseError(format!("merged evidence chain does not verify: {}", e))
+    })?;
+
+    let mut out = run_dir.open_for_write(COMPACTION_TMP_FILE)?;
+    for entry in &entries {
+        writeln!(out, "{}", entry).map_err(io_err)?;
+    }
+    out.sync_all().map_err(io_err)?;
+    drop(out);
+    fs::rename(dir.join(COMPACTION_TMP_FILE), dir.join(EVIDENCE_CHAIN_FILE)).map_err(io_err)?;
+    for shard in shards.iter().filter(|s| *s != EVIDENCE_CHAIN_FILE) {
+        fs::remove_file(dir.join(shard)).map_err(io_err)?;
+    }
+
+    Ok(CompactionReport {
+        input_files: shards.len() as u32,
+        total_entries: entries.len() as u64,
+        duplicates_removed,
+        output_size_bytes: fs::metadata(dir.join(EVIDENCE_CHAIN_FILE)).map_err(io_err)?.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_dir::STATE_FILE;
+    use crate::testing::{entry, EvidenceChainWriter};
+
+    fn write_chain(run_dir: &RunDir, len: usize) -> Vec<String> {
+        fs::create_dir_all(run_dir.path()).unwrap();
+        fs::write(run_dir.path().join(STATE_FILE), r#"{"status": "shipped"}"#).unwrap();
+        let path = run_dir.path().join(EVIDENCE_CHAIN_FILE);
+        let mut writer = EvidenceChainWriter::new(&path).unwrap();
+        for _ in 0..len {
+            writer.append(&entry("note", "master", "planning")).unwrap();
+        }
+        drop(writer);
+        fs::read_to_string(path).unwrap().lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn shards_are_merged_in_seq_order_without_duplicates() {
+        let root = tempfile::tempdir().unwrap();
+        let run_dir = RunDir::for_write(root.path(), "run-1").unwrap();
+        let lines = write_chain(&run_dir, 4);
+        let dir = run_dir.path();
+        fs::write(dir.join(EVIDENCE_CHAIN_FILE), format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        let shard = format!("{}\n{}\n{}\n", lines[3], lines[1], lines[2]);
+        fs::write(dir.join("evidence-chain.1.ndjson"), shard).unwrap();
+
+        let report = compact(&run_dir).unwrap();
+        assert_eq!(report.input_files, 2);
+        assert_eq!(report.total_entries, 4);
+        assert_eq!(report.duplicates_removed, 1);
+        let merged = fs::read_to_string(dir.join(EVIDENCE_CHAIN_FILE)).unwrap();
+        assert_eq!(merged, lines.join("\n") + "\n");
+        assert!(!dir.join("evidence-chain.1.ndjson").exists());
+    }
+
+    #[test]
+    fn a_chain_with_a_gap_is_left_untouched() {
+        let root = tempfile::tempdir().unwrap();
+        let run_dir = RunDir::for_write(root.path(), "run-1").unwrap();
+        let lines = write_chain(&run_dir, 3);
+        let original = format!("{}\n{}\n", lines[0], lines[2]);
+        fs::write(run_dir.path().join(EVIDENCE_CHAIN_FILE), &original).unwrap();
+
+        assert!(matches!(compact(&run_dir), Err(FactoryError::ParseError(_))));
+        let kept = fs::read_to_string(run_dir.path().join(EVIDENCE_CHAIN_FILE)).unwrap();
+        assert_eq!(kept, original);
+    }
+
+    #[test]
+    fn a_run_in_progress_is_not_compacted() {
+        let root = tempfile::tempdir().unwrap();
+        let run_dir = RunDir::for_write(root.path(), "run-1").unwrap();
+        write_chain(&run_dir, 2);
+        fs::write(run_dir.path().join(STATE_FILE), r#"{"status": "building"}"#).unwrap();
+        fs::write(run_dir.path().join("evidence-chain.1.ndjson"), "").unwrap();
+
+        assert!(matches!(compact(&run_dir), Err(FactoryError::InvalidArgument(_))));
+        assert!(run_dir.path().join("evidence-chain.1.ndjson").exists());
+    }
+}
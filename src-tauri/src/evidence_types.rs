@@ -0,0 +1,120 @@
+//! Evidence entry types — what each `type` in an evidence chain means,
+//! mirroring the factory's `EntryType` union in `evidence-chain.ts`.
+//!
+//! `expected_data_fields` lists the `data` keys the factory always writes
+//! for the type; entries may carry more.
+
+use phf::phf_map;
+
+use crate::types::EvidenceTypeDescriptor;
+
+#[derive(Debug, Clone, Copy)]
+pub struct EvidenceTypeInfo {
+    pub description: &'static str,
+    /// Whether an auditor reviewing the run needs to see entries of this
+    /// type, as opposed to progress chatter.
+    pub is_audit_relevant: bool,
+    pub expected_data_fields: &'static [&'static str],
+}
+
+pub static KNOWN_EVIDENCE_TYPES: phf::Map<&'static str, EvidenceTypeInfo> = phf_map! {
+    "run_start" => EvidenceTypeInfo {
+        description: "The master started a run from a spec.",
+        is_audit_relevant: true,
+        expected_data_fields: &["runId", "specHash", "pipeline", "maxRetries"],
+    },
+    "worker_start" => EvidenceTypeInfo {
+        description: "A stage's worker started an attempt.",
+        is_audit_relevant: false,
+        expected_data_fields: &["attempt", "stageId"],
+    },
+    "worker_end" => EvidenceTypeInfo {
+        description: "A worker attempt finished, as done or failed.",
+        is_audit_relevant: false,
+        expected_data_fields: &["status", "attempt"],
+    },
+    "gate_pass" => EvidenceTypeInfo {
+        description: "A quality gate passed, with the checks it measured.",
+        is_audit_relevant: true,
+        expected_data_fields: &["gateId", "passed", "checks"],
+    },
+    "gate_fail" => EvidenceTypeInfo {
+        description: "A quality gate failed, with the checks it measured.",
+        is_audit_relevant: true,
+        expected_data_fields: &["gateId", "passed", "checks"],
+    },
+    "artifact_produced" => EvidenceTypeInfo {
+        description: "A release artifact was written for a delivery target.",
+        is_audit_relevant: true,
+        expected_data_fields: &["target"],
+    },
+    "llm_call" => EvidenceTypeInfo {
+        description: "A worker called the model; prompt and response are recorded as hashes.",
+        is_audit_relevant: true,
+        expected_data_fields: &["promptHash", "responseHash", "model", "role"],
+    },
+    "error" => EvidenceTypeInfo {
+        description: "A worker attempt threw.",
+        is_audit_relevant: true,
+        expected_data_fields: &["error"],
+    },
+    "note" => EvidenceTypeInfo {
+        description: "Progress information; `event` says what happened.",
+        is_audit_relevant: false,
+        expected_data_fields: &["event"],
+    },
+    "consequence_hit" => EvidenceTypeInfo {
+        description: "Planning matched a consequence memory record.",
+        is_audit_relevant: true,
+        expected_data_fields: &["recordId", "pattern", "confidence"],
+    },
+    "run_end" => EvidenceTypeInfo {
+        description: "The run finished as shipped or aborted.",
+        is_audit_relevant: true,
+        expected_data_fields: &["status"],
+    },
+};
+
+/// Every known type, sorted by name.
+pub fn descriptors() -> Vec<EvidenceTypeDescriptor> {
+    let mut types: Vec<EvidenceTypeDescriptor> = KNOWN_EVIDENCE_TYPES
+        .entries()
+        .map(|(entry_type, info)| EvidenceTypeDescriptor {
+            entry_type: entry_type.to_string(),
+            description: info.description.to_string(),
+            is_audit_relevant: info.is_audit_relevant,
+            expected_data_fields: info.expected_data_fields.iter().map(|f| f.to_string()).collect(),
+        })
+        .collect();
+    types.sort_by(|a, b| a.entry_type.cmp(&b.entry_type));
+    types
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn factory_entry_types_are_all_known() {
+        let factory_types = [
+            "run_start",
+            "worker_start",
+            "worker_end",
+            "gate_pass",
+            "gate_fail",
+            "artifact_produced",
+            "llm_call",
+            "error",
+            "note",
+            "consequence_hit",
+            "run_end",
+        ];
+        assert!(factory_types.iter().all(|t| KNOWN_EVIDENCE_TYPES.contains_key(t)));
+        assert_eq!(KNOWN_EVIDENCE_TYPES.len(), factory_types.len());
+
+        let listed = descriptors();
+        assert_eq!(listed[0].entry_type, "artifact_produced");
+        let note = listed.iter().find(|d| d.entry_type == "note").unwrap();
+        assert!(!note.is_audit_relevant);
+    }
+}
@@ -1,26 +1,165 @@
+pub mod archive;
+pub mod audit;
+pub mod auth;
 pub mod bridge;
 pub mod commands;
+pub mod config;
+pub mod consequence;
 pub mod enforcer;
+pub mod envelope;
+pub mod error;
+pub mod event_router;
+pub mod event_store;
 pub mod events;
+pub mod evidence_chain;
+pub mod evidence_types;
+#[cfg(windows)]
+pub mod named_pipe;
+pub mod origin;
+pub mod pid_file;
+pub mod progress;
+pub mod rate_limit;
+pub mod run_dir;
+pub mod session;
+pub mod spec_migration;
+pub mod spec_schema;
+pub mod spec_template;
+#[cfg(test)]
+mod testing;
+#[cfg(unix)]
+pub mod tls;
 pub mod types;
 
 use std::sync::Mutex;
 
+use tauri::Manager;
+
+use auth::AuthContext;
 use bridge::FactoryBridge;
+use config::BridgeConfig;
+use rate_limit::RateLimiter;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let config = BridgeConfig::default();
+    let auth = AuthContext::load(&config.working_dir);
+    let auto_reconnect = config.auto_reconnect;
+    if config.auto_expire_consequence_memory {
+        match commands::memory::expire_consequence_memory(&config) {
+            Ok(report) => tracing::info!(
+                "expired {} of {} consequence memory domains ({} keys)",
+                report.domains_expired,
+                report.domains_checked,
+                report.keys_removed
+            ),
+            Err(e) => tracing::warn!("consequence memory expiration failed: {}", e),
+        }
+    }
+    bridge::warn_if_dist_missing();
+    let bridge = FactoryBridge::new(config.clone());
+    let progress = bridge.progress_tracker();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .manage(Mutex::new(FactoryBridge::new()))
+        .manage(auth)
+        .manage(RateLimiter::new(config.rate_limits))
+        .manage(config)
+        .manage(Mutex::new(bridge))
+        .setup(move |app| {
+            tauri::async_runtime::spawn(progress::emit_summaries(app.handle().clone(), progress));
+            if auto_reconnect {
+                let bridge = app.state::<Mutex<FactoryBridge>>();
+                if let Ok(mut b) = bridge.lock() {
+                    b.reconnect_existing(app.handle());
+                };
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::run::start_run,
             commands::run::abort_run,
+            commands::run::clone_run,
+            commands::run::generate_deterministic_run_id,
             commands::query::get_run_status,
+            commands::query::poll_run_until_complete,
             commands::query::get_run_history,
+            commands::query::get_active_run_ids,
+            commands::query::get_latest_run,
+            commands::query::get_run_history_by_spec,
+            commands::query::search_run_history,
+            commands::query::find_corrupted_runs,
             commands::query::get_gate_results,
+            commands::query::get_gate_summary,
+            commands::query::get_stage_gates,
+            commands::query::get_gate_stages,
+            commands::query::get_gate_pass_rate_trend,
+            commands::query::get_gate_history,
+            commands::query::get_run_anomalies,
             commands::query::get_evidence_range,
+            commands::query::get_evidence_from,
+            commands::query::get_evidence_by_hash,
+            commands::query::get_checkpoint,
+            commands::query::get_run_timeline,
+            commands::query::stream_evidence_range,
+            commands::query::stream_worker_log,
+            commands::query::tail_evidence,
+            commands::query::get_evidence_stats,
+            commands::query::list_run_evidence_types,
+            commands::query::get_evidence_count,
+            commands::query::get_artifact,
+            commands::query::get_artifact_content,
+            commands::query::get_artifact_diff,
+            commands::query::get_run_count,
+            commands::query::get_run_environment,
+            commands::query::get_runner_info,
+            commands::query::get_runner_comparison,
+            commands::query::search_all_runs_evidence,
+            commands::query::get_latest_artifact,
+            commands::query::get_worker_aggregate_progress,
+            commands::query::get_worker_count,
+            commands::query::get_worker_timeline,
+            commands::query::get_run_errors,
+            commands::query::get_run_logs,
+            commands::query::list_run_stages,
             commands::spec::validate_spec,
+            commands::spec::validate_blueprint,
+            commands::spec::get_blueprint_content,
+            commands::spec::get_blueprint_variables,
+            commands::spec::migrate_spec,
+            commands::spec::derive_spec_from_plan,
+            commands::spec::get_spec_schema,
+            commands::spec::get_run_spec_diff,
+            commands::spec::get_spec,
+            commands::spec::get_blueprint_spec,
+            commands::spec::get_stage_dependencies,
+            commands::metrics::get_metrics,
+            commands::health::get_run_health_score,
+            commands::bridge::get_bridge_state,
+            commands::bridge::get_last_error,
+            commands::bridge::get_current_run_id,
+            commands::bridge::query_event_store,
+            commands::bridge::subscribe_events_matching,
+            commands::bridge::unsubscribe_events,
+            commands::bridge::update_suppressed_events,
+            commands::memory::import_consequence_memory,
+            commands::memory::get_consequence_memory_keys,
+            commands::memory::get_consequence_memory_value,
+            commands::memory::get_consequence_memory_snapshot,
+            commands::memory::get_consequence_memory_size,
+            commands::memory::get_consequence_memory_age,
+            commands::memory::get_consequence_memory_changes,
+            commands::memory::purge_consequence_memory,
+            commands::memory::set_consequence_memory_ttl,
+            commands::memory::apply_consequence_memory_expiration,
+            commands::evidence::compact_evidence_chain,
+            commands::evidence::list_evidence_types,
+            commands::evidence::get_evidence_types,
+            commands::evidence::validate_run_directory,
+            commands::evidence::repair_run_directory,
+            commands::evidence::reindex_runs,
+            commands::export::export_evidence_sarif,
+            commands::export::export_run,
+            commands::export::export_run_html,
         ])
         .run(tauri::generate_context!())
         .expect("error running tauri application");
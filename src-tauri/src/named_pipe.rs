@@ -0,0 +1,128 @@
+/// Named pipes — the Windows transport for factory commands under
+/// `IpcMode::NamedPipe`.
+///
+/// The bridge creates an outbound pipe server at `\\.\pipe\{name}` before
+/// spawning the factory, which connects to it as a client and reads
+/// command lines from it instead of stdin. Events still arrive on stdout,
+/// so the pipe only ever carries data one way.
+
+use std::fs::File;
+use std::io;
+use std::os::windows::io::{AsRawHandle, FromRawHandle, RawHandle};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use windows_sys::Win32::Foundation::{ERROR_PIPE_CONNECTED, HANDLE, INVALID_HANDLE_VALUE};
+use windows_sys::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_OUTBOUND};
+use windows_sys::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_WAIT,
+};
+
+use crate::error::FactoryError;
+
+/// The factory reads commands from the pipe named here instead of stdin.
+pub const PIPE_ENV: &str = "NEOXTEN_COMMAND_PIPE";
+
+const PIPE_BUFFER_BYTES: u32 = 64 * 1024;
+
+pub fn pipe_path(name: &str) -> String {
+    format!(r"\\.\pipe\{}", name)
+}
+
+/// A pipe created but not yet connected to.
+pub struct PipeServer {
+    path: String,
+    pipe: File,
+}
+
+/// Create the server end of `\\.\pipe\{name}`. Fails if another process
+/// already owns a pipe by that name.
+pub fn create(name: &str) -> Result<PipeServer, FactoryError> {
+    let path = pipe_path(name);
+    let wide: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+    let handle = unsafe {
+        CreateNamedPipeW(
+            wide.as_ptr(),
+            PIPE_ACCESS_OUTBOUND | FILE_FLAG_FIRST_PIPE_INSTANCE,
+            PIPE_TYPE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            1,
+            PIPE_BUFFER_BYTES,
+            0,
+            0,
+            std::ptr::null(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(FactoryError::BridgeError(format!(
+            "creating {}: {}",
+            path,
+            io::Error::last_os_error()
+        )));
+    }
+    let pipe = unsafe { File::from_raw_handle(handle as RawHandle) };
+    Ok(PipeServer { path, pipe })
+}
+
+impl PipeServer {
+    /// Wait up to `timeout` for the client to connect, then return the
+    /// end commands are written to.
+    pub fn accept(self, timeout: Duration) -> Result<File, FactoryError> {
+        let PipeServer { path, pipe } = self;
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let handle = pipe.as_raw_handle() as HANDLE;
+            let ok = unsafe { ConnectNamedPipe(handle, std::ptr::null_mut()) };
+            // A client that connected between creation and this call is
+            // reported as an error, but the pipe is usable.
+            let err = io::Error::last_os_error();
+            let connected = ok != 0 || err.raw_os_error() == Some(ERROR_PIPE_CONNECTED as i32);
+            let _ = tx.send(if connected { Ok(pipe) } else { Err(err) });
+        });
+
+        match rx.recv_timeout(timeout) {
+            Ok(Ok(pipe)) => Ok(pipe),
+            Ok(Err(e)) => Err(FactoryError::BridgeError(format!("connecting {}: {}", path, e))),
+            Err(_) => {
+                // ConnectNamedPipe has no timeout; connecting to the pipe
+                // ourselves is what releases the waiting thread.
+                let _ = File::open(&path);
+                Err(FactoryError::Timeout(format!(
+                    "factory did not connect to {} within {:?}",
+                    path, timeout
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+
+    #[test]
+    fn commands_written_to_the_pipe_reach_the_client() {
+        let name = format!("neoxten-test-{}", std::process::id());
+        let server = create(&name).unwrap();
+        let client = std::thread::spawn(move || {
+            let pipe = File::open(pipe_path(&name)).unwrap();
+            let mut line = String::new();
+            BufReader::new(pipe).read_line(&mut line).unwrap();
+            line
+        });
+
+        let mut pipe = server.accept(Duration::from_secs(5)).unwrap();
+        writeln!(pipe, r#"{{"type":"abort_run"}}"#).unwrap();
+
+        assert_eq!(client.join().unwrap(), "{\"type\":\"abort_run\"}\n");
+    }
+
+    #[test]
+    fn accept_times_out_without_a_client() {
+        let name = format!("neoxten-test-timeout-{}", std::process::id());
+        let server = create(&name).unwrap();
+
+        let result = server.accept(Duration::from_millis(50));
+        assert!(matches!(result, Err(FactoryError::Timeout(_))));
+    }
+}
@@ -0,0 +1,94 @@
+//! Origin checks for sensitive commands.
+//!
+//! Any script running in a WebView can `invoke` a command, so the role
+//! check alone can't tell the app's own UI from a page the WebView was
+//! navigated to or a window opened by injected script. Commands that
+//! `FactoryCommand::is_sensitive` marks are also refused unless they come
+//! from the main window showing one of `BridgeConfig::trusted_origins`.
+
+use tauri::{Runtime, Url, Webview};
+
+use crate::enforcer::FactoryCommand;
+use crate::error::{Denial, FactoryError};
+
+/// The label Tauri gives the window declared in `tauri.conf.json`.
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Origins the bundled frontend is served from on each platform.
+pub const BUNDLED_ORIGINS: &[&str] =
+    &["tauri://localhost", "http://tauri.localhost", "https://tauri.localhost"];
+
+/// The Vite dev server from `build.devUrl`, trusted in debug builds only.
+pub const DEV_SERVER_ORIGIN: &str = "http://localhost:5173";
+
+/// Refuse `cmd` if it is sensitive and `webview` isn't the trusted UI.
+pub fn guard<R: Runtime>(
+    cmd: &FactoryCommand,
+    webview: &Webview<R>,
+    trusted_origins: &[String],
+) -> Result<(), FactoryError> {
+    if !cmd.is_sensitive() {
+        return Ok(());
+    }
+    let url = webview
+        .url()
+        .map_err(|e| untrusted(format!("webview URL unavailable: {}", e)))?;
+    check(webview.label(), &url, trusted_origins)
+}
+
+/// `url` is the page the invoking webview is showing.
+pub fn check(label: &str, url: &Url, trusted_origins: &[String]) -> Result<(), FactoryError> {
+    if label != MAIN_WINDOW_LABEL {
+        return Err(untrusted(format!("command not accepted from window {:?}", label)));
+    }
+    let origin = origin_of(url);
+    if !trusted_origins.contains(&origin) {
+        return Err(untrusted(format!("command not accepted from origin {}", origin)));
+    }
+    Ok(())
+}
+
+fn untrusted(reason: String) -> FactoryError {
+    FactoryError::Unauthorized(Denial::UntrustedOrigin(reason))
+}
+
+/// `Url::origin` is opaque for custom schemes such as `tauri:`, so the
+/// origin is spelled out from its parts.
+fn origin_of(url: &Url) -> String {
+    match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}://{}:{}", url.scheme(), host, port),
+        (Some(host), None) => format!("{}://{}", url.scheme(), host),
+        (None, _) => format!("{}:", url.scheme()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_main_window_on_a_trusted_origin_passes() {
+        let trusted: Vec<String> = BUNDLED_ORIGINS.iter().map(|o| o.to_string()).collect();
+        let url = |s: &str| Url::parse(s).unwrap();
+
+        assert!(check("main", &url("tauri://localhost/index.html"), &trusted).is_ok());
+        assert!(check("main", &url("https://tauri.localhost/runs?id=1"), &trusted).is_ok());
+        for (label, page) in [
+            ("main", "https://evil.example.com/"),
+            ("main", "http://localhost:5173/"),
+            ("main", "https://tauri.localhost.evil.example.com/"),
+            ("main", "data:text/html,hi"),
+            ("popup", "tauri://localhost/"),
+        ] {
+            assert!(
+                matches!(
+                    check(label, &url(page), &trusted),
+                    Err(FactoryError::Unauthorized(Denial::UntrustedOrigin(_)))
+                ),
+                "{} {}",
+                label,
+                page
+            );
+        }
+    }
+}
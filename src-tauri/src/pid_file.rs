@@ -0,0 +1,109 @@
+//! PID file — lets external monitoring find the factory processes without
+//! asking the app.
+//!
+//! `ops/factory/.bridge.pid` holds one PID per line, one line per running
+//! factory process. It is rewritten whenever a process is spawned or
+//! stopped and removed once none are left.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::FactoryError;
+
+pub const PID_FILE: &str = "ops/factory/.bridge.pid";
+
+/// Write `pids` to `path`, or remove the file when there are none.
+pub fn write(path: &Path, pids: &[u32]) -> Result<(), FactoryError> {
+    if pids.is_empty() {
+        return remove(path);
+    }
+    let io_err =
+        |e: std::io::Error| FactoryError::IoError(format!("writing {}: {}", path.display(), e));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_err)?;
+    }
+    let contents: String = pids.iter().map(|pid| format!("{}\n", pid)).collect();
+    fs::write(path, contents).map_err(io_err)
+}
+
+pub fn remove(path: &Path) -> Result<(), FactoryError> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(FactoryError::IoError(format!("removing {}: {}", path.display(), e))),
+    }
+}
+
+pub fn read(path: &Path) -> Vec<u32> {
+    fs::read_to_string(path)
+        .map(|data| data.lines().filter_map(|l| l.trim().parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Delete a PID file left behind by a previous session whose processes
+/// are all gone. A file naming a live process is left alone.
+pub fn clear_stale(path: &Path) -> Result<(), FactoryError> {
+    if !path.exists() || read(path).into_iter().any(is_alive) {
+        return Ok(());
+    }
+    remove(path)
+}
+
+#[cfg(unix)]
+pub fn is_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 performs the permission and existence checks only. EPERM
+    // means the process exists but belongs to someone else.
+    let rc = unsafe { libc::kill(pid, 0) };
+    rc == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(windows)]
+pub fn is_alive(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        let mut code = 0;
+        let ok = GetExitCodeProcess(handle, &mut code) != 0;
+        CloseHandle(handle);
+        ok && code == STILL_ACTIVE as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn stale_pid_file_is_cleared() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PID_FILE);
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = child.id();
+        child.wait().unwrap();
+
+        write(&path, &[dead_pid]).unwrap();
+        clear_stale(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn live_pid_file_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PID_FILE);
+
+        write(&path, &[std::process::id()]).unwrap();
+        clear_stale(&path).unwrap();
+        assert_eq!(read(&path), [std::process::id()]);
+    }
+}
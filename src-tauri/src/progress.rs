@@ -0,0 +1,194 @@
+//! Run progress summaries — the periodic `factory://run-progress-summary`
+//! event.
+//!
+//! Each run's event router records the `worker-progress` and
+//! `stage-changed` events it forwards into a `ProgressTracker`, which keeps
+//! the most recent worker samples per run in a bounded ring buffer. A
+//! background task turns those into one summary per run every two seconds,
+//! so the frontend no longer aggregates individual worker events.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter};
+
+use crate::events::EVENT_RUN_PROGRESS_SUMMARY;
+use crate::types::RunProgressSummary;
+
+pub const SUMMARY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Worker samples kept per run. Only each worker's latest one matters, so
+/// this just has to exceed the number of workers reporting at once.
+const RECENT_SAMPLES: usize = 256;
+
+/// A worker with no progress event for this long is no longer active.
+const ACTIVE_WINDOW: Duration = Duration::from_secs(60);
+
+struct WorkerSample {
+    worker_id: String,
+    percent: f32,
+    at: Instant,
+}
+
+struct RunProgress {
+    /// When the bridge saw the run's first event.
+    started: Instant,
+    stage: String,
+    samples: VecDeque<WorkerSample>,
+}
+
+/// Shared between the event routers, which record, and the summary task,
+/// which reads.
+#[derive(Clone, Default)]
+pub struct ProgressTracker {
+    runs: Arc<Mutex<HashMap<String, RunProgress>>>,
+}
+
+impl ProgressTracker {
+    /// Note one event for `run_id`. `event` is the name without the
+    /// `factory://` prefix. A completed run is forgotten.
+    pub fn record(&self, run_id: &str, event: &str, data: &serde_json::Value) {
+        let Ok(mut runs) = self.runs.lock() else {
+            return;
+        };
+        if event == "run-completed" {
+            runs.remove(run_id);
+            return;
+        }
+        let run = runs.entry(run_id.to_string()).or_insert_with(|| RunProgress {
+            started: Instant::now(),
+            stage: String::new(),
+            samples: VecDeque::new(),
+        });
+        match event {
+            "stage-changed" if data["status"] == "started" => {
+                run.stage = data["stage"].as_str().unwrap_or_default().to_string();
+            }
+            "worker-progress" => {
+                let Some(worker_id) = data["workerId"].as_str() else {
+                    return;
+                };
+                // A progress event without a percentage keeps the worker's
+                // last one; it still counts as a sign of life.
+                let percent = data["progress"].as_f64().map(|p| p.clamp(0.0, 100.0) as f32);
+                let last = || {
+                    let mut samples = run.samples.iter().rev();
+                    samples.find(|s| s.worker_id == worker_id).map(|s| s.percent)
+                };
+                let percent = percent.or_else(last).unwrap_or(0.0);
+                if run.samples.len() == RECENT_SAMPLES {
+                    run.samples.pop_front();
+                }
+                run.samples.push_back(WorkerSample {
+                    worker_id: worker_id.to_string(),
+                    percent,
+                    at: Instant::now(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// One summary per tracked run, as of `now`.
+    pub fn summaries(&self, now: Instant) -> Vec<RunProgressSummary> {
+        let Ok(runs) = self.runs.lock() else {
+            return vec![];
+        };
+        let mut summaries: Vec<RunProgressSummary> =
+            runs.iter().map(|(run_id, run)| summarize(run_id, run, now)).collect();
+        summaries.sort_by(|a, b| a.run_id.cmp(&b.run_id));
+        summaries
+    }
+}
+
+fn summarize(run_id: &str, run: &RunProgress, now: Instant) -> RunProgressSummary {
+    let mut latest: HashMap<&str, &WorkerSample> = HashMap::new();
+    for sample in &run.samples {
+        latest.insert(&sample.worker_id, sample);
+    }
+
+    let percent_complete = if latest.is_empty() {
+        0.0
+    } else {
+        latest.values().map(|s| s.percent).sum::<f32>() / latest.len() as f32
+    };
+    let active_workers = latest
+        .values()
+        .filter(|s| s.percent < 100.0 && now.saturating_duration_since(s.at) <= ACTIVE_WINDOW)
+        .count() as u32;
+    let elapsed_ms = now.saturating_duration_since(run.started).as_millis() as u64;
+    // Linear extrapolation; meaningless before any progress is reported.
+    let estimated_remaining_ms = (percent_complete > 0.0 && percent_complete < 100.0).then(|| {
+        (elapsed_ms as f64 * f64::from(100.0 - percent_complete) / f64::from(percent_complete))
+            as u64
+    });
+
+    RunProgressSummary {
+        run_id: run_id.to_string(),
+        percent_complete,
+        active_workers,
+        stage: run.stage.clone(),
+        elapsed_ms,
+        estimated_remaining_ms,
+    }
+}
+
+/// Emit a summary for every tracked run every `SUMMARY_INTERVAL`, for the
+/// life of the app.
+pub async fn emit_summaries(app: AppHandle, tracker: ProgressTracker) {
+    let mut interval = tokio::time::interval(SUMMARY_INTERVAL);
+    loop {
+        interval.tick().await;
+        for summary in tracker.summaries(Instant::now()) {
+            let _ = app.emit(EVENT_RUN_PROGRESS_SUMMARY, &summary);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn summary_uses_each_workers_latest_progress() {
+        let tracker = ProgressTracker::default();
+        let stage = json!({"stage": "building", "status": "started"});
+        tracker.record("run-1", "stage-changed", &stage);
+        tracker.record("run-1", "worker-progress", &json!({"workerId": "a", "progress": 10}));
+        tracker.record("run-1", "worker-progress", &json!({"workerId": "b", "progress": 100}));
+        tracker.record("run-1", "worker-progress", &json!({"workerId": "a", "progress": 50}));
+        let no_percent = json!({"workerId": "a", "message": "still going"});
+        tracker.record("run-1", "worker-progress", &no_percent);
+
+        let summaries = tracker.summaries(Instant::now());
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!(summary.stage, "building");
+        assert_eq!(summary.percent_complete, 75.0);
+        assert_eq!(summary.active_workers, 1);
+        assert!(summary.estimated_remaining_ms.is_some());
+    }
+
+    #[test]
+    fn completed_runs_are_dropped() {
+        let tracker = ProgressTracker::default();
+        tracker.record("run-1", "worker-progress", &json!({"workerId": "a", "progress": 10}));
+        tracker.record("run-1", "run-completed", &json!({"status": "shipped"}));
+
+        assert!(tracker.summaries(Instant::now()).is_empty());
+    }
+
+    #[test]
+    fn ring_buffer_is_bounded() {
+        let tracker = ProgressTracker::default();
+        for i in 0..RECENT_SAMPLES + 10 {
+            let worker = format!("w{}", i);
+            tracker.record("run-1", "worker-progress", &json!({"workerId": worker, "progress": 1}));
+        }
+
+        let runs = tracker.runs.lock().unwrap();
+        assert_eq!(runs["run-1"].samples.len(), RECENT_SAMPLES);
+    }
+}
@@ -0,0 +1,137 @@
+//! RateLimiter — token buckets per `FactoryCommand` variant.
+//!
+//! Keeps a misbehaving frontend from flooding the factory process. Each
+//! variant has its own bucket, so a burst of status queries cannot starve
+//! an abort. Buckets hold one second's worth of tokens and refill
+//! continuously.
+
+use std::collections::HashMap;
+use std::mem::Discriminant;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::enforcer::FactoryCommand;
+use crate::error::FactoryError;
+
+/// Commands allowed per second, by command category.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RateLimits {
+    pub read_only_per_sec: f64,
+    pub run_control_per_sec: f64,
+    pub spec_per_sec: f64,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            read_only_per_sec: 10.0,
+            run_control_per_sec: 1.0,
+            spec_per_sec: 2.0,
+        }
+    }
+}
+
+impl RateLimits {
+    fn for_command(&self, cmd: &FactoryCommand) -> f64 {
+        match cmd {
+            FactoryCommand::StartRun { .. }
+            | FactoryCommand::AbortRun { .. }
+            | FactoryCommand::CloneRun { .. }
+            | FactoryCommand::ReindexRuns => self.run_control_per_sec,
+            FactoryCommand::ValidateSpec { .. } | FactoryCommand::DeriveSpecFromPlan { .. } => {
+                self.spec_per_sec
+            }
+            _ => self.read_only_per_sec,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+pub struct RateLimiter {
+    limits: RateLimits,
+    buckets: Mutex<HashMap<Discriminant<FactoryCommand>, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token for `cmd`, or report how long until one is available.
+    pub fn check(&self, cmd: &FactoryCommand) -> Result<(), FactoryError> {
+        self.check_at(cmd, Instant::now())
+    }
+
+    fn check_at(&self, cmd: &FactoryCommand, now: Instant) -> Result<(), FactoryError> {
+        let rate = self.limits.for_command(cmd);
+        if rate <= 0.0 {
+            return Err(FactoryError::RateLimited {
+                retry_after_ms: u64::MAX,
+            });
+        }
+
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|e| FactoryError::BridgeError(format!("lock error: {}", e)))?;
+        let bucket = buckets.entry(std::mem::discriminant(cmd)).or_insert(Bucket {
+            tokens: rate,
+            refilled_at: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait_secs = (1.0 - bucket.tokens) / rate;
+            Err(FactoryError::RateLimited {
+                retry_after_ms: (wait_secs * 1000.0).ceil() as u64,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn bucket_allows_a_burst_then_refills() {
+        let limiter = RateLimiter::new(RateLimits::default());
+        let abort = FactoryCommand::AbortRun { run_id: "r".into() };
+        let t0 = Instant::now();
+
+        assert!(limiter.check_at(&abort, t0).is_ok());
+        match limiter.check_at(&abort, t0) {
+            Err(FactoryError::RateLimited { retry_after_ms }) => assert_eq!(retry_after_ms, 1000),
+            other => panic!("expected rate limit, got {:?}", other),
+        }
+        assert!(limiter.check_at(&abort, t0 + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn variants_have_independent_buckets() {
+        let limiter = RateLimiter::new(RateLimits::default());
+        let t0 = Instant::now();
+
+        for _ in 0..10 {
+            assert!(limiter.check_at(&FactoryCommand::GetRunStatus, t0).is_ok());
+        }
+        assert!(limiter.check_at(&FactoryCommand::GetRunStatus, t0).is_err());
+        assert!(limiter.check_at(&FactoryCommand::GetRunHistory { include_active: true }, t0).is_ok());
+    }
+}
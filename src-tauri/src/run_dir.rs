@@ -0,0 +1,349 @@
+//! RunDir — a validated handle on one run's directory under
+//! `ops/factory/runs/{run_id}`.
+//!
+//! Every path a command derives from caller input goes through here, so
+//! run IDs and artifact paths cannot escape the run directory. Handles are
+//! opened either for reading, which is all the query commands get, or for
+//! writing; a read handle never opens a file writable, so a query cannot
+//! clobber an active run's state.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+use crate::error::FactoryError;
+use crate::types::RunPhase;
+
+pub const RUNS_ROOT: &str = "ops/factory/runs";
+pub const STATE_FILE: &str = "run-state.json";
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const EVIDENCE_CHAIN_FILE: &str = "evidence-chain.ndjson";
+/// Copy of the spec taken when the run started, if `snapshot_spec` was on.
+pub const SPEC_SNAPSHOT_FILE: &str = "spec-snapshot.yaml";
+/// Environment variables at run start, if `capture_environment` was on.
+pub const ENVIRONMENT_FILE: &str = "environment.json";
+/// The spec and blueprint paths a run was started with, written by the
+/// bridge. The factory's manifest does not record them.
+pub const RUN_INPUTS_FILE: &str = "run-inputs.json";
+/// The machine the run executed on, written by the factory.
+pub const RUNNER_FILE: &str = "runner.json";
+/// Summary of the evidence chain up to some `seq`, for starting a read
+/// part-way through.
+pub const EVIDENCE_CHECKPOINT_FILE: &str = "evidence-checkpoint.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunDir {
+    run_id: String,
+    path: PathBuf,
+    access: Access,
+}
+
+impl RunDir {
+    pub fn for_read(runs_root: &Path, run_id: &str) -> Result<Self, FactoryError> {
+        Self::open_dir(runs_root, run_id, Access::Read)
+    }
+
+    pub fn for_write(runs_root: &Path, run_id: &str) -> Result<Self, FactoryError> {
+        Self::open_dir(runs_root, run_id, Access::Write)
+    }
+
+    fn open_dir(runs_root: &Path, run_id: &str, access: Access) -> Result<Self, FactoryError> {
+        let valid = !run_id.is_empty()
+            && run_id != "."
+            && run_id != ".."
+            && !run_id.contains(['/', '\\']);
+        if !valid {
+            return Err(FactoryError::InvalidArgument(format!(
+                "invalid run id: {:?}",
+                run_id
+            )));
+        }
+        Ok(Self {
+            run_id: run_id.to_string(),
+            path: runs_root.join(run_id),
+            access,
+        })
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn access(&self) -> Access {
+        self.access
+    }
+
+    pub fn artifacts_dir(&self) -> PathBuf {
+        self.path.join("artifacts")
+    }
+
+    pub fn exists(&self, file: &str) -> bool {
+        self.path.join(file).exists()
+    }
+
+    /// Open `file` read-only, whatever the handle's access mode.
+    pub fn open(&self, file: &str) -> Result<File, FactoryError> {
+        OpenOptions::new()
+            .read(true)
+            .open(self.path.join(file))
+            .map_err(|e| {
+                FactoryError::IoError(format!("reading {} for run {}: {}", file, self.run_id, e))
+            })
+    }
+
+    /// Create the run directory, failing if it already exists. Only the
+    /// caller that created it gets `Ok`, so this claims the run ID.
+    pub fn create(&self) -> Result<(), FactoryError> {
+        if self.access != Access::Write {
+            return Err(FactoryError::InvalidArgument(format!(
+                "run {} was opened read-only; cannot create it",
+                self.run_id
+            )));
+        }
+        let runs_root = self.path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(runs_root)
+            .and_then(|()| fs::create_dir(&self.path))
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::AlreadyExists => {
+                    FactoryError::InvalidArgument(format!("run {} already exists", self.run_id))
+                }
+                _ => FactoryError::IoError(format!(
+                    "creating directory for run {}: {}",
+                    self.run_id, e
+                )),
+            })
+    }
+
+    /// Open `file` for writing, creating it (and the run directory) if
+    /// needed. Refused on a read handle.
+    pub fn open_for_write(&self, file: &str) -> Result<File, FactoryError> {
+        if self.access != Access::Write {
+            return Err(FactoryError::InvalidArgument(format!(
+                "run {} was opened read-only; cannot write {}",
+                self.run_id, file
+            )));
+        }
+        fs::create_dir_all(&self.path).map_err(|e| {
+            FactoryError::IoError(format!("creating directory for run {}: {}", self.run_id, e))
+        })?;
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(self.path.join(file))
+            .map_err(|e| {
+                FactoryError::IoError(format!("writing {} for run {}: {}", file, self.run_id, e))
+            })
+    }
+
+    /// Refuse `action` unless the run's state, or failing that its
+    /// manifest, records a terminal status, which is returned. The factory
+    /// may still be writing to a run in any other state.
+    pub fn require_terminal(&self, action: &str) -> Result<String, FactoryError> {
+        let status = [STATE_FILE, MANIFEST_FILE]
+            .iter()
+            .filter_map(|file| self.read_json(file).ok())
+            .find_map(|json| json["status"].as_str().map(str::to_string));
+        match status {
+            Some(s) if RunPhase::try_from(s.as_str()).is_ok_and(RunPhase::is_terminal) => Ok(s),
+            Some(s) => Err(FactoryError::InvalidArgument(format!(
+                "run {} is {}; {} needs a finished run",
+                self.run_id, s, action
+            ))),
+            None => Err(FactoryError::InvalidArgument(format!(
+                "run {} records no status; {} needs a finished run",
+                self.run_id, action
+            ))),
+        }
+    }
+
+    /// Read `file` from the run directory; errors name the run and file.
+    pub fn read_to_string(&self, file: &str) -> Result<String, FactoryError> {
+        let mut data = String::new();
+        self.open(file)?.read_to_string(&mut data).map_err(|e| {
+            FactoryError::IoError(format!("reading {} for run {}: {}", file, self.run_id, e))
+        })?;
+        Ok(data)
+    }
+
+    /// Read and parse a JSON file from the run directory.
+    pub fn read_json(&self, file: &str) -> Result<serde_json::Value, FactoryError> {
+        self.read_typed(file)
+    }
+
+    /// Read a JSON file from the run directory into `T`, so a missing or
+    /// mistyped field is an error naming the field.
+    pub fn read_typed<T: DeserializeOwned>(&self, file: &str) -> Result<T, FactoryError> {
+        let data = self.read_to_string(file)?;
+        serde_json::from_str(&data).map_err(|e| {
+            FactoryError::ParseError(format!("{} for run {}: {}", file, self.run_id, e))
+        })
+    }
+
+    /// Resolve `relative` inside the artifacts directory.
+    ///
+    /// Each existing component is canonicalized as it is added, so a
+    /// symlink pointing outside the directory is caught even when a plain
+    /// string prefix check would pass. Components that don't exist yet are
+    /// joined lexically; they cannot be symlinks.
+    pub fn safe_artifact_path(&self, relative: &str) -> Result<PathBuf, FactoryError> {
+        let escape = || {
+            FactoryError::InvalidArgument(format!(
+                "artifact path escapes run directory: {}",
+                relative
+            ))
+        };
+
+        let base = fs::canonicalize(self.artifacts_dir())
+            .map_err(|e| {
+                FactoryError::IoError(format!("artifacts dir for run {}: {}", self.run_id, e))
+            })?;
+        let mut current = base.clone();
+
+        for component in Path::new(relative).components() {
+            match component {
+                Component::CurDir => continue,
+                Component::ParentDir => {
+                    current.pop();
+                }
+                Component::Normal(part) => {
+                    current.push(part);
+                    if fs::symlink_metadata(&current).is_ok() {
+                        current = fs::canonicalize(&current).map_err(|e| {
+                            FactoryError::IoError(format!("resolving artifact {}: {}", relative, e))
+                        })?;
+                    }
+                }
+                Component::RootDir | Component::Prefix(_) => return Err(escape()),
+            }
+            if !current.starts_with(&base) {
+                return Err(escape());
+            }
+        }
+
+        Ok(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn run_dir(root: &Path) -> RunDir {
+        let dir = RunDir::for_read(root, "run-1").unwrap();
+        fs::create_dir_all(dir.artifacts_dir()).unwrap();
+        dir
+    }
+
+    #[test]
+    fn rejects_invalid_run_ids() {
+        let root = tempfile::tempdir().unwrap();
+        for id in ["", ".", "..", "../other", "a/b", "a\\b"] {
+            assert!(RunDir::for_read(root.path(), id).is_err(), "{:?}", id);
+            assert!(RunDir::for_write(root.path(), id).is_err(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn only_the_first_create_claims_a_run() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = RunDir::for_write(&root.path().join("runs"), "run-1").unwrap();
+
+        dir.create().unwrap();
+        assert!(dir.path().is_dir());
+        assert!(matches!(dir.create(), Err(FactoryError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn read_handles_cannot_open_files_for_writing() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = run_dir(root.path());
+        fs::write(dir.path().join(STATE_FILE), "{}").unwrap();
+
+        assert!(matches!(
+            dir.open_for_write(STATE_FILE),
+            Err(FactoryError::InvalidArgument(_))
+        ));
+        assert_eq!(fs::read_to_string(dir.path().join(STATE_FILE)).unwrap(), "{}");
+
+        let mut file = dir.open(STATE_FILE).unwrap();
+        assert!(file.write_all(b"clobbered").is_err());
+    }
+
+    #[test]
+    fn write_handles_can_write() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = RunDir::for_write(root.path(), "run-1").unwrap();
+        fs::create_dir_all(dir.path()).unwrap();
+
+        dir.open_for_write(STATE_FILE).unwrap().write_all(b"{}").unwrap();
+        assert_eq!(dir.read_to_string(STATE_FILE).unwrap(), "{}");
+    }
+
+    #[test]
+    fn resolves_existing_and_missing_paths_inside_artifacts() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = run_dir(root.path());
+        fs::write(dir.artifacts_dir().join("report.txt"), "ok").unwrap();
+        let base = fs::canonicalize(dir.artifacts_dir()).unwrap();
+
+        assert_eq!(
+            dir.safe_artifact_path("report.txt").unwrap(),
+            base.join("report.txt")
+        );
+        assert_eq!(
+            dir.safe_artifact_path("reports/./new/../later.txt").unwrap(),
+            base.join("reports").join("later.txt")
+        );
+    }
+
+    #[test]
+    fn rejects_traversal_and_absolute_paths() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = run_dir(root.path());
+
+        assert!(dir.safe_artifact_path("../run-state.json").is_err());
+        assert!(dir.safe_artifact_path("missing/../../run-state.json").is_err());
+        assert!(dir.safe_artifact_path("/etc/passwd").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlink_escaping_artifacts_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = run_dir(root.path());
+        let outside = root.path().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(&outside, dir.artifacts_dir().join("link")).unwrap();
+
+        assert!(dir.safe_artifact_path("link/secret.txt").is_err());
+        assert!(dir.safe_artifact_path("link/not-yet-written.txt").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn allows_symlink_within_artifacts_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = run_dir(root.path());
+        let target = dir.artifacts_dir().join("builds");
+        fs::create_dir_all(&target).unwrap();
+        std::os::unix::fs::symlink(&target, dir.artifacts_dir().join("latest")).unwrap();
+
+        let resolved = dir.safe_artifact_path("latest/app.apk").unwrap();
+        assert_eq!(resolved, fs::canonicalize(&target).unwrap().join("app.apk"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Session recordings — the factory's stdout captured to a file, so the
+//! UI can later be driven without Node.js (`IpcMode::SimulationMode`).
+//!
+//! A recording is NDJSON, one line per event line the factory printed:
+//! `{"elapsedMs": 1520, "line": "{\"event\":...}"}`, where `elapsedMs`
+//! counts from when the bridge started reading. `line` is kept verbatim so replay goes
+//! through the same event router as a live process.
+
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::FactoryError;
+
+/// Where `record_session` writes recordings, one file per run.
+pub const SESSIONS_DIR: &str = "ops/factory/sessions";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedLine {
+    pub elapsed_ms: u64,
+    pub line: String,
+}
+
+/// Appends a live process's stdout lines to a recording.
+pub struct SessionRecorder {
+    out: BufWriter<File>,
+    started: Instant,
+}
+
+impl SessionRecorder {
+    /// Start a recording for `run_id` under `dir`, named after the run and
+    /// the current time so repeated runs don't overwrite each other.
+    pub fn create(dir: &Path, run_id: &str) -> Result<Self, FactoryError> {
+        let path = dir.join(format!(
+            "{}-{}.ndjson",
+            run_id,
+            chrono::Utc::now().format("%Y%m%dT%H%M%S")
+        ));
+        let io_err = |e: std::io::Error| {
+            FactoryError::IoError(format!("creating {}: {}", path.display(), e))
+        };
+        fs::create_dir_all(dir).map_err(io_err)?;
+        Ok(Self {
+            out: BufWriter::new(File::create(&path).map_err(io_err)?),
+            started: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, line: &str) -> std::io::Result<()> {
+        let recorded = RecordedLine {
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            line: line.to_string(),
+        };
+        serde_json::to_writer(&mut self.out, &recorded)?;
+        self.out.write_all(b"\n")?;
+        // Flushed per line: a recording is most useful when the app or the
+        // factory died mid-run.
+        self.out.flush()
+    }
+}
+
+/// Every line of the recording at `path`, in file order. Lines that don't
+/// parse are skipped, as a truncated last line would be.
+pub fn read_recording(path: &Path) -> Result<Vec<RecordedLine>, FactoryError> {
+    let file = File::open(path)
+        .map_err(|e| FactoryError::IoError(format!("opening {}: {}", path.display(), e)))?;
+    let mut lines = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line =
+            line.map_err(|e| FactoryError::IoError(format!("reading {}: {}", path.display(), e)))?;
+        if let Ok(recorded) = serde_json::from_str::<RecordedLine>(&line) {
+            lines.push(recorded);
+        }
+    }
+    Ok(lines)
+}
+
+/// Write `recording` to `out` one line at a time, sleeping between lines
+/// so they arrive with their recorded spacing divided by `speed`. Returns
+/// early if `out` is closed.
+pub fn replay(recording: &[RecordedLine], speed: f32, mut out: impl Write) {
+    let started = Instant::now();
+    for recorded in recording {
+        let due = Duration::from_secs_f64(recorded.elapsed_ms as f64 / 1000.0 / speed as f64);
+        if let Some(wait) = due.checked_sub(started.elapsed()) {
+            std::thread::sleep(wait);
+        }
+        if writeln!(out, "{}", recorded.line).and_then(|()| out.flush()).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_sessions_replay_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = SessionRecorder::create(dir.path(), "run-1").unwrap();
+        recorder.record(r#"{"event":"factory://run-started"}"#).unwrap();
+        recorder.record(r#"{"event":"factory://run-completed"}"#).unwrap();
+        drop(recorder);
+
+        let path = fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap().path();
+        let recording = read_recording(&path).unwrap();
+        assert_eq!(recording.len(), 2);
+        assert!(recording[0].elapsed_ms <= recording[1].elapsed_ms);
+
+        let mut out = Vec::new();
+        replay(&recording, 1.0, &mut out);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"event\":\"factory://run-started\"}\n{\"event\":\"factory://run-completed\"}\n"
+        );
+    }
+
+    #[test]
+    fn replay_speed_scales_the_recorded_gaps() {
+        let recording = [
+            RecordedLine { elapsed_ms: 0, line: "a".into() },
+            RecordedLine { elapsed_ms: 400, line: "b".into() },
+        ];
+
+        let started = Instant::now();
+        replay(&recording, 4.0, std::io::sink());
+        let took = started.elapsed();
+        assert!(took >= Duration::from_millis(100), "{:?}", took);
+        assert!(took < Duration::from_millis(400), "{:?}", took);
+    }
+}
@@ -0,0 +1,148 @@
+//! Spec migrations — upgrade spec files written against an older
+//! `schema_version`.
+//!
+//! Each `Migration` moves a spec up exactly one version; `migrate` chains
+//! them until the target is reached. Migrations work on the raw YAML
+//! mapping rather than a typed spec, so fields this crate doesn't model
+//! pass through untouched. Comments and key formatting are not preserved.
+
+use serde_yaml::{Mapping, Value};
+
+use crate::error::FactoryError;
+
+const VERSION_KEY: &str = "schema_version";
+
+pub trait Migration: Send + Sync {
+    /// The `schema_version` this migration accepts.
+    fn source_version(&self) -> &'static str;
+    /// The `schema_version` it produces.
+    fn target_version(&self) -> &'static str;
+    /// Rewrite `spec`, describing each change in `changes`.
+    fn apply(&self, spec: Mapping, changes: &mut Vec<String>) -> Mapping;
+}
+
+/// Every known migration, oldest first.
+pub fn migrations() -> Vec<Box<dyn Migration>> {
+    vec![Box::new(DeliveryTargets)]
+}
+
+/// 2025.1 → 2026.1. Specs written before the `delivery` section existed
+/// listed their targets at the top level; 2026.1 also requires `quality`,
+/// which may be empty.
+struct DeliveryTargets;
+
+impl Migration for DeliveryTargets {
+    fn source_version(&self) -> &'static str {
+        "2025.1"
+    }
+
+    fn target_version(&self) -> &'static str {
+        "2026.1"
+    }
+
+    fn apply(&self, mut spec: Mapping, changes: &mut Vec<String>) -> Mapping {
+        if let Some(targets) = spec.remove("targets") {
+            let delivery = spec
+                .entry(Value::from("delivery"))
+                .or_insert(Value::Mapping(Mapping::new()));
+            if let Value::Mapping(delivery) = delivery {
+                if !delivery.contains_key("targets") {
+                    delivery.insert(Value::from("targets"), targets);
+                    changes.push("moved targets to delivery.targets".into());
+                }
+            }
+        }
+        if !spec.contains_key("quality") {
+            spec.insert(Value::from("quality"), Value::Mapping(Mapping::new()));
+            changes.push("added empty quality section".into());
+        }
+        spec
+    }
+}
+
+/// The spec's declared `schema_version`.
+pub fn spec_version(spec: &Mapping) -> Result<String, FactoryError> {
+    spec.get(VERSION_KEY)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| FactoryError::ParseError(format!("spec has no string {}", VERSION_KEY)))
+}
+
+/// Apply migrations in sequence until `spec` is at `target`. Returns the
+/// migrated spec and a description of every change made.
+pub fn migrate(
+    mut spec: Mapping,
+    target: &str,
+    migrations: &[Box<dyn Migration>],
+) -> Result<(Mapping, Vec<String>), FactoryError> {
+    let mut version = spec_version(&spec)?;
+    let mut changes = Vec::new();
+
+    // Each step moves to a version no earlier step produced, so the chain
+    // can be at most as long as the migration list.
+    for _ in 0..=migrations.len() {
+        if version == target {
+            return Ok((spec, changes));
+        }
+        let step = migrations
+            .iter()
+            .find(|m| m.source_version() == version)
+            .ok_or_else(|| {
+                FactoryError::InvalidArgument(format!(
+                    "no migration from schema version {} towards {}",
+                    version, target
+                ))
+            })?;
+        spec = step.apply(spec, &mut changes);
+        spec.insert(Value::from(VERSION_KEY), Value::from(step.target_version()));
+        changes.push(format!("{}: {} -> {}", VERSION_KEY, version, step.target_version()));
+        version = step.target_version().to_string();
+    }
+    Err(FactoryError::InvalidArgument(format!(
+        "migrations from schema version {} never reach {}",
+        spec_version(&spec)?,
+        target
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Mapping {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn old_spec_is_upgraded_to_the_current_version() {
+        let spec = parse("schema_version: '2025.1'\nproduct: { name: x }\ntargets: [web]\n");
+
+        let (migrated, changes) = migrate(spec, "2026.1", &migrations()).unwrap();
+        assert_eq!(spec_version(&migrated).unwrap(), "2026.1");
+        assert_eq!(migrated["delivery"]["targets"][0], "web");
+        assert!(!migrated.contains_key("targets"));
+        assert!(migrated["quality"].is_mapping());
+        assert_eq!(changes.last().unwrap(), "schema_version: 2025.1 -> 2026.1");
+    }
+
+    #[test]
+    fn current_spec_is_left_alone() {
+        let spec = parse("schema_version: '2026.1'\nquality: {}\n");
+        let (migrated, changes) = migrate(spec.clone(), "2026.1", &migrations()).unwrap();
+        assert_eq!(migrated, spec);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        let spec = parse("schema_version: '2024.9'\n");
+        assert!(matches!(
+            migrate(spec, "2026.1", &migrations()),
+            Err(FactoryError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            migrate(parse("product: {}\n"), "2026.1", &migrations()),
+            Err(FactoryError::ParseError(_))
+        ));
+    }
+}
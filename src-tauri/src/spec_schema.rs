@@ -0,0 +1,107 @@
+//! The factory spec's JSON Schema, embedded so the app can validate specs
+//! without shelling out to Node. `build.rs` refuses to build if the file
+//! is not JSON with a `$schema` key; the test below compiles it as a
+//! schema proper.
+
+use crate::error::FactoryError;
+use crate::types::SpecValidationError;
+
+pub const SPEC_SCHEMA: &str = include_str!("../schemas/spec-schema.json");
+
+/// Embedded schemas by `schema_version`, oldest first. The last one is the
+/// current schema.
+const SCHEMAS: &[(&str, &str)] = &[("2026.1", SPEC_SCHEMA)];
+
+/// The schema for `version`, or the current one when `version` is `None`.
+pub fn schema_for(version: Option<&str>) -> Option<&'static str> {
+    match version {
+        Some(v) => SCHEMAS.iter().find(|(known, _)| *known == v).map(|(_, s)| *s),
+        None => SCHEMAS.last().map(|(_, s)| *s),
+    }
+}
+
+/// Every `schema_version` with an embedded schema.
+pub fn versions() -> Vec<&'static str> {
+    SCHEMAS.iter().map(|(v, _)| *v).collect()
+}
+
+/// Check `spec` against the schema for its `schema_version`, or the
+/// current schema if it has none or an unknown one. Each error carries
+/// the JSON Pointer of the offending value.
+pub fn validate(spec: &serde_json::Value) -> Result<Vec<SpecValidationError>, FactoryError> {
+    let schema = schema_for(spec["schema_version"].as_str())
+        .or_else(|| schema_for(None))
+        .ok_or_else(|| FactoryError::ParseError("no spec schema is embedded".into()))?;
+    let schema: serde_json::Value = serde_json::from_str(schema)
+        .map_err(|e| FactoryError::ParseError(format!("embedded spec schema: {}", e)))?;
+    let compiled = jsonschema::JSONSchema::compile(&schema)
+        .map_err(|e| FactoryError::ParseError(format!("embedded spec schema: {}", e)))?;
+
+    let Err(errors) = compiled.validate(spec) else {
+        return Ok(vec![]);
+    };
+    Ok(errors
+        .map(|e| SpecValidationError {
+            message: e.to_string(),
+            json_pointer: Some(e.instance_path.to_string()),
+            line: None,
+            column: None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_schema_compiles() {
+        let schema: serde_json::Value = serde_json::from_str(SPEC_SCHEMA).unwrap();
+        if let Err(e) = jsonschema::JSONSchema::compile(&schema) {
+            panic!("spec schema does not compile: {}", e);
+        }
+    }
+
+    #[test]
+    fn registry_defaults_to_the_current_schema() {
+        assert_eq!(schema_for(None), Some(SPEC_SCHEMA));
+        assert_eq!(schema_for(Some("2026.1")), Some(SPEC_SCHEMA));
+        assert_eq!(schema_for(Some("1999.0")), None);
+    }
+
+    #[test]
+    fn embedded_schema_rejects_a_spec_without_features() {
+        let schema: serde_json::Value = serde_json::from_str(SPEC_SCHEMA).unwrap();
+        let compiled = jsonschema::JSONSchema::compile(&schema).unwrap();
+        let spec = serde_json::json!({
+            "schema_version": "2026.1",
+            "product": {
+                "name": "Demo",
+                "version": "1.0.0",
+                "description": "A demo app",
+                "platforms": ["web"],
+            },
+            "features": [],
+            "journeys": [],
+            "quality": {},
+            "delivery": { "targets": ["web"] },
+        });
+
+        assert!(!compiled.is_valid(&spec));
+    }
+
+    #[test]
+    fn errors_point_at_the_bad_value() {
+        let spec = serde_json::json!({
+            "schema_version": "2026.1",
+            "quality": { "min_coverage": "high" },
+        });
+
+        let errors = validate(&spec).unwrap();
+        let bad = errors
+            .iter()
+            .find(|e| e.json_pointer.as_deref() == Some("/quality/min_coverage"))
+            .expect("no error for the non-numeric threshold");
+        assert!(bad.message.contains("high"));
+    }
+}
@@ -0,0 +1,232 @@
+//! Spec stubs from plan text — the in-process fallback for
+//! `DeriveSpecFromPlan` when no factory process is attached.
+//!
+//! This is keyword matching, not planning: the product name comes from the
+//! first heading, platforms and stages from known words, features from
+//! bullet lines, and quality thresholds from percentages ("coverage at
+//! least 80%" becomes `min_coverage: 80`). The result always passes the
+//! embedded schema but is meant to be edited before a run.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::error::FactoryError;
+
+const SPEC_STUB_TEMPLATE: &str = include_str!("../templates/spec-stub.yaml.j2");
+
+/// `schema_version` written into every stub.
+const STUB_SCHEMA_VERSION: &str = "2026.1";
+
+/// Platform keywords, as written in plans, and the spec platform each names.
+const PLATFORM_KEYWORDS: &[(&str, &str)] = &[
+    ("chrome extension", "chrome_extension"),
+    ("android", "android"),
+    ("desktop", "desktop"),
+    ("unity", "unity"),
+    ("web", "web"),
+];
+
+/// Stage ids from the default pipeline, matched with `_` or a space.
+const STAGE_NAMES: &[&str] = &[
+    "spec_validation",
+    "planning",
+    "building",
+    "assembly",
+    "testing",
+    "ui_inspection",
+    "security_audit",
+    "release_package",
+    "run_audit",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanOutline {
+    pub name: String,
+    pub description: String,
+    pub platforms: Vec<String>,
+    pub features: Vec<PlanFeature>,
+    pub quality: BTreeMap<String, f64>,
+    pub stages: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanFeature {
+    pub id: String,
+    pub description: String,
+}
+
+/// Pull what the stub needs out of free-form plan text.
+pub fn parse_plan(plan_text: &str) -> PlanOutline {
+    let lowered = plan_text.to_lowercase();
+
+    let name = heading_re()
+        .captures(plan_text)
+        .map(|c| c[1].trim().to_string())
+        .unwrap_or_else(|| "Untitled product".to_string());
+    let description = plan_text
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with('#') && !bullet_re().is_match(l))
+        .unwrap_or(&name)
+        .to_string();
+
+    let mut platforms: Vec<String> = PLATFORM_KEYWORDS
+        .iter()
+        .filter(|(keyword, _)| word_re(keyword).is_match(&lowered))
+        .map(|(_, platform)| platform.to_string())
+        .collect();
+    if platforms.is_empty() {
+        platforms.push("web".to_string());
+    }
+
+    let mut features: Vec<PlanFeature> = plan_text
+        .lines()
+        .filter_map(|l| bullet_re().captures(l))
+        .map(|c| c[1].trim().to_string())
+        .filter(|d| !d.is_empty())
+        .enumerate()
+        .map(|(i, description)| PlanFeature {
+            id: format!("feature-{}", i + 1),
+            description,
+        })
+        .collect();
+    if features.is_empty() {
+        features.push(PlanFeature {
+            id: "feature-1".to_string(),
+            description: description.clone(),
+        });
+    }
+
+    let quality = threshold_re()
+        .captures_iter(&lowered)
+        .filter_map(|c| Some((format!("min_{}", &c[1]), c[2].parse().ok()?)))
+        .collect();
+
+    let stages = STAGE_NAMES
+        .iter()
+        .filter(|stage| word_re(&stage.replace('_', "[_ ]")).is_match(&lowered))
+        .map(|stage| stage.to_string())
+        .collect();
+
+    PlanOutline {
+        name,
+        description,
+        platforms,
+        features,
+        quality,
+        stages,
+    }
+}
+
+/// Render a stub spec YAML for `plan_text`.
+pub fn derive_spec(plan_text: &str) -> Result<String, FactoryError> {
+    let outline = parse_plan(plan_text);
+    let mut env = minijinja::Environment::new();
+    env.set_keep_trailing_newline(true);
+    env.add_template("spec-stub", SPEC_STUB_TEMPLATE)
+        .map_err(|e| FactoryError::ParseError(format!("spec stub template: {}", e)))?;
+    let template = env
+        .get_template("spec-stub")
+        .map_err(|e| FactoryError::ParseError(format!("spec stub template: {}", e)))?;
+    template
+        .render(minijinja::context! {
+            schema_version => STUB_SCHEMA_VERSION,
+            name => outline.name,
+            description => outline.description,
+            platforms => outline.platforms,
+            features => outline.features,
+            quality => outline.quality,
+            stages => outline.stages,
+        })
+        .map_err(|e| FactoryError::ParseError(format!("rendering spec stub: {}", e)))
+}
+
+fn heading_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?mi)^\s*(?:#+|product:)\s*(.+)$").unwrap())
+}
+
+fn bullet_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*(?:[-*]|\d+[.)])\s+(.*)$").unwrap())
+}
+
+/// A word followed by a percentage, optionally via "of", "at least",
+/// ">=", "above" or a colon.
+fn threshold_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\b([a-z]+)\s*(?:(?:of|at least|>=|above|:)\s*)?(\d{1,3}(?:\.\d+)?)\s*%")
+            .unwrap()
+    })
+}
+
+fn word_re(pattern: &str) -> Regex {
+    Regex::new(&format!(r"\b{}\b", pattern)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = "\
+# Recipe Box
+
+A web and Android app for saving recipes.
+
+- Save a recipe from a URL
+- Search saved recipes
+1. Share a recipe with a friend
+
+Testing must reach coverage at least 80% and accessibility of 95.5%.
+Skip ui inspection; security audit is required.
+";
+
+    #[test]
+    fn plan_keywords_are_extracted() {
+        let outline = parse_plan(PLAN);
+
+        assert_eq!(outline.name, "Recipe Box");
+        assert_eq!(outline.description, "A web and Android app for saving recipes.");
+        assert_eq!(outline.platforms, ["android", "web"]);
+        assert_eq!(
+            outline.features.iter().map(|f| f.description.as_str()).collect::<Vec<_>>(),
+            [
+                "Save a recipe from a URL",
+                "Search saved recipes",
+                "Share a recipe with a friend"
+            ]
+        );
+        assert_eq!(outline.quality["min_coverage"], 80.0);
+        assert_eq!(outline.quality["min_accessibility"], 95.5);
+        assert_eq!(outline.stages, ["testing", "ui_inspection", "security_audit"]);
+    }
+
+    #[test]
+    fn stub_spec_passes_the_embedded_schema() {
+        let yaml = derive_spec(PLAN).unwrap();
+        let spec: serde_json::Value = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(crate::spec_schema::validate(&spec).unwrap(), vec![]);
+        assert_eq!(spec["product"]["name"], "Recipe Box");
+        assert_eq!(spec["features"][2]["id"], "feature-3");
+        assert_eq!(spec["journeys"][0]["exercisesFeatures"].as_array().unwrap().len(), 3);
+        assert_eq!(spec["quality"]["min_coverage"], 80.0);
+        assert_eq!(spec["delivery"]["targets"], serde_json::json!(["android", "web"]));
+        assert_eq!(spec["extensions"]["plan"]["stages"][0], "testing");
+    }
+
+    #[test]
+    fn bare_plan_still_yields_a_valid_stub() {
+        let yaml = derive_spec("make something people want").unwrap();
+        let spec: serde_json::Value = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(crate::spec_schema::validate(&spec).unwrap(), vec![]);
+        assert_eq!(spec["product"]["platforms"], serde_json::json!(["web"]));
+        assert_eq!(spec["quality"], serde_json::json!({}));
+        assert!(spec.get("extensions").is_none());
+    }
+}
@@ -0,0 +1,314 @@
+//! Test fixtures shared across modules. Compiled for tests only.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::error::FactoryError;
+use crate::evidence_chain::entry_hash;
+use crate::run_dir::{RunDir, EVIDENCE_CHAIN_FILE, MANIFEST_FILE, STATE_FILE};
+use crate::types::{EvidenceEntry, RunPhase};
+
+/// Writes a synthetic evidence chain in the factory's NDJSON format, so
+/// tests don't hand-write lines. Sequence numbers, `prevHash` and `hash`
+/// are filled in the way `EvidenceChain.append` fills them, so the result
+/// also passes the factory's own `verify()`.
+pub struct EvidenceChainWriter {
+    out: BufWriter<File>,
+    next_seq: u64,
+    prev_hash: Option<String>,
+}
+
+impl EvidenceChainWriter {
+    /// Start a new chain at `path`, replacing any file already there.
+    pub fn new(path: &Path) -> Result<Self, FactoryError> {
+        let io_err = |e: std::io::Error| {
+            FactoryError::IoError(format!("creating {}: {}", path.display(), e))
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        Ok(Self {
+            out: BufWriter::new(File::create(path).map_err(io_err)?),
+            next_seq: 0,
+            prev_hash: None,
+        })
+    }
+
+    /// Append `entry`. Its `seq` and `hash` are ignored and assigned here;
+    /// an empty `timestamp` becomes the current time.
+    pub fn append(&mut self, entry: &EvidenceEntry) -> Result<(), FactoryError> {
+        let timestamp = if entry.timestamp.is_empty() {
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+        } else {
+            entry.timestamp.clone()
+        };
+        // serde_json's default map is sorted, which matches the key order
+        // of the factory's stableStringify.
+        let mut line = serde_json::json!({
+            "data": entry.data,
+            "prevHash": self.prev_hash,
+            "seq": self.next_seq,
+            "stage": entry.stage,
+            "timestamp": timestamp,
+            "type": entry.entry_type,
+            "workerId": entry.worker_id,
+        });
+        let hash = entry_hash(&line);
+        line["hash"] = serde_json::Value::String(hash.clone());
+
+        writeln!(self.out, "{}", line)
+            .and_then(|()| self.out.flush())
+            .map_err(|e| FactoryError::IoError(format!("writing evidence entry: {}", e)))?;
+        self.next_seq += 1;
+        self.prev_hash = Some(hash);
+        Ok(())
+    }
+
+    pub fn append_many(&mut self, entries: &[EvidenceEntry]) -> Result<(), FactoryError> {
+        entries.iter().try_for_each(|entry| self.append(entry))
+    }
+}
+
+/// Builds a run directory the way the factory leaves one: `run-state.json`,
+/// an evidence chain, artifacts, and for a shipped or aborted run a
+/// `manifest.json`.
+///
+/// Stages start one second apart from `started_at`, and the last stage
+/// listed is the current one. Evidence entries are spread across the
+/// stages in order.
+pub struct TestRunBuilder {
+    run_id: String,
+    status: String,
+    started_at: chrono::DateTime<chrono::Utc>,
+    stages: Vec<String>,
+    gate_results: Vec<(String, bool)>,
+    evidence_entries: usize,
+    artifacts: Vec<(String, Vec<u8>)>,
+}
+
+impl Default for TestRunBuilder {
+    fn default() -> Self {
+        Self {
+            run_id: "test-run".to_string(),
+            status: "running".to_string(),
+            started_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+            stages: vec!["spec_validation".to_string()],
+            gate_results: Vec::new(),
+            evidence_entries: 0,
+            artifacts: Vec::new(),
+        }
+    }
+}
+
+impl TestRunBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_run_id(mut self, run_id: &str) -> Self {
+        self.run_id = run_id.to_string();
+        self
+    }
+
+    /// Any status string, so tests can also write ones the factory never
+    /// would.
+    pub fn with_status(mut self, status: &str) -> Self {
+        self.status = status.to_string();
+        self
+    }
+
+    pub fn with_started_at(mut self, started_at: &str) -> Self {
+        self.started_at = started_at.parse().expect("RFC 3339 timestamp");
+        self
+    }
+
+    pub fn with_stages(mut self, stages: &[&str]) -> Self {
+        self.stages = stages.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    pub fn with_gate_result(mut self, gate_id: &str, passed: bool) -> Self {
+        self.gate_results.push((gate_id.to_string(), passed));
+        self
+    }
+
+    pub fn with_evidence_entries(mut self, count: usize) -> Self {
+        self.evidence_entries = count;
+        self
+    }
+
+    /// An artifact at `name` under the run's `artifacts/`, recorded in the
+    /// manifest with its SHA-256.
+    pub fn with_artifact(mut self, name: &str, content: impl Into<Vec<u8>>) -> Self {
+        self.artifacts.push((name.to_string(), content.into()));
+        self
+    }
+
+    /// Write the run under `base_dir`, the runs root, and open it for
+    /// reading.
+    pub fn build(self, base_dir: &Path) -> Result<RunDir, FactoryError> {
+        let write_dir = RunDir::for_write(base_dir, &self.run_id)?;
+        let write = |name: &Path, content: &[u8]| {
+            let path = write_dir.path().join(name);
+            let io_err = |e: std::io::Error| {
+                FactoryError::IoError(format!("writing {}: {}", path.display(), e))
+            };
+            fs::create_dir_all(path.parent().unwrap()).map_err(io_err)?;
+            fs::write(&path, content).map_err(io_err)
+        };
+        let at = |offset_secs: usize| {
+            (self.started_at + chrono::Duration::seconds(offset_secs as i64))
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+        };
+
+        let timestamps: serde_json::Map<String, serde_json::Value> = self
+            .stages
+            .iter()
+            .enumerate()
+            .map(|(i, stage)| (stage.clone(), serde_json::json!({ "start": at(i) })))
+            .collect();
+        let gate_results: Vec<serde_json::Value> = self
+            .gate_results
+            .iter()
+            .map(|(gate_id, passed)| {
+                serde_json::json!({
+                    "gateId": gate_id,
+                    "passed": passed,
+                    "timestamp": at(self.stages.len()),
+                    "checks": [],
+                })
+            })
+            .collect();
+        let state = serde_json::json!({
+            "runId": self.run_id,
+            "status": self.status,
+            "currentStage": self.stages.last().map_or("initializing", String::as_str),
+            "gateResults": gate_results,
+            "timestamps": timestamps,
+        });
+        write(Path::new(STATE_FILE), state.to_string().as_bytes())?;
+
+        let mut chain = EvidenceChainWriter::new(&write_dir.path().join(EVIDENCE_CHAIN_FILE))?;
+        for i in 0..self.evidence_entries {
+            let stage = match self.stages.len() {
+                0 => "initializing",
+                n => self.stages[i * n / self.evidence_entries].as_str(),
+            };
+            chain.append(&EvidenceEntry {
+                timestamp: at(i * self.stages.len().max(1) / self.evidence_entries),
+                ..entry("note", "master", stage)
+            })?;
+        }
+
+        let mut artifact_hashes = Vec::new();
+        for (name, content) in &self.artifacts {
+            write(&Path::new("artifacts").join(name), content)?;
+            let digest = Sha256::digest(content);
+            artifact_hashes.push(serde_json::json!({
+                "path": name,
+                "sha256": digest.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+                "platform": "web",
+                "sizeBytes": content.len(),
+            }));
+        }
+
+        if RunPhase::try_from(self.status.as_str()).is_ok_and(RunPhase::is_terminal) {
+            let duration_ms = 1000 * self.stages.len().max(1) as u64;
+            let manifest = serde_json::json!({
+                "runId": self.run_id,
+                "status": self.status,
+                "startedAt": at(0),
+                "completedAt": at(self.stages.len().max(1)),
+                "durationMs": duration_ms,
+                "artifactHashes": artifact_hashes,
+            });
+            write(Path::new(MANIFEST_FILE), manifest.to_string().as_bytes())?;
+        }
+
+        RunDir::for_read(base_dir, &self.run_id)
+    }
+}
+
+/// A minimal entry of `entry_type` from `worker_id`, for `append`.
+pub fn entry(entry_type: &str, worker_id: &str, stage: &str) -> EvidenceEntry {
+    EvidenceEntry {
+        entry_type: entry_type.to_string(),
+        worker_id: worker_id.to_string(),
+        stage: stage.to_string(),
+        data: serde_json::json!({}),
+        ..EvidenceEntry::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writer_links_entries_by_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("evidence-chain.ndjson");
+        let mut writer = EvidenceChainWriter::new(&path).unwrap();
+        writer
+            .append_many(&[
+                entry("run_start", "master", "initializing"),
+                entry("note", "planner", "planning"),
+            ])
+            .unwrap();
+
+        let lines: Vec<serde_json::Value> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["seq"], 1);
+        assert_eq!(lines[0]["prevHash"], serde_json::Value::Null);
+        assert_eq!(lines[1]["prevHash"], lines[0]["hash"]);
+
+        assert!(crate::evidence_chain::verify(&lines).is_ok());
+    }
+
+    #[test]
+    fn built_runs_have_state_chain_artifacts_and_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        let run_dir = TestRunBuilder::new()
+            .with_run_id("run-1")
+            .with_status("shipped")
+            .with_stages(&["spec_validation", "planning"])
+            .with_gate_result("spec_valid", true)
+            .with_evidence_entries(4)
+            .with_artifact("release/app.apk", "apk bytes")
+            .build(root.path())
+            .unwrap();
+
+        let state = run_dir.read_json(STATE_FILE).unwrap();
+        assert_eq!(state["currentStage"], "planning");
+        assert_eq!(state["gateResults"][0]["gateId"], "spec_valid");
+        assert_eq!(state["timestamps"]["planning"]["start"], "2026-01-01T00:00:01.000Z");
+
+        let chain: Vec<serde_json::Value> = run_dir
+            .read_to_string(EVIDENCE_CHAIN_FILE)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(chain.len(), 4);
+        assert_eq!(chain[0]["stage"], "spec_validation");
+        assert_eq!(chain[3]["stage"], "planning");
+        assert!(crate::evidence_chain::verify(&chain).is_ok());
+
+        let manifest = run_dir.read_json(MANIFEST_FILE).unwrap();
+        assert_eq!(manifest["artifactHashes"][0]["path"], "release/app.apk");
+        assert_eq!(
+            fs::read(run_dir.artifacts_dir().join("release/app.apk")).unwrap(),
+            b"apk bytes"
+        );
+
+        let running = TestRunBuilder::new().build(root.path()).unwrap();
+        assert!(!running.exists(MANIFEST_FILE));
+    }
+}
@@ -0,0 +1,285 @@
+//! Mutual TLS for the Unix socket a reconnecting bridge talks to the
+//! factory over, for shared machines where the factory runs as another
+//! user. The factory's certificate must chain to `ca_cert_path` and be
+//! issued for `localhost`; the bridge presents `cert_path`.
+//!
+//! The bridge reads events on a blocking thread, so the TLS session runs
+//! as a task on the async runtime instead, taking commands from a channel.
+//! Decrypted bytes go through a second channel to a thread of their own,
+//! which writes them to the pipe the reader thread reads, so a full pipe
+//! never blocks a runtime worker.
+
+use std::fs::File;
+use std::io::{self, BufReader, PipeReader, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+use crate::config::TlsConfig;
+use crate::error::FactoryError;
+
+/// Name the factory's certificate must be issued for. A socket has no
+/// hostname to check it against.
+pub const SERVER_NAME: &str = "localhost";
+
+/// How long `connect` waits for the handshake to finish.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Client configuration trusting only `tls.ca_cert_path` and presenting
+/// `tls.cert_path`.
+pub fn client_config(tls: &TlsConfig) -> Result<Arc<ClientConfig>, FactoryError> {
+    let mut roots = RootCertStore::empty();
+    for cert in read_certs(&tls.ca_cert_path)? {
+        roots.add(cert).map_err(|e| {
+            let path = tls.ca_cert_path.display();
+            FactoryError::BridgeError(format!("CA certificate {}: {}", path, e))
+        })?;
+    }
+    let config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_client_auth_cert(read_certs(&tls.cert_path)?, read_key(&tls.key_path)?)
+        .map_err(|e| {
+            let path = tls.cert_path.display();
+            FactoryError::BridgeError(format!("client certificate {}: {}", path, e))
+        })?;
+    Ok(Arc::new(config))
+}
+
+/// Run a TLS session over `stream`, returning once the handshake is done.
+/// Closing either end ends the session: the reader then sees end of file
+/// and the writer fails with `BrokenPipe`.
+pub fn connect(
+    stream: std::os::unix::net::UnixStream,
+    tls: &TlsConfig,
+) -> Result<(PipeReader, TlsWriter), FactoryError> {
+    let connector = TlsConnector::from(client_config(tls)?);
+    stream
+        .set_nonblocking(true)
+        .map_err(|e| FactoryError::BridgeError(format!("socket setup error: {}", e)))?;
+    let (reader, mut pipe) = io::pipe()
+        .map_err(|e| FactoryError::BridgeError(format!("TLS pipe error: {}", e)))?;
+    let (commands, mut pending) = mpsc::unbounded_channel::<Vec<u8>>();
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    let (received, to_pipe) = std::sync::mpsc::channel::<Vec<u8>>();
+    std::thread::spawn(move || {
+        for bytes in to_pipe {
+            if pipe.write_all(&bytes).is_err() {
+                break;
+            }
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let handshake = async {
+            let stream = tokio::net::UnixStream::from_std(stream)?;
+            connector.connect(ServerName::try_from(SERVER_NAME).unwrap(), stream).await
+        };
+        let session = match handshake.await {
+            Ok(session) => session,
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+        let _ = ready_tx.send(Ok(()));
+
+        let (mut tls_reader, mut tls_writer) = tokio::io::split(session);
+        let reading = async move {
+            let mut buf = [0u8; 8192];
+            loop {
+                match tls_reader.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if received.send(buf[..n].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        };
+        let writing = async move {
+            while let Some(bytes) = pending.recv().await {
+                if tls_writer.write_all(&bytes).await.is_err()
+                    || tls_writer.flush().await.is_err()
+                {
+                    break;
+                }
+            }
+            let _ = tls_writer.shutdown().await;
+        };
+        tokio::select! {
+            () = reading => {}
+            () = writing => {}
+        }
+    });
+
+    match ready_rx.recv_timeout(HANDSHAKE_TIMEOUT) {
+        Ok(Ok(())) => Ok((reader, TlsWriter(commands))),
+        Ok(Err(e)) => Err(FactoryError::BridgeError(format!("TLS handshake failed: {}", e))),
+        Err(_) => Err(FactoryError::BridgeError("TLS handshake timed out".into())),
+    }
+}
+
+/// The command side of a session from `connect`.
+pub struct TlsWriter(mpsc::UnboundedSender<Vec<u8>>);
+
+impl Write for TlsWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "TLS session closed"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, FactoryError> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(open(path)?))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| FactoryError::ParseError(format!("reading {}: {}", path.display(), e)))?;
+    if certs.is_empty() {
+        return Err(FactoryError::ParseError(format!(
+            "no certificates in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+fn read_key(path: &Path) -> Result<PrivateKeyDer<'static>, FactoryError> {
+    rustls_pemfile::private_key(&mut BufReader::new(open(path)?))
+        .map_err(|e| FactoryError::ParseError(format!("reading {}: {}", path.display(), e)))?
+        .ok_or_else(|| FactoryError::ParseError(format!("no private key in {}", path.display())))
+}
+
+fn open(path: &Path) -> Result<File, FactoryError> {
+    File::open(path)
+        .map_err(|e| FactoryError::IoError(format!("opening {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    use rcgen::{
+        BasicConstraints, Certificate, CertificateParams, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+    };
+    use tokio::io::AsyncBufReadExt;
+    use tokio_rustls::rustls::server::WebPkiClientVerifier;
+    use tokio_rustls::rustls::ServerConfig;
+    use tokio_rustls::TlsAcceptor;
+
+    use super::*;
+
+    struct Authority {
+        cert: Certificate,
+        key: KeyPair,
+    }
+
+    impl Authority {
+        fn new() -> Self {
+            let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let key = KeyPair::generate().unwrap();
+            let cert = params.self_signed(&key).unwrap();
+            Self { cert, key }
+        }
+
+        /// A certificate and key signed by this authority, as PEM.
+        fn issue(&self, name: &str, usage: ExtendedKeyUsagePurpose) -> (String, String) {
+            let mut params = CertificateParams::new(vec![name.to_string()]).unwrap();
+            params.extended_key_usages = vec![usage];
+            let key = KeyPair::generate().unwrap();
+            let cert = params.signed_by(&key, &self.cert, &self.key).unwrap();
+            (cert.pem(), key.serialize_pem())
+        }
+    }
+
+    /// Bridge-side files under `dir`, trusting `trusted` and presenting a
+    /// certificate from `issuer`.
+    fn client_files(dir: &Path, trusted: &Authority, issuer: &Authority) -> TlsConfig {
+        let (cert, key) = issuer.issue("factory-ui", ExtendedKeyUsagePurpose::ClientAuth);
+        let tls = TlsConfig {
+            cert_path: dir.join("client.pem"),
+            key_path: dir.join("client.key"),
+            ca_cert_path: dir.join("ca.pem"),
+        };
+        std::fs::write(&tls.cert_path, cert).unwrap();
+        std::fs::write(&tls.key_path, key).unwrap();
+        std::fs::write(&tls.ca_cert_path, trusted.cert.pem()).unwrap();
+        tls
+    }
+
+    /// A factory stand-in that requires a client certificate from `ca`,
+    /// prefixes every line it reads with `ack:` and sends it back.
+    fn serve_echo(listener: UnixListener, ca: &Authority) {
+        let (cert, key) = ca.issue(SERVER_NAME, ExtendedKeyUsagePurpose::ServerAuth);
+        let mut cert_pem = cert.as_bytes();
+        let mut key_pem = key.as_bytes();
+        let mut roots = RootCertStore::empty();
+        roots.add(ca.cert.der().clone()).unwrap();
+        let verifier = WebPkiClientVerifier::builder(Arc::new(roots)).build().unwrap();
+        let config = ServerConfig::builder()
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(
+                rustls_pemfile::certs(&mut cert_pem).collect::<Result<_, _>>().unwrap(),
+                rustls_pemfile::private_key(&mut key_pem).unwrap().unwrap(),
+            )
+            .unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        listener.set_nonblocking(true).unwrap();
+
+        tauri::async_runtime::spawn(async move {
+            let listener = tokio::net::UnixListener::from_std(listener).unwrap();
+            let (socket, _) = listener.accept().await.unwrap();
+            let Ok(session) = acceptor.accept(socket).await else {
+                return;
+            };
+            let (reader, mut writer) = tokio::io::split(session);
+            let mut lines = tokio::io::BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                writer.write_all(format!("ack:{}\n", line).as_bytes()).await.unwrap();
+                writer.flush().await.unwrap();
+            }
+        });
+    }
+
+    #[test]
+    fn mutual_tls_session_carries_lines_both_ways() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = Authority::new();
+        let tls = client_files(dir.path(), &ca, &ca);
+        let socket = dir.path().join("factory.sock");
+        serve_echo(UnixListener::bind(&socket).unwrap(), &ca);
+
+        let (reader, mut writer) = connect(UnixStream::connect(&socket).unwrap(), &tls).unwrap();
+        writer.write_all(b"{\"type\":\"query\"}\n").unwrap();
+
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).unwrap();
+        assert_eq!(line, "ack:{\"type\":\"query\"}\n");
+    }
+
+    #[test]
+    fn factory_certificate_from_another_authority_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let factory_ca = Authority::new();
+        let other_ca = Authority::new();
+        let tls = client_files(dir.path(), &other_ca, &factory_ca);
+        let socket = dir.path().join("factory.sock");
+        serve_echo(UnixListener::bind(&socket).unwrap(), &factory_ca);
+
+        let result = connect(UnixStream::connect(&socket).unwrap(), &tls);
+        assert!(matches!(result, Err(FactoryError::BridgeError(_))));
+    }
+}
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::error::FactoryError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RunStatus {
     pub run_id: String,
     pub status: String,
@@ -10,12 +14,114 @@ pub struct RunStatus {
     pub duration_ms: u64,
 }
 
+/// The fields of `run-state.json` the app reads. The factory writes many
+/// more (the spec, plan, build output...), which are ignored.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStateFile {
+    pub run_id: String,
+    pub status: String,
+    pub current_stage: String,
+    #[serde(default)]
+    pub gate_results: Vec<StateGateResult>,
+}
+
+/// One entry of `run-state.json`'s `gateResults`. Its `checks` are not
+/// read.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateGateResult {
+    pub gate_id: String,
+    pub passed: bool,
+    #[serde(default)]
+    pub skipped: bool,
+    #[serde(default)]
+    pub stage: Option<String>,
+}
+
+/// `evidence-checkpoint.json`: the chain up to `seq` summarized.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointFile {
+    pub seq: u64,
+    pub created_at: String,
+    pub covers_entries: u64,
+}
+
+/// The fields of `manifest.json` that run history is built from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestFile {
+    pub run_id: String,
+    pub status: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+}
+
+/// `run-inputs.json`: what `start_run` or `clone_run` launched the run
+/// with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunInputs {
+    pub spec_path: String,
+    #[serde(default)]
+    pub blueprint_path: Option<String>,
+}
+
+/// The `status` field of `run-state.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunPhase {
+    Initializing,
+    Running,
+    Paused,
+    Shipped,
+    Aborted,
+}
+
+impl RunPhase {
+    /// Whether the factory writes nothing further to a run in this phase.
+    pub fn is_terminal(self) -> bool {
+        matches!(self, RunPhase::Shipped | RunPhase::Aborted)
+    }
+}
+
+impl TryFrom<&str> for RunPhase {
+    type Error = FactoryError;
+
+    fn try_from(status: &str) -> Result<Self, Self::Error> {
+        match status {
+            "initializing" => Ok(RunPhase::Initializing),
+            "running" => Ok(RunPhase::Running),
+            "paused" => Ok(RunPhase::Paused),
+            "shipped" => Ok(RunPhase::Shipped),
+            "aborted" => Ok(RunPhase::Aborted),
+            other => Err(FactoryError::ParseError(format!("unknown run status {:?}", other))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GateResult {
     pub gate_id: String,
     pub passed: bool,
     pub timestamp: String,
     pub checks: Vec<GateCheck>,
+    /// The stage the gate belongs to, if the factory recorded one.
+    pub stage: Option<String>,
+}
+
+/// Gate counts for one run, for dashboards. `pass_rate` is over the gates
+/// that ran, 0 if none did; `first_failure` is the first failed gate in
+/// `run-state.json` order and `blocking_stage` the stage it belongs to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateSummary {
+    pub total_gates: u32,
+    pub passed: u32,
+    pub failed: u32,
+    pub skipped: u32,
+    pub pass_rate: f32,
+    pub first_failure: Option<String>,
+    pub blocking_stage: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,11 +132,15 @@ pub struct GateCheck {
     pub threshold: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Every field defaults, so a partial or older-format chain line still
+/// deserializes instead of being dropped.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct EvidenceEntry {
     pub seq: u64,
     #[serde(rename = "type")]
     pub entry_type: String,
+    #[serde(alias = "workerId")]
     pub worker_id: String,
     pub stage: String,
     pub timestamp: String,
@@ -38,6 +148,49 @@ pub struct EvidenceEntry {
     pub data: serde_json::Value,
 }
 
+/// An error entry from `get_run_errors`, with its `data.message` pulled
+/// out when it is a string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunErrorEntry {
+    pub entry: EvidenceEntry,
+    pub message: Option<String>,
+}
+
+/// An evidence entry from `search_all_runs_evidence`. `matched_field` is
+/// the path of the first field containing the query, such as `type` or
+/// `data.error`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossRunEvidenceMatch {
+    pub run_id: String,
+    pub entry: EvidenceEntry,
+    pub matched_field: String,
+}
+
+/// One line of a worker's log, as streamed by `stream_worker_log`: the
+/// evidence entries that worker appended, without the hash chain fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageLogEntry {
+    pub seq: u64,
+    pub timestamp: String,
+    pub worker_id: String,
+    pub stage: String,
+    pub entry_type: String,
+    pub data: serde_json::Value,
+}
+
+impl From<EvidenceEntry> for StageLogEntry {
+    fn from(entry: EvidenceEntry) -> Self {
+        Self {
+            seq: entry.seq,
+            timestamp: entry.timestamp,
+            worker_id: entry.worker_id,
+            stage: entry.stage,
+            entry_type: entry.entry_type,
+            data: entry.data,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArtifactInfo {
     pub path: String,
@@ -46,23 +199,771 @@ pub struct ArtifactInfo {
     pub platform: String,
 }
 
+/// An artifact's bytes, for showing small text artifacts inline.
+/// `content_base64` holds at most the requested number of bytes;
+/// `total_bytes` is the file's full size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactContent {
+    pub path: String,
+    pub content_base64: String,
+    pub truncated: bool,
+    pub total_bytes: u64,
+}
+
+/// One artifact compared between two runs. Hashes and sizes come from each
+/// run's manifest and are `None` where the run didn't produce it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactDiffResult {
+    pub artifact_name: String,
+    pub hash_a: Option<String>,
+    pub hash_b: Option<String>,
+    pub size_a: Option<u64>,
+    pub size_b: Option<u64>,
+    pub changed: bool,
+    /// For text artifacts under 100 KB present in both runs.
+    pub line_diff: Option<LineDiffSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineDiffSummary {
+    pub lines_added: u64,
+    pub lines_removed: u64,
+    pub lines_unchanged: u64,
+}
+
+/// The machine a run executed on, from the run's `runner.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunnerInfo {
+    pub hostname: String,
+    pub os: String,
+    pub arch: String,
+    #[serde(alias = "cpuCount")]
+    pub cpu_count: u32,
+    #[serde(alias = "memoryGb")]
+    pub memory_gb: f32,
+    #[serde(alias = "nodeVersion")]
+    pub node_version: String,
+    #[serde(alias = "factoryVersion")]
+    pub factory_version: String,
+}
+
+/// Two runs' runners side by side. Arch and memory are shown but not
+/// compared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunnerComparison {
+    pub hostname_changed: bool,
+    pub os_changed: bool,
+    pub cpu_count_changed: bool,
+    pub node_version_changed: bool,
+    pub factory_version_changed: bool,
+    pub runner_a: RunnerInfo,
+    pub runner_b: RunnerInfo,
+}
+
+/// An artifact and the run that produced it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactLocator {
+    pub run_id: String,
+    pub artifact: ArtifactInfo,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunHistoryEntry {
     pub run_id: String,
     pub status: String,
     pub started_at: String,
     pub duration_ms: u64,
+    pub spec_path: Option<String>,
+    pub blueprint_path: Option<String>,
+}
+
+/// A run found by `search_run_history`. `matched_fields` are the history
+/// entry or manifest fields, in snake_case, that contained the query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunHistoryMatch {
+    pub run_id: String,
+    pub entry: RunHistoryEntry,
+    pub matched_fields: Vec<String>,
+}
+
+impl From<ManifestFile> for RunHistoryEntry {
+    fn from(m: ManifestFile) -> Self {
+        Self {
+            run_id: m.run_id,
+            status: m.status,
+            started_at: m.started_at,
+            duration_ms: m.duration_ms,
+            spec_path: None,
+            blueprint_path: None,
+        }
+    }
+}
+
+impl RunHistoryEntry {
+    /// Fill in the spec and blueprint from the run's `run-inputs.json`, if
+    /// it has one.
+    pub fn with_inputs(self, inputs: Option<RunInputs>) -> Self {
+        match inputs {
+            Some(inputs) => Self {
+                spec_path: Some(inputs.spec_path),
+                blueprint_path: inputs.blueprint_path,
+                ..self
+            },
+            None => self,
+        }
+    }
+}
+
+/// How one run fared at a given gate, for pass-rate trends.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatePassRatePoint {
+    pub run_id: String,
+    pub started_at: String,
+    pub passed: bool,
+    pub checks_passed: u32,
+    pub checks_total: u32,
+}
+
+/// A gate check measurement far from that check's recent history, as
+/// found by `get_run_anomalies`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateAnomaly {
+    pub gate_id: String,
+    pub check_name: String,
+    pub measured: f64,
+    pub historical_mean: f64,
+    pub historical_stddev: f64,
+    pub z_score: f64,
+}
+
+/// A run's verdict at one gate; `gate_result` is `None` when the run
+/// never evaluated that gate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateHistoryEntry {
+    pub run_id: String,
+    pub started_at: String,
+    pub gate_result: Option<GateResult>,
+}
+
+/// A run's health as one number, graded A to F, with the components it
+/// was computed from. Component weights sum to 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunHealthScore {
+    pub score: f32,
+    pub grade: String,
+    pub components: Vec<HealthComponent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthComponent {
+    pub name: String,
+    pub score: f32,
+    pub weight: f32,
+}
+
+/// A milestone on a run's timeline. `event_type` is one of
+/// "run_started", "run_completed", "stage_started", "stage_completed",
+/// "gate_evaluated", "worker_joined" or "worker_left".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub timestamp: String,
+    pub event_type: String,
+    pub description: String,
+    pub metadata: serde_json::Value,
+}
+
+/// A blueprint file's raw YAML, with the result of validating it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlueprintContent {
+    pub path: String,
+    pub content: String,
+    pub size_bytes: u64,
+    pub validation: SpecValidationResult,
+}
+
+/// A value a blueprint is parameterized by, declared under `variables:`
+/// or only referenced as a `{{ name }}` marker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlueprintVariable {
+    pub name: String,
+    pub default_value: Option<serde_json::Value>,
+    pub description: Option<String>,
+    pub required: bool,
+}
+
+/// A run's pipeline as a DAG. `nodes` are stage ids in execution order;
+/// each edge is `(dependency, stage)`. `critical_path` is the longest
+/// chain of stages, first to last.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageDependencyGraph {
+    pub nodes: Vec<String>,
+    pub edges: Vec<(String, String)>,
+    pub critical_path: Vec<String>,
+}
+
+/// One pipeline stage of a run. `status` is one of "pending", "active",
+/// "completed", "failed" or "skipped".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageInfo {
+    pub name: String,
+    pub status: String,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub gates_passed: u32,
+    pub gates_failed: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AggregateProgress {
+    pub total_workers: u32,
+    pub active_workers: u32,
+    pub average_percent: f32,
+    pub slowest_worker: Option<String>,
+    pub fastest_worker: Option<String>,
+    pub stages_active: Vec<String>,
+}
+
+/// Emitted every two seconds per run as `factory://run-progress-summary`.
+/// `elapsed_ms` counts from the run's first event seen by the bridge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunProgressSummary {
+    pub run_id: String,
+    pub percent_complete: f32,
+    pub active_workers: u32,
+    pub stage: String,
+    pub elapsed_ms: u64,
+    pub estimated_remaining_ms: Option<u64>,
+}
+
+/// How many runs exist, in total and per run-state `status`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunCountSummary {
+    pub total: u64,
+    pub by_status: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkerCountInfo {
+    pub total_ever_seen: u32,
+    pub currently_active: u32,
+    pub completed: u32,
+}
+
+/// What one worker did during a run, from its evidence entries.
+/// `completed_at` is set once its last stage interval has ended.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkerTimeline {
+    pub worker_id: String,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub total_duration_ms: u64,
+    pub stages_active: Vec<WorkerStageInterval>,
+    pub gates_evaluated: Vec<String>,
+}
+
+/// A stretch of a worker's entries in one stage. An interval still open
+/// runs to the worker's latest entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkerStageInterval {
+    pub stage: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub duration_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FactoryEvent {
     pub event: String,
+    /// The run this event belongs to, lifted out of `data.runId` so
+    /// routing doesn't have to dig through the payload. The payload keeps
+    /// its own copy for the frontend.
+    #[serde(skip)]
+    pub run_id: Option<String>,
     #[serde(flatten)]
-    pub data: serde_json::Value,
+    pub payload: FactoryEventPayload,
+}
+
+/// Everything on an event's line besides its name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FactoryEventPayload {
+    /// The event's fields, from a line in the format this app reads.
+    Fields(serde_json::Value),
+    /// A line in a format this app doesn't know, kept whole because its
+    /// event name could still be recovered.
+    Unknown(serde_json::Value),
+}
+
+impl FactoryEventPayload {
+    pub fn as_value(&self) -> &serde_json::Value {
+        match self {
+            Self::Fields(value) | Self::Unknown(value) => value,
+        }
+    }
+
+    pub fn into_value(self) -> serde_json::Value {
+        match self {
+            Self::Fields(value) | Self::Unknown(value) => value,
+        }
+    }
+
+    fn as_value_mut(&mut self) -> &mut serde_json::Value {
+        match self {
+            Self::Fields(value) | Self::Unknown(value) => value,
+        }
+    }
+}
+
+impl FactoryEvent {
+    /// Parse one NDJSON line from the factory's stdout.
+    ///
+    /// The payload is untyped, so a new event type never fails to parse. A
+    /// future format may carry the name differently, though: as a number,
+    /// or as `{"event": {"name": ...}}`. Any line with a recoverable name
+    /// is still an event, with the whole line as an `Unknown` payload; only
+    /// lines with no name at all are errors.
+    pub fn from_line(line: &str) -> Result<Self, serde_json::Error> {
+        let mut event: Self = match serde_json::from_str(line) {
+            Ok(event) => event,
+            Err(e) => {
+                let raw: serde_json::Value = serde_json::from_str(line)?;
+                let event = fallback_event_name(&raw["event"]).ok_or(e)?;
+                Self {
+                    event,
+                    run_id: None,
+                    payload: FactoryEventPayload::Unknown(raw),
+                }
+            }
+        };
+        event.run_id = event.payload.as_value()["runId"].as_str().map(String::from);
+        Ok(event)
+    }
+
+    /// Attribute the event to `run_id` unless the factory already named a
+    /// run, keeping `data.runId` in step with the field.
+    pub fn tag_run(&mut self, run_id: &str) {
+        if self.run_id.is_some() {
+            return;
+        }
+        if let serde_json::Value::Object(fields) = self.payload.as_value_mut() {
+            fields.insert("runId".into(), serde_json::Value::String(run_id.to_string()));
+        }
+        self.run_id = Some(run_id.to_string());
+    }
+}
+
+fn fallback_event_name(event: &serde_json::Value) -> Option<String> {
+    match event {
+        serde_json::Value::String(name) => Some(name.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Object(fields) => fields
+            .get("name")
+            .or_else(|| fields.get("type"))
+            .and_then(serde_json::Value::as_str)
+            .map(String::from),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpecValidationResult {
     pub valid: bool,
     pub errors: Vec<String>,
+    /// Deprecated or missing-but-recommended fields. A spec with only
+    /// warnings is still valid.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// `errors` again, with where each one is, for inline editor markers.
+    #[serde(default)]
+    pub error_details: Vec<SpecValidationError>,
+}
+
+/// A spec error located by JSON Pointer into the parsed spec (such as
+/// `/features/0/id`) or, for YAML syntax errors, by line and column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpecValidationError {
+    pub message: String,
+    pub json_pointer: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationResult {
+    pub original_version: String,
+    pub target_version: String,
+    pub changes_made: Vec<String>,
+    pub output_path: String,
+}
+
+/// How a run's spec file differs now from the snapshot taken when the run
+/// started. `diff` holds the differing lines in order, prefixed with `-`
+/// (only in the snapshot) or `+` (only in the current file).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecDiff {
+    pub run_id: String,
+    pub spec_path: String,
+    pub changed: bool,
+    pub diff: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunResult {
+    pub spec_valid: bool,
+    pub blueprint_valid: bool,
+    pub spec_errors: Vec<String>,
+    pub blueprint_errors: Vec<String>,
+    pub would_start: bool,
+}
+
+/// `start_run` answers with `Started(run_id)` for real runs, which
+/// serializes as the bare run ID string, and with a structured report for
+/// dry runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StartRunOutcome {
+    Started(String),
+    DryRun(DryRunResult),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub imported_keys: u64,
+    pub skipped_keys: u64,
+    pub domains_touched: Vec<String>,
+}
+
+/// A known evidence entry type, for documentation in the UI. See
+/// `evidence_types::KNOWN_EVIDENCE_TYPES`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceTypeDescriptor {
+    pub entry_type: String,
+    pub description: String,
+    pub is_audit_relevant: bool,
+    pub expected_data_fields: Vec<String>,
+}
+
+/// The evidence type registry next to the types one run used.
+/// `unknown_types` are those the run wrote that the registry doesn't know;
+/// both lists are empty when no run was given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceTypeReport {
+    pub registered_types: Vec<EvidenceTypeDescriptor>,
+    pub observed_types: Vec<String>,
+    pub unknown_types: Vec<String>,
+}
+
+/// A row of the event store. `seq` is the event's own sequence number,
+/// where it carried one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredEvent {
+    pub id: i64,
+    pub run_id: String,
+    pub event_type: String,
+    pub seq: Option<i64>,
+    pub payload: serde_json::Value,
+    pub received_at: String,
+}
+
+/// What `reindex_runs` got through. `errors` names each run and what
+/// didn't check out in it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReindexReport {
+    pub runs_processed: u64,
+    pub evidence_files_indexed: u64,
+    pub artifacts_hashed: u64,
+    pub errors: Vec<String>,
+}
+
+/// What `validate_run_directory` found in one run directory. `missing_files`
+/// lists the expected entries that aren't there (`artifacts/` for the
+/// directory); `unexpected_files` those the factory never writes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunDirectoryValidation {
+    pub run_id: String,
+    pub has_manifest: bool,
+    pub has_state_file: bool,
+    pub has_evidence_chain: bool,
+    pub has_artifacts_dir: bool,
+    pub is_complete: bool,
+    pub missing_files: Vec<String>,
+    pub unexpected_files: Vec<String>,
+}
+
+/// The entries `repair_run_directory` created, in the order it created them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepairReport {
+    pub run_id: String,
+    pub created: Vec<String>,
+}
+
+/// A run's evidence checkpoint. `is_current` is whether it covers the
+/// chain through its last entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointInfo {
+    pub seq: u64,
+    pub created_at: String,
+    pub covers_entries: u64,
+    pub file_size_bytes: u64,
+    pub is_current: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionReport {
+    pub input_files: u32,
+    pub total_entries: u64,
+    pub duplicates_removed: u64,
+    pub output_size_bytes: u64,
+}
+
+/// All of consequence memory at one moment, e.g. as a backup before a
+/// purge. `domains` maps each domain to its records keyed by ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsequenceMemorySnapshot {
+    pub domains: HashMap<String, serde_json::Value>,
+    pub total_keys: u64,
+    pub size_bytes: u64,
+    pub snapshot_at: String,
+}
+
+/// How much of the consequence memory file each domain takes up. Domains
+/// are largest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsequenceMemorySize {
+    pub total_size_bytes: u64,
+    pub domains: Vec<DomainSize>,
+    pub largest_domain: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainSize {
+    pub domain: String,
+    pub size_bytes: u64,
+    pub key_count: u64,
+}
+
+/// A consequence memory record a run wrote, with the record it replaced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsequenceMemoryChange {
+    pub domain: String,
+    pub key: String,
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: serde_json::Value,
+    /// The new record's `createdAt`.
+    pub written_at: String,
+}
+
+/// How long ago one consequence memory domain last changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsequenceMemoryAgeEntry {
+    pub domain: String,
+    /// RFC 3339.
+    pub last_modified: String,
+    pub age_days: f32,
+}
+
+/// What `apply_consequence_memory_expiration` found and removed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpirationReport {
+    pub domains_checked: u32,
+    pub domains_expired: u32,
+    pub keys_removed: u64,
+}
+
+/// What purging a consequence memory domain removed, or with
+/// `deleted: false`, would remove.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeReport {
+    pub domain: String,
+    pub keys_that_would_be_deleted: u64,
+    pub size_bytes: u64,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifExportResult {
+    pub path: String,
+    pub results_count: u32,
+    pub run_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HtmlExportResult {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunArchiveResult {
+    pub path: String,
+    pub run_id: String,
+    pub size_bytes: u64,
+}
+
+/// Summary counters for one evidence chain. Timestamps are those of the
+/// first and last lines in file order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceStats {
+    pub total_entries: u64,
+    pub entries_by_type: HashMap<String, u64>,
+    pub entries_by_worker: HashMap<String, u64>,
+    pub min_seq: u64,
+    pub max_seq: u64,
+    pub first_timestamp: String,
+    pub last_timestamp: String,
+    pub chain_size_bytes: u64,
+}
+
+/// The entry types one run's evidence chain uses, sorted, with how many
+/// entries of each it holds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunEvidenceTypes {
+    pub types: Vec<String>,
+    pub counts: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceCountResult {
+    pub total: u64,
+    pub max_seq: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeStateInfo {
+    pub running: bool,
+    pub active_runs: Vec<String>,
+    pub commands_sent: u64,
+    /// Duplicate evidence entries the event router did not forward.
+    pub duplicates_dropped: u64,
+    /// Bytes written to and read from factory processes, counted per
+    /// NDJSON line including its newline.
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// From spawning the latest factory process to its ready event; `None`
+    /// while it is still starting or if nothing was spawned.
+    pub spawn_duration_ms: Option<u64>,
+    /// Panic message of a stdout reader thread that crashed, after which
+    /// its process's events no longer arrive; `None` again once a new
+    /// reader starts. Also in `ops/factory/.bridge-crash.log`.
+    pub reader_crash: Option<String>,
+}
+
+/// The latest `factory://error` event any factory process sent, kept by
+/// the bridge for a frontend that reconnects after missing it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryErrorEvent {
+    pub message: String,
+    pub occurred_at: String,
+    pub run_id: Option<String>,
+    pub command_type: Option<String>,
+}
+
+impl FactoryErrorEvent {
+    /// From an error event's payload. Without a `timestamp`, the time it
+    /// was received stands in.
+    pub fn from_payload(data: &serde_json::Value) -> Self {
+        let text = |key: &str| data[key].as_str().map(String::from);
+        Self {
+            message: text("message").unwrap_or_default(),
+            occurred_at: text("timestamp").unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+            run_id: text("runId"),
+            command_type: text("commandType"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorruptedRunInfo {
+    pub run_id: String,
+    pub path: String,
+    pub error: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_phases_parse_from_state_statuses() {
+        assert_eq!(RunPhase::try_from("paused").unwrap(), RunPhase::Paused);
+        assert!(RunPhase::try_from("shipped").unwrap().is_terminal());
+        assert!(!RunPhase::try_from("initializing").unwrap().is_terminal());
+        assert!(RunPhase::try_from("Running").is_err());
+    }
+
+    #[test]
+    fn evidence_entry_with_only_seq_uses_defaults() {
+        let entry: EvidenceEntry = serde_json::from_str(r#"{"seq": 7}"#).unwrap();
+        assert_eq!(entry.seq, 7);
+        assert_eq!(entry.entry_type, "");
+        assert_eq!(entry.worker_id, "");
+        assert_eq!(entry.stage, "");
+        assert_eq!(entry.timestamp, "");
+        assert_eq!(entry.hash, "");
+        assert!(entry.data.is_null());
+    }
+
+    #[test]
+    fn evidence_entry_accepts_camel_case_worker_id() {
+        let entry: EvidenceEntry =
+            serde_json::from_str(r#"{"seq": 1, "type": "note", "workerId": "planner"}"#).unwrap();
+        assert_eq!(entry.entry_type, "note");
+        assert_eq!(entry.worker_id, "planner");
+    }
+
+    #[test]
+    fn factory_event_lifts_run_id_from_payload() {
+        let line = r#"{"event": "stage-changed", "runId": "abc123", "stage": "building"}"#;
+        let mut event = FactoryEvent::from_line(line).unwrap();
+        assert_eq!(event.run_id.as_deref(), Some("abc123"));
+        assert!(matches!(event.payload, FactoryEventPayload::Fields(_)));
+
+        event.tag_run("other");
+        assert_eq!(event.run_id.as_deref(), Some("abc123"));
+        assert_eq!(event.payload.as_value()["runId"], "abc123");
+    }
+
+    #[test]
+    fn factory_event_without_run_id_takes_the_process_tag() {
+        let line = r#"{"event": "error", "message": "boom"}"#;
+        let mut event = FactoryEvent::from_line(line).unwrap();
+        assert_eq!(event.run_id, None);
+
+        event.tag_run("run-1");
+        assert_eq!(event.run_id.as_deref(), Some("run-1"));
+        assert_eq!(event.payload.as_value()["runId"], "run-1");
+        assert_eq!(event.payload.as_value()["message"], "boom");
+    }
+
+    #[test]
+    fn factory_event_name_is_recovered_from_unknown_formats() {
+        let line = r#"{"event": {"name": "stage-retried", "version": 2}, "runId": "r1"}"#;
+        let event = FactoryEvent::from_line(line).unwrap();
+        assert_eq!(event.event, "stage-retried");
+        assert_eq!(event.run_id.as_deref(), Some("r1"));
+        assert!(matches!(event.payload, FactoryEventPayload::Unknown(_)));
+        assert_eq!(event.payload.as_value()["event"]["version"], 2);
+
+        assert!(FactoryEvent::from_line(r#"{"seq": 1}"#).is_err());
+        assert!(FactoryEvent::from_line("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn factory_error_events_read_the_payload_fields() {
+        let event = FactoryErrorEvent::from_payload(&serde_json::json!({
+            "message": "gate crashed",
+            "timestamp": "2026-03-01T12:00:00Z",
+            "runId": "run-1",
+            "commandType": "start_run",
+        }));
+        assert_eq!(event.message, "gate crashed");
+        assert_eq!(event.occurred_at, "2026-03-01T12:00:00Z");
+        assert_eq!(event.run_id.as_deref(), Some("run-1"));
+        assert_eq!(event.command_type.as_deref(), Some("start_run"));
+
+        let bare = FactoryErrorEvent::from_payload(&serde_json::json!({ "message": "x" }));
+        assert!(!bare.occurred_at.is_empty());
+        assert_eq!((bare.run_id, bare.command_type), (None, None));
+    }
 }
@@ -1,5 +1,11 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { RunStatus, GateResult, RunHistoryEntry, SpecValidationResult } from "../lib/commands";
+import type {
+  RunStatus,
+  GateResult,
+  RunHistoryEntry,
+  SpecValidationResult,
+  EvidenceCountResult,
+} from "../lib/commands";
 
 export function useFactoryCommand() {
   return {
@@ -12,8 +18,8 @@ export function useFactoryCommand() {
     getRunStatus: (runId: string) =>
       invoke<RunStatus>("get_run_status", { runId }),
 
-    getRunHistory: () =>
-      invoke<RunHistoryEntry[]>("get_run_history"),
+    getRunHistory: (includeActive?: boolean) =>
+      invoke<RunHistoryEntry[]>("get_run_history", { includeActive }),
 
     getGateResults: (runId: string) =>
       invoke<GateResult[]>("get_gate_results", { runId }),
@@ -21,6 +27,12 @@ export function useFactoryCommand() {
     getEvidenceRange: (runId: string, from: number, to: number) =>
       invoke<unknown[]>("get_evidence_range", { runId, from, to }),
 
+    getEvidenceFrom: (runId: string, from: number, limit?: number) =>
+      invoke<unknown[]>("get_evidence_from", { runId, from, limit }),
+
+    getEvidenceCount: (runId: string) =>
+      invoke<EvidenceCountResult>("get_evidence_count", { runId }),
+
     validateSpec: (specPath: string) =>
       invoke<SpecValidationResult>("validate_spec", { specPath }),
   };
@@ -6,15 +6,67 @@
 export type FactoryCommand =
   | { type: "GetRunStatus" }
   | { type: "GetGateResults" }
+  | { type: "GetGateSummary"; params: { runId: string } }
+  | { type: "GetStageGates"; params: { runId: string; stage: string } }
+  | { type: "GetGateStages"; params: { runId: string } }
   | { type: "GetEvidenceEntry"; params: { seq: number } }
   | { type: "GetEvidenceRange"; params: { from: number; to: number } }
+  | { type: "GetEvidenceByHash"; params: { runId: string; hash: string } }
+  | { type: "ListRunEvidenceTypes"; params: { runId: string } }
+  | { type: "GetEvidenceTypes"; params: { runId?: string } }
+  | { type: "GetCheckpoint"; params: { runId: string } }
   | { type: "GetArtifact"; params: { path: string } }
+  | { type: "GetArtifactContent"; params: { runId: string; path: string; maxBytes: number } }
+  | { type: "GetArtifactDiff"; params: { runIdA: string; runIdB: string; artifactName: string } }
+  | { type: "GetLatestArtifact"; params: { pattern: string } }
   | { type: "GetConsequenceMemory"; params: { domain?: string } }
-  | { type: "GetRunHistory" }
+  | { type: "GetConsequenceMemoryKeys"; params: { domain: string } }
+  | { type: "GetConsequenceMemorySnapshot" }
+  | { type: "GetConsequenceMemorySize" }
+  | { type: "GetConsequenceMemoryAge" }
+  | { type: "GetConsequenceMemoryChanges"; params: { runId: string } }
+  | { type: "GetLastError" }
+  | { type: "GetRunHistory"; params: { includeActive: boolean } }
+  | { type: "SearchRunHistory"; params: { query: string; fields?: string[]; maxResults?: number } }
+  | { type: "GetLatestRun" }
+  | { type: "GetCurrentRunId" }
+  | { type: "GetRunCount" }
+  | { type: "ListRunStages"; params: { runId: string } }
+  | { type: "GetWorkerCount"; params: { runId: string } }
+  | { type: "GetWorkerTimeline"; params: { runId: string; workerId: string } }
+  | { type: "GetRunErrors"; params: { runId: string } }
+  | { type: "GetRunLogs"; params: { runId: string; level?: LogLevel; limit?: number } }
+  | { type: "GetGateHistory"; params: { gateId: string; lastNRuns: number } }
+  | { type: "GetRunAnomalies"; params: { runId: string; zScoreThreshold?: number } }
+  | { type: "GetStageDependencies"; params: { runId: string } }
+  | { type: "GetRunEnvironment"; params: { runId: string } }
+  | { type: "GetRunnerInfo"; params: { runId: string } }
+  | { type: "GetRunnerComparison"; params: { runIdA: string; runIdB: string } }
   | { type: "StartRun"; params: { specPath: string; blueprintPath?: string } }
   | { type: "AbortRun"; params: { runId: string } }
+  | { type: "CloneRun"; params: { sourceRunId: string; overrides?: unknown } }
+  | { type: "ReindexRuns" }
+  | { type: "CompactEvidenceChain"; params: { runId: string } }
+  | { type: "RepairRunDirectory"; params: { runId: string } }
+  | { type: "ExportRun"; params: { runId: string; outputPath: string } }
+  | { type: "ExportEvidenceSarif"; params: { runId: string; outputPath: string } }
+  | { type: "ExportRunHtml"; params: { runId: string; outputPath: string } }
+  | { type: "UpdateSuppressedEvents"; params: { events: string[] } }
   | { type: "ValidateSpec"; params: { specPath: string } }
-  | { type: "DeriveSpecFromPlan"; params: { planText: string } };
+  | { type: "ValidateBlueprint"; params: { blueprintPath: string } }
+  | { type: "GetBlueprintContent"; params: { blueprintPath: string } }
+  | { type: "GetBlueprintVariables"; params: { blueprintPath: string } }
+  | { type: "GetSpec"; params: { runId: string } }
+  | { type: "GetBlueprintSpec"; params: { specPath: string; blueprintPath: string } }
+  | { type: "GetSpecSchema"; params: { version?: string } }
+  | { type: "DeriveSpecFromPlan"; params: { planText: string } }
+  | { type: "MigrateSpec"; params: { specPath: string; targetVersion: string; outputPath?: string } }
+  | { type: "PurgeConsequenceMemory"; params: { domain: string; confirm: boolean } }
+  | { type: "ApplyConsequenceMemoryExpiration" }
+  | { type: "ImportConsequenceMemory"; params: { sourcePath: string; mergeStrategy: string } }
+  | { type: "SetConsequenceMemoryTtl"; params: { domain: string; ttlDays: number } };
+
+export type LogLevel = "error" | "warn" | "info" | "debug";
 
 export interface RunStatus {
   runId: string;
@@ -30,6 +82,7 @@ export interface GateResult {
   passed: boolean;
   timestamp: string;
   checks: Array<{ name: string; passed: boolean; measured: number; threshold: number }>;
+  stage?: string;
 }
 
 export interface RunHistoryEntry {
@@ -39,7 +92,21 @@ export interface RunHistoryEntry {
   durationMs: number;
 }
 
+export interface EvidenceCountResult {
+  total: number;
+  max_seq: number;
+}
+
+export interface SpecValidationError {
+  message: string;
+  json_pointer: string | null;
+  line: number | null;
+  column: number | null;
+}
+
 export interface SpecValidationResult {
   valid: boolean;
   errors: string[];
+  warnings?: string[];
+  error_details?: SpecValidationError[];
 }
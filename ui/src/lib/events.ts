@@ -21,6 +21,15 @@ export interface WorkerProgressEvent {
   timestamp: string;
 }
 
+export interface RunProgressSummaryEvent {
+  run_id: string;
+  percent_complete: number;
+  active_workers: number;
+  stage: string;
+  elapsed_ms: number;
+  estimated_remaining_ms: number | null;
+}
+
 export interface GateResultEvent {
   gateId: string;
   passed: boolean;
@@ -59,13 +68,32 @@ export interface FactoryErrorEvent {
   timestamp: string;
 }
 
+export interface BridgeStartedEvent {
+  runId: string;
+  spawnDurationMs: number;
+}
+
+export interface BridgeErrorEvent {
+  runId: string;
+  message: string;
+  lines: string[];
+}
+
+export interface BridgeReconnectedEvent {
+  pid: number;
+}
+
 export type FactoryEventMap = {
   "factory://run-started": RunStartedEvent;
   "factory://stage-changed": StageChangedEvent;
   "factory://worker-progress": WorkerProgressEvent;
+  "factory://run-progress-summary": RunProgressSummaryEvent;
   "factory://gate-result": GateResultEvent;
   "factory://evidence-entry": EvidenceEntryEvent;
   "factory://artifact-produced": ArtifactProducedEvent;
   "factory://run-completed": RunCompletedEvent;
   "factory://error": FactoryErrorEvent;
+  "factory://bridge-started": BridgeStartedEvent;
+  "factory://bridge-error": BridgeErrorEvent;
+  "factory://bridge-reconnected": BridgeReconnectedEvent;
 };